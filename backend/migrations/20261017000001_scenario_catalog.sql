@@ -0,0 +1,42 @@
+-- Built-in scenario catalog (replaces the hardcoded default_scenarios() list).
+-- Rows are seeded at startup from the Rust defaults with ON CONFLICT DO NOTHING,
+-- so admin edits are never overwritten by a deploy.
+CREATE TABLE IF NOT EXISTS scenarios (
+    id TEXT PRIMARY KEY,
+    version INTEGER NOT NULL DEFAULT 1 CHECK (version > 0),
+    title TEXT NOT NULL,
+    description TEXT NOT NULL,
+    scenario_type TEXT NOT NULL CHECK (scenario_type IN (
+        'soft-skills', 'test-cases', 'requirement-definition', 'incident-response', 'business-execution'
+    )),
+    feature_mockup JSONB,
+    scenario_guide TEXT,
+    kickoff_prompt TEXT NOT NULL,
+    evaluation_criteria JSONB NOT NULL,
+    passing_score REAL,
+    missions JSONB,
+    agent_prompt TEXT,
+    single_response BOOLEAN,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    updated_by_user_id TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_scenarios_active_sort
+    ON scenarios(sort_order, id) WHERE is_active;
+
+CREATE TRIGGER update_scenarios_updated_at
+    BEFORE UPDATE ON scenarios
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Immutable snapshot of every catalog revision (full Scenario JSON).
+CREATE TABLE IF NOT EXISTS scenario_versions (
+    scenario_id TEXT NOT NULL REFERENCES scenarios(id) ON DELETE CASCADE,
+    version INTEGER NOT NULL,
+    definition JSONB NOT NULL,
+    created_by_user_id TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (scenario_id, version)
+);
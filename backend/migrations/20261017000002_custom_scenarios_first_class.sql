@@ -0,0 +1,21 @@
+-- Custom scenarios carry the same content as catalog scenarios so sessions,
+-- chat and evaluation can run on them.
+ALTER TABLE custom_scenarios ALTER COLUMN product DROP NOT NULL;
+ALTER TABLE custom_scenarios ADD COLUMN IF NOT EXISTS scenario_guide TEXT;
+ALTER TABLE custom_scenarios ADD COLUMN IF NOT EXISTS agent_prompt TEXT;
+ALTER TABLE custom_scenarios ADD COLUMN IF NOT EXISTS single_response BOOLEAN;
+
+-- Align scenario_type with ScenarioType (kebab-case) used by the catalog.
+ALTER TABLE custom_scenarios DROP CONSTRAINT IF EXISTS custom_scenarios_scenario_type_check;
+UPDATE custom_scenarios
+SET scenario_type = CASE scenario_type
+    WHEN 'basic' THEN 'soft-skills'
+    WHEN 'test-case' THEN 'test-cases'
+    ELSE scenario_type
+END
+WHERE scenario_type IN ('basic', 'test-case');
+ALTER TABLE custom_scenarios ADD CONSTRAINT custom_scenarios_scenario_type_check CHECK (
+    scenario_type IN (
+        'soft-skills', 'test-cases', 'requirement-definition', 'incident-response', 'business-execution'
+    )
+);
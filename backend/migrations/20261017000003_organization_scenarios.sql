@@ -0,0 +1,12 @@
+-- Organization-scoped scenarios live alongside personal custom scenarios.
+-- Rows with organization_id set are managed by org owners/admins and only
+-- reach members once published.
+ALTER TABLE custom_scenarios
+    ADD COLUMN IF NOT EXISTS organization_id TEXT REFERENCES organizations(id) ON DELETE CASCADE;
+ALTER TABLE custom_scenarios
+    ADD COLUMN IF NOT EXISTS status TEXT NOT NULL DEFAULT 'published'
+    CHECK (status IN ('draft', 'published', 'retired'));
+ALTER TABLE custom_scenarios ADD COLUMN IF NOT EXISTS published_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_custom_scenarios_org_status
+    ON custom_scenarios(organization_id, status) WHERE organization_id IS NOT NULL;
@@ -0,0 +1,14 @@
+-- Sessions keep every evaluation attempt. One attempt per session is marked
+-- official and feeds organization progress.
+ALTER TABLE evaluations DROP CONSTRAINT IF EXISTS evaluations_session_id_key;
+ALTER TABLE evaluations ADD COLUMN IF NOT EXISTS attempt INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE evaluations ADD COLUMN IF NOT EXISTS model_id TEXT;
+ALTER TABLE evaluations ADD COLUMN IF NOT EXISTS is_official BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Existing rows are the single evaluation each session had so far.
+UPDATE evaluations SET is_official = TRUE;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_evaluations_session_attempt
+    ON evaluations(session_id, attempt);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_evaluations_session_official
+    ON evaluations(session_id) WHERE is_official;
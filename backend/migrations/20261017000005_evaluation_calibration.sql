@@ -0,0 +1,3 @@
+-- Calibrated evaluations aggregate several model samples. Per-category
+-- variance lives in the categories JSON; the sample count is kept per attempt.
+ALTER TABLE evaluations ADD COLUMN IF NOT EXISTS sample_count INTEGER NOT NULL DEFAULT 1;
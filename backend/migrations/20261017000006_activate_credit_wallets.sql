@@ -0,0 +1,17 @@
+-- Wallets were backfilled with an allowance while credits were disabled, so
+-- their balances have no ledger history. Record that balance as an
+-- `opening_balance` credit so the ledger explains every wallet.
+INSERT INTO credit_ledger (id, wallet_id, direction, amount, reason, reference_type, reference_id)
+SELECT
+    'ledger-opening-' || w.id,
+    w.id,
+    'credit',
+    w.monthly_credits + w.purchased_credits,
+    'opening_balance',
+    'wallet',
+    w.id
+FROM credit_wallets w
+WHERE w.monthly_credits + w.purchased_credits > 0
+  AND NOT EXISTS (
+      SELECT 1 FROM credit_ledger l WHERE l.wallet_id = w.id
+  );
@@ -0,0 +1,4 @@
+-- One monthly_reset ledger entry per wallet and reset period, so a reset
+-- that runs twice cannot grant the allowance twice.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_credit_ledger_monthly_reset
+    ON credit_ledger(wallet_id, reference_id) WHERE reason = 'monthly_reset';
@@ -0,0 +1,4 @@
+-- A checkout session credits its wallet once, however often Stripe delivers
+-- the completion event.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_credit_ledger_purchase
+    ON credit_ledger(wallet_id, reference_type, reference_id) WHERE reason = 'purchase';
@@ -0,0 +1,27 @@
+-- Record which user caused each ledger entry so usage can be broken down by
+-- member, and backfill it for entries whose reference still resolves.
+ALTER TABLE credit_ledger ADD COLUMN IF NOT EXISTS actor_user_id TEXT;
+
+UPDATE credit_ledger l
+SET actor_user_id = s.user_id
+FROM messages m
+JOIN sessions s ON s.id = m.session_id
+WHERE l.actor_user_id IS NULL
+  AND l.reference_type = 'message'
+  AND l.reference_id = m.id;
+
+UPDATE credit_ledger l
+SET actor_user_id = s.user_id
+FROM evaluations e
+JOIN sessions s ON s.id = e.session_id
+WHERE l.actor_user_id IS NULL
+  AND l.reference_type = 'evaluation'
+  AND l.reference_id = e.id::TEXT;
+
+UPDATE credit_ledger
+SET actor_user_id = metadata->>'purchasedBy'
+WHERE actor_user_id IS NULL
+  AND reason = 'purchase';
+
+CREATE INDEX IF NOT EXISTS idx_credit_ledger_wallet_occurred
+    ON credit_ledger(wallet_id, occurred_at DESC);
@@ -0,0 +1,25 @@
+-- When a subscription entered past_due; the dunning grace window runs from here.
+ALTER TABLE subscriptions ADD COLUMN IF NOT EXISTS past_due_since TIMESTAMPTZ;
+
+-- Subscription-backed entitlements expire on their valid_until without a
+-- webhook, so the lapse sweep looks them up by expiry.
+CREATE INDEX IF NOT EXISTS idx_entitlements_subscription_expiry
+    ON entitlements(valid_until)
+    WHERE status = 'active' AND source_subscription_id IS NOT NULL AND valid_until IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS billing_notifications (
+    id TEXT PRIMARY KEY,
+    organization_id TEXT NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    entitlement_id TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('team_plan_lapsed')),
+    delivery_status TEXT NOT NULL CHECK (delivery_status IN ('pending', 'sent', 'skipped', 'failed')),
+    delivery_message TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Each owner hears about a given lapse once, whether the webhook or the
+-- sweep notices it first.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_billing_notifications_once
+    ON billing_notifications(entitlement_id, user_id, kind);
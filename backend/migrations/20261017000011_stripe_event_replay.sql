@@ -0,0 +1,5 @@
+-- Failed or unprocessed webhook events can be replayed by an operator;
+-- count the attempts and tell hand-imported debug events apart.
+ALTER TABLE stripe_events ADD COLUMN IF NOT EXISTS replay_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE stripe_events ADD COLUMN IF NOT EXISTS source TEXT NOT NULL DEFAULT 'webhook'
+    CHECK (source IN ('webhook', 'import'));
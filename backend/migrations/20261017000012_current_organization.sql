@@ -0,0 +1,5 @@
+-- The organization a member of several organizations has switched to. It
+-- scopes /organizations/current/*, new sessions and plan resolution; NULL
+-- falls back to the most recently joined organization.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS current_organization_id TEXT
+    REFERENCES organizations(id) ON DELETE SET NULL;
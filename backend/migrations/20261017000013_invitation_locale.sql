@@ -0,0 +1,2 @@
+-- Language of the invitation email, kept so a resend matches the original.
+ALTER TABLE organization_invitations ADD COLUMN IF NOT EXISTS locale TEXT NOT NULL DEFAULT 'ja';
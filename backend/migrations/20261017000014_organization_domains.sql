@@ -0,0 +1,40 @@
+-- Email domains an organization has proven it controls. Users whose email is
+-- on a verified domain are offered to join, or joined automatically with
+-- `default_role` when `join_policy` is 'auto'.
+CREATE TABLE IF NOT EXISTS organization_domains (
+    id TEXT PRIMARY KEY,
+    organization_id TEXT NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    domain TEXT NOT NULL,
+    join_policy TEXT NOT NULL CHECK (join_policy IN ('offer', 'auto')),
+    default_role TEXT NOT NULL CHECK (default_role IN ('member', 'reviewer')),
+    verification_token TEXT NOT NULL,
+    verified_at TIMESTAMPTZ,
+    created_by_user_id TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_organization_domains_org_domain
+    ON organization_domains(organization_id, domain);
+
+-- Only one organization can hold a verified claim on a domain.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_organization_domains_verified
+    ON organization_domains(domain)
+    WHERE verified_at IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS organization_audit_events (
+    id TEXT PRIMARY KEY,
+    organization_id TEXT NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    actor_user_id TEXT,
+    action TEXT NOT NULL,
+    subject_user_id TEXT,
+    detail JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_organization_audit_events_org_created
+    ON organization_audit_events(organization_id, created_at DESC);
+
+-- Domain joins happen once per user and organization; this lookup runs on
+-- every authenticated request for users on a verified domain.
+CREATE INDEX IF NOT EXISTS idx_organization_audit_events_subject
+    ON organization_audit_events(organization_id, subject_user_id, action);
@@ -0,0 +1,17 @@
+-- Ownership hand-overs started by the owner. Roles and
+-- `organizations.created_by_user_id` only change once the target member
+-- accepts; at most one transfer per organization is pending at a time.
+CREATE TABLE IF NOT EXISTS organization_ownership_transfers (
+    id TEXT PRIMARY KEY,
+    organization_id TEXT NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    from_user_id TEXT NOT NULL,
+    to_user_id TEXT NOT NULL,
+    status TEXT NOT NULL CHECK (status IN ('pending', 'accepted', 'declined', 'canceled', 'expired')) DEFAULT 'pending',
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_org_ownership_transfers_pending
+    ON organization_ownership_transfers(organization_id)
+    WHERE status = 'pending';
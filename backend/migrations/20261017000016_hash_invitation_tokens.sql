@@ -0,0 +1,4 @@
+-- Invitations stored the raw accept token in `invite_token_hash`. Replace it
+-- with its SHA-256 hex digest; the service hashes incoming tokens the same way.
+UPDATE organization_invitations
+SET invite_token_hash = encode(sha256(convert_to(invite_token_hash, 'UTF8')), 'hex');
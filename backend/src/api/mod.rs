@@ -6,42 +6,74 @@ use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
 use crate::features::billing::handlers::{
-    __path_checkout_team, __path_create_portal_session, __path_stripe_webhook, checkout_team,
-    create_portal_session, stripe_webhook,
+    __path_checkout_credits, __path_checkout_team, __path_create_portal_session,
+    __path_list_invoices, __path_stripe_webhook, __path_update_team_seats, checkout_credits,
+    checkout_team, create_portal_session, list_invoices, stripe_webhook, update_team_seats,
 };
 use crate::features::billing::models::{
-    BillingPortalSessionResponse, CreateBillingPortalSessionRequest, CreateTeamCheckoutRequest,
-    StripeWebhookResponse, TeamCheckoutResponse,
+    BillingPortalSessionResponse, CreateBillingPortalSessionRequest, CreateCreditCheckoutRequest,
+    CreateTeamCheckoutRequest, CreditCheckoutResponse, Invoice, InvoiceListResponse,
+    StripeWebhookResponse, TeamCheckoutResponse, TeamSeatsResponse, UpdateTeamSeatsRequest,
 };
 use crate::features::comments::handlers::{
     __path_create_comment, __path_list_comments, create_comment, list_comments,
 };
-use crate::features::credits::handlers::{__path_get_my_credits, get_my_credits};
-use crate::features::credits::models::CreditBalanceResponse;
-use crate::features::entitlements::handlers::{__path_get_my_entitlements, get_my_entitlements};
-use crate::features::entitlements::models::{EntitlementResponse, PlanCode};
-use crate::features::evaluations::handlers::{__path_evaluate_session, evaluate_session};
+use crate::features::credits::handlers::{
+    __path_get_my_credit_ledger, __path_get_my_credit_usage, __path_get_my_credits,
+    __path_get_organization_credit_ledger, __path_get_organization_credit_usage,
+    get_my_credit_ledger, get_my_credit_usage, get_my_credits, get_organization_credit_ledger,
+    get_organization_credit_usage,
+};
+use crate::features::credits::models::{
+    CreditBalanceResponse, CreditLedgerEntry, CreditLedgerPage, CreditMemberUsage,
+    CreditUsageBreakdown, CreditUsageBucket,
+};
+use crate::features::entitlements::handlers::{
+    __path_get_my_entitlements, __path_get_my_usage, get_my_entitlements, get_my_usage,
+};
+use crate::features::entitlements::models::{
+    DailyUsageQuota, EntitlementResponse, PlanCode, UsageResponse,
+};
+use crate::features::evaluations::handlers::{
+    __path_evaluate_session, __path_list_session_evaluations, __path_mark_official_evaluation,
+    evaluate_session, list_session_evaluations, mark_official_evaluation,
+};
 use crate::features::evaluations::models::{
-    EvaluationCriterion, EvaluationRequest, ScoringGuidelines,
+    EvaluationAttempt, EvaluationCriterion, EvaluationRequest, ScoringGuidelines,
 };
 use crate::features::health::handlers::{__path_health, health};
 use crate::features::imports::handlers::{__path_import_sessions, import_sessions};
 use crate::features::messages::handlers::{
-    __path_list_messages, __path_post_message, list_messages, post_message,
+    __path_list_messages, __path_post_message, __path_stream_message, list_messages, post_message,
+    stream_message,
 };
 use crate::features::organizations::handlers::{
-    __path_accept_invitation, __path_create_invitation, __path_create_organization,
+    __path_accept_invitation, __path_accept_organization_offer, __path_accept_ownership_transfer,
+    __path_cancel_ownership_transfer, __path_create_domain, __path_create_invitation,
+    __path_create_organization, __path_create_ownership_transfer, __path_delete_domain,
     __path_delete_member, __path_get_current_organization, __path_get_current_progress,
-    __path_list_current_members, __path_list_member_completed_sessions,
-    __path_update_current_organization, __path_update_member, accept_invitation, create_invitation,
-    create_organization, delete_member, get_current_organization, get_current_progress,
-    list_current_members, list_member_completed_sessions, update_current_organization,
-    update_member,
+    __path_get_ownership_transfer, __path_list_audit_events, __path_list_current_members,
+    __path_list_domains, __path_list_invitations, __path_list_member_completed_sessions,
+    __path_list_my_organizations, __path_list_organization_offers, __path_resend_invitation,
+    __path_revoke_invitation, __path_switch_current_organization,
+    __path_update_current_organization, __path_update_member, __path_verify_domain,
+    accept_invitation, accept_organization_offer, accept_ownership_transfer,
+    cancel_ownership_transfer, create_domain, create_invitation, create_organization,
+    create_ownership_transfer, delete_domain, delete_member, get_current_organization,
+    get_current_progress, get_ownership_transfer, list_audit_events, list_current_members,
+    list_domains, list_invitations, list_member_completed_sessions, list_my_organizations,
+    list_organization_offers, resend_invitation, revoke_invitation, switch_current_organization,
+    update_current_organization, update_member, verify_domain,
 };
 use crate::features::organizations::models::{
-    CreateInvitationRequest, CreateOrganizationRequest, CurrentOrganizationResponse,
-    InvitationEmailDelivery, InvitationResponse, Organization, OrganizationMember,
-    OrganizationMemberProgress, OrganizationMembersResponse, OrganizationProgressResponse,
+    CreateInvitationRequest, CreateOrganizationDomainRequest, CreateOrganizationRequest,
+    CreateOwnershipTransferRequest, CurrentOrganizationResponse, InvitationEmailDelivery,
+    InvitationResponse, MyOrganization, MyOrganizationsResponse, Organization,
+    OrganizationAuditEvent, OrganizationAuditEventsResponse, OrganizationDomain,
+    OrganizationDomainsResponse, OrganizationInvitation, OrganizationInvitationsResponse,
+    OrganizationMember, OrganizationMemberProgress, OrganizationMembersResponse, OrganizationOffer,
+    OrganizationOffersResponse, OrganizationProgressResponse, OwnershipTransfer,
+    OwnershipTransferAcceptResponse, OwnershipTransferResponse, SwitchOrganizationRequest,
     UpdateMemberRequest, UpdateOrganizationRequest,
 };
 use crate::features::outputs::handlers::{
@@ -55,8 +87,21 @@ use crate::features::product_config::handlers::{
 };
 use crate::features::product_config::models::{ProductConfig, UpdateProductConfigRequest};
 use crate::features::scenarios::handlers::{
-    __path_create_scenario, __path_get_scenario, __path_list_scenarios, create_scenario,
-    get_scenario, list_scenarios,
+    __path_create_catalog_scenario, __path_create_organization_scenario, __path_create_scenario,
+    __path_deactivate_catalog_scenario, __path_export_scenarios, __path_get_scenario,
+    __path_import_scenarios, __path_import_scenarios_csv, __path_list_catalog_scenario_versions,
+    __path_list_catalog_scenarios, __path_list_organization_scenarios, __path_list_scenarios,
+    __path_publish_organization_scenario, __path_retire_organization_scenario,
+    __path_update_catalog_scenario, __path_update_organization_scenario, create_catalog_scenario,
+    create_organization_scenario, create_scenario, deactivate_catalog_scenario, export_scenarios,
+    get_scenario, import_scenarios, import_scenarios_csv, list_catalog_scenario_versions,
+    list_catalog_scenarios, list_organization_scenarios, list_scenarios,
+    publish_organization_scenario, retire_organization_scenario, update_catalog_scenario,
+    update_organization_scenario,
+};
+use crate::features::scenarios::models::{
+    CatalogScenario, OrganizationScenario, ScenarioBundle, ScenarioImportReport,
+    ScenarioImportResult, ScenarioVersion, UpsertCatalogScenarioRequest,
 };
 use crate::features::sessions::handlers::{
     __path_create_session, __path_delete_session, __path_get_session, __path_list_sessions,
@@ -87,34 +132,74 @@ pub const OPENAPI_SPEC_PATH: &str = "../specs/001-pm-simulation-web/contracts/op
         list_scenarios,
         get_scenario,
         create_scenario,
+        export_scenarios,
+        import_scenarios,
+        import_scenarios_csv,
+        list_catalog_scenarios,
+        create_catalog_scenario,
+        update_catalog_scenario,
+        deactivate_catalog_scenario,
+        list_catalog_scenario_versions,
+        list_organization_scenarios,
+        create_organization_scenario,
+        update_organization_scenario,
+        publish_organization_scenario,
+        retire_organization_scenario,
         health,
         create_session,
         list_sessions,
         get_session,
         delete_session,
         post_message,
+        stream_message,
         list_messages,
         evaluate_session,
+        list_session_evaluations,
+        mark_official_evaluation,
         list_comments,
         create_comment,
         list_outputs,
         create_output,
         delete_output,
         create_organization,
+        list_my_organizations,
+        switch_current_organization,
+        list_organization_offers,
+        accept_organization_offer,
         get_current_organization,
         update_current_organization,
         list_current_members,
         get_current_progress,
         list_member_completed_sessions,
+        list_invitations,
         create_invitation,
+        resend_invitation,
+        revoke_invitation,
         accept_invitation,
         update_member,
         delete_member,
+        get_ownership_transfer,
+        create_ownership_transfer,
+        accept_ownership_transfer,
+        cancel_ownership_transfer,
+        list_domains,
+        create_domain,
+        verify_domain,
+        delete_domain,
+        list_audit_events,
         get_my_account,
         delete_my_account,
         get_my_entitlements,
+        get_my_usage,
         get_my_credits,
+        get_my_credit_ledger,
+        get_my_credit_usage,
+        get_organization_credit_ledger,
+        get_organization_credit_usage,
         checkout_team,
+        checkout_credits,
+        update_team_seats,
+        list_invoices,
         create_portal_session,
         stripe_webhook,
         import_sessions,
@@ -128,6 +213,13 @@ pub const OPENAPI_SPEC_PATH: &str = "../specs/001-pm-simulation-web/contracts/op
     components(schemas(
         Scenario,
         ScenarioType,
+        CatalogScenario,
+        OrganizationScenario,
+        ScenarioBundle,
+        ScenarioImportResult,
+        ScenarioImportReport,
+        UpsertCatalogScenarioRequest,
+        ScenarioVersion,
         crate::features::health::handlers::HealthResponse,
         FeatureMockup,
         Session,
@@ -137,6 +229,7 @@ pub const OPENAPI_SPEC_PATH: &str = "../specs/001-pm-simulation-web/contracts/op
         MessageRole,
         MessageTag,
         Evaluation,
+        EvaluationAttempt,
         EvaluationRequest,
         EvaluationCriterion,
         ScoringGuidelines,
@@ -151,11 +244,27 @@ pub const OPENAPI_SPEC_PATH: &str = "../specs/001-pm-simulation-web/contracts/op
         Organization,
         OrganizationMember,
         CurrentOrganizationResponse,
+        MyOrganization,
+        MyOrganizationsResponse,
+        SwitchOrganizationRequest,
         OrganizationMembersResponse,
         OrganizationProgressResponse,
         OrganizationMemberProgress,
         InvitationEmailDelivery,
         InvitationResponse,
+        OrganizationInvitation,
+        OrganizationInvitationsResponse,
+        OrganizationDomain,
+        OrganizationDomainsResponse,
+        CreateOrganizationDomainRequest,
+        OrganizationOffer,
+        OrganizationOffersResponse,
+        OrganizationAuditEvent,
+        OrganizationAuditEventsResponse,
+        OwnershipTransfer,
+        OwnershipTransferResponse,
+        OwnershipTransferAcceptResponse,
+        CreateOwnershipTransferRequest,
         CreateOrganizationRequest,
         UpdateOrganizationRequest,
         CreateInvitationRequest,
@@ -168,12 +277,25 @@ pub const OPENAPI_SPEC_PATH: &str = "../specs/001-pm-simulation-web/contracts/op
         UpdateProductConfigRequest,
         MyAccountResponse,
         EntitlementResponse,
+        UsageResponse,
+        DailyUsageQuota,
         PlanCode,
         CreditBalanceResponse,
+        CreditLedgerEntry,
+        CreditLedgerPage,
+        CreditUsageBucket,
+        CreditMemberUsage,
+        CreditUsageBreakdown,
         CreateBillingPortalSessionRequest,
         BillingPortalSessionResponse,
         CreateTeamCheckoutRequest,
         TeamCheckoutResponse,
+        CreateCreditCheckoutRequest,
+        CreditCheckoutResponse,
+        UpdateTeamSeatsRequest,
+        TeamSeatsResponse,
+        Invoice,
+        InvoiceListResponse,
         StripeWebhookResponse
     ))
 )]
@@ -184,20 +306,57 @@ pub fn router_with_state(state: SharedState) -> Router {
         .route("/health", get(health))
         .route("/scenarios", get(list_scenarios))
         .route("/scenarios", post(create_scenario))
+        .route("/scenarios/export", get(export_scenarios))
+        .route("/scenarios/import", post(import_scenarios))
+        .route("/scenarios/import/csv", post(import_scenarios_csv))
         .route("/scenarios/:id", get(get_scenario))
+        .route(
+            "/admin/scenarios",
+            get(list_catalog_scenarios).post(create_catalog_scenario),
+        )
+        .route(
+            "/admin/scenarios/:id",
+            axum::routing::put(update_catalog_scenario).delete(deactivate_catalog_scenario),
+        )
+        .route(
+            "/admin/scenarios/:id/versions",
+            get(list_catalog_scenario_versions),
+        )
         .route("/sessions", post(create_session).get(list_sessions))
         .route("/sessions/:id", get(get_session).delete(delete_session))
         .route("/me", get(get_my_account).delete(delete_my_account))
         .route("/me/entitlements", get(get_my_entitlements))
+        .route("/me/usage", get(get_my_usage))
+        .route("/me/organizations", get(list_my_organizations))
+        .route(
+            "/me/organizations/current",
+            axum::routing::put(switch_current_organization),
+        )
+        .route("/me/organization-offers", get(list_organization_offers))
+        .route(
+            "/me/organization-offers/:organizationId/accept",
+            post(accept_organization_offer),
+        )
         .route("/me/credits", get(get_my_credits))
+        .route("/me/credits/ledger", get(get_my_credit_ledger))
+        .route("/me/credits/usage", get(get_my_credit_usage))
         .route("/billing/checkout/team", post(checkout_team))
+        .route("/billing/checkout/credits", post(checkout_credits))
+        .route("/billing/seats", post(update_team_seats))
+        .route("/billing/invoices", get(list_invoices))
         .route("/billing/portal/session", post(create_portal_session))
         .route("/billing/webhook/stripe", post(stripe_webhook))
         .route(
             "/sessions/:id/messages",
             get(list_messages).post(post_message),
         )
+        .route("/sessions/:id/messages/stream", post(stream_message))
         .route("/sessions/:id/evaluate", post(evaluate_session))
+        .route("/sessions/:id/evaluations", get(list_session_evaluations))
+        .route(
+            "/sessions/:id/evaluations/:attempt/official",
+            post(mark_official_evaluation),
+        )
         .route(
             "/sessions/:id/comments",
             get(list_comments).post(create_comment),
@@ -217,13 +376,29 @@ pub fn router_with_state(state: SharedState) -> Router {
         )
         .route("/organizations/current/members", get(list_current_members))
         .route("/organizations/current/progress", get(get_current_progress))
+        .route(
+            "/organizations/current/credits/ledger",
+            get(get_organization_credit_ledger),
+        )
+        .route(
+            "/organizations/current/credits/usage",
+            get(get_organization_credit_usage),
+        )
         .route(
             "/organizations/current/members/:memberId/sessions/completed",
             get(list_member_completed_sessions),
         )
         .route(
             "/organizations/current/invitations",
-            post(create_invitation),
+            get(list_invitations).post(create_invitation),
+        )
+        .route(
+            "/organizations/current/invitations/:invitationId/resend",
+            post(resend_invitation),
+        )
+        .route(
+            "/organizations/current/invitations/:invitationId/revoke",
+            post(revoke_invitation),
         )
         .route(
             "/organizations/current/invitations/:token/accept",
@@ -233,6 +408,48 @@ pub fn router_with_state(state: SharedState) -> Router {
             "/organizations/current/members/:memberId",
             axum::routing::patch(update_member).delete(delete_member),
         )
+        .route(
+            "/organizations/current/transfer-ownership",
+            get(get_ownership_transfer)
+                .post(create_ownership_transfer)
+                .delete(cancel_ownership_transfer),
+        )
+        .route(
+            "/organizations/current/transfer-ownership/accept",
+            post(accept_ownership_transfer),
+        )
+        .route(
+            "/organizations/current/domains",
+            get(list_domains).post(create_domain),
+        )
+        .route(
+            "/organizations/current/domains/:domainId",
+            axum::routing::delete(delete_domain),
+        )
+        .route(
+            "/organizations/current/domains/:domainId/verify",
+            post(verify_domain),
+        )
+        .route(
+            "/organizations/current/audit-events",
+            get(list_audit_events),
+        )
+        .route(
+            "/organizations/current/scenarios",
+            get(list_organization_scenarios).post(create_organization_scenario),
+        )
+        .route(
+            "/organizations/current/scenarios/:id",
+            axum::routing::put(update_organization_scenario),
+        )
+        .route(
+            "/organizations/current/scenarios/:id/publish",
+            post(publish_organization_scenario),
+        )
+        .route(
+            "/organizations/current/scenarios/:id/retire",
+            post(retire_organization_scenario),
+        )
         .route(
             "/sessions/:id/test-cases",
             get(list_test_cases).post(create_test_case),
@@ -18,6 +18,12 @@ impl AppError {
     }
 }
 
+impl Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
 impl<E> From<E> for AppError
 where
     E: Into<anyhow::Error>,
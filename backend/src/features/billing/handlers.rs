@@ -1,12 +1,18 @@
-use axum::{body::Bytes, extract::State, http::HeaderMap, Json};
+use axum::{
+    body::Bytes,
+    extract::{Query, State},
+    http::HeaderMap,
+    Json,
+};
 
 use crate::error::AppError;
 use crate::middleware::auth::AuthUser;
 use crate::state::SharedState;
 
 use super::models::{
-    BillingPortalSessionResponse, CreateBillingPortalSessionRequest, CreateTeamCheckoutRequest,
-    StripeWebhookResponse, TeamCheckoutResponse,
+    BillingPortalSessionResponse, CreateBillingPortalSessionRequest, CreateCreditCheckoutRequest,
+    CreateTeamCheckoutRequest, CreditCheckoutResponse, InvoiceListResponse, InvoiceQuery,
+    StripeWebhookResponse, TeamCheckoutResponse, TeamSeatsResponse, UpdateTeamSeatsRequest,
 };
 
 #[utoipa::path(
@@ -28,6 +34,66 @@ pub async fn checkout_team(
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/billing/checkout/credits",
+    request_body = CreateCreditCheckoutRequest,
+    responses((status = 200, body = CreditCheckoutResponse))
+)]
+pub async fn checkout_credits(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Json(body): Json<CreateCreditCheckoutRequest>,
+) -> Result<Json<CreditCheckoutResponse>, AppError> {
+    let response = state
+        .services()
+        .billing()
+        .create_credit_checkout(&auth.user_id, body)
+        .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/billing/seats",
+    request_body = UpdateTeamSeatsRequest,
+    responses((status = 200, body = TeamSeatsResponse))
+)]
+pub async fn update_team_seats(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Json(body): Json<UpdateTeamSeatsRequest>,
+) -> Result<Json<TeamSeatsResponse>, AppError> {
+    let response = state
+        .services()
+        .billing()
+        .update_team_seats(&auth.user_id, body)
+        .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    get,
+    path = "/billing/invoices",
+    params(
+        ("organizationId" = Option<String>, Query, description = "List the organization's invoices instead of the caller's"),
+        ("limit" = Option<i64>, Query, description = "Number of invoices (default 20, max 100)")
+    ),
+    responses((status = 200, body = InvoiceListResponse))
+)]
+pub async fn list_invoices(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Query(query): Query<InvoiceQuery>,
+) -> Result<Json<InvoiceListResponse>, AppError> {
+    let response = state
+        .services()
+        .billing()
+        .list_invoices(&auth.user_id, &query)
+        .await?;
+    Ok(Json(response))
+}
+
 #[utoipa::path(
     post,
     path = "/billing/portal/session",
@@ -0,0 +1,136 @@
+use sqlx::{PgPool, Row};
+
+use crate::error::{anyhow_error, AppError};
+use crate::shared::email::{self, EmailLocale, EmailTemplate};
+use crate::shared::helpers::next_id;
+
+use super::services::default_portal_return_url;
+
+const TEAM_PLAN_LAPSED: &str = "team_plan_lapsed";
+
+/// Expire subscription-backed entitlements whose `valid_until` has passed
+/// (a cancellation reached its period end, or a past_due grace window ran
+/// out) and tell the owners of affected organizations. Returns how many
+/// entitlements expired.
+pub async fn run_lapse_sweep(pool: &PgPool) -> Result<usize, AppError> {
+    let rows = sqlx::query(
+        r#"
+        UPDATE entitlements
+        SET status = 'expired'
+        WHERE status = 'active'
+          AND source_subscription_id IS NOT NULL
+          AND valid_until IS NOT NULL
+          AND valid_until <= NOW()
+        RETURNING id, scope_type, scope_id
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow_error(format!("Failed to expire lapsed entitlements: {e}")))?;
+
+    for row in &rows {
+        let scope_type: String = row.try_get("scope_type").unwrap_or_default();
+        if scope_type != "organization" {
+            continue;
+        }
+        let entitlement_id: String = row.try_get("id").unwrap_or_default();
+        let organization_id: String = row.try_get("scope_id").unwrap_or_default();
+        notify_team_plan_lapsed(pool, &organization_id, &entitlement_id).await?;
+    }
+
+    Ok(rows.len())
+}
+
+/// Record a lapse notification for each active owner of the organization and
+/// email them. Members need no action: they resolve to Free once the
+/// organization entitlement is gone, and their sessions are kept.
+pub(crate) async fn notify_team_plan_lapsed(
+    pool: &PgPool,
+    organization_id: &str,
+    entitlement_id: &str,
+) -> Result<(), AppError> {
+    let owners = sqlx::query(
+        r#"
+        SELECT m.user_id, u.email, o.name AS organization_name
+        FROM organization_members m
+        INNER JOIN organizations o ON o.id = m.organization_id
+        LEFT JOIN users u ON u.id = m.user_id
+        WHERE m.organization_id = $1
+          AND m.role = 'owner'
+          AND m.status = 'active'
+        "#,
+    )
+    .bind(organization_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow_error(format!("Failed to list organization owners: {e}")))?;
+
+    for owner in owners {
+        let user_id: String = owner.try_get("user_id").unwrap_or_default();
+        let email: Option<String> = owner.try_get("email").ok().flatten();
+        let organization_name: String = owner.try_get("organization_name").unwrap_or_default();
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO billing_notifications (
+                id, organization_id, user_id, entitlement_id, kind, delivery_status
+            )
+            VALUES ($1, $2, $3, $4, $5, 'pending')
+            ON CONFLICT (entitlement_id, user_id, kind) DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(next_id("billing_notification"))
+        .bind(organization_id)
+        .bind(&user_id)
+        .bind(entitlement_id)
+        .bind(TEAM_PLAN_LAPSED)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| anyhow_error(format!("Failed to record lapse notification: {e}")))?;
+        let Some(inserted) = inserted else {
+            continue;
+        };
+        let notification_id: String = inserted.try_get("id").unwrap_or_default();
+
+        let (status, message) = match email.as_deref() {
+            Some(email) => send_team_plan_lapsed_email(email, &organization_name).await,
+            None => (
+                "skipped",
+                Some("BILLING_EMAIL_SKIPPED: owner has no email".to_string()),
+            ),
+        };
+        sqlx::query(
+            r#"
+            UPDATE billing_notifications
+            SET delivery_status = $2, delivery_message = $3, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(&notification_id)
+        .bind(status)
+        .bind(message)
+        .execute(pool)
+        .await
+        .map_err(|e| anyhow_error(format!("Failed to update lapse notification: {e}")))?;
+    }
+
+    Ok(())
+}
+
+async fn send_team_plan_lapsed_email(
+    to_email: &str,
+    organization_name: &str,
+) -> (&'static str, Option<String>) {
+    let billing_link = default_portal_return_url();
+    let rendered = email::render(
+        EmailTemplate::TeamPlanLapsed,
+        EmailLocale::default_from_env(),
+        &[
+            ("organization_name", organization_name),
+            ("billing_link", &billing_link),
+        ],
+    );
+    let delivery = email::send("BILLING", to_email, &rendered).await;
+    (delivery.status, delivery.message)
+}
@@ -1,3 +1,4 @@
 pub mod handlers;
+pub mod lifecycle;
 pub mod models;
 pub mod services;
@@ -19,6 +19,45 @@ pub struct TeamCheckoutResponse {
     pub message: Option<String>,
 }
 
+/// Change the seat count of an organization's running Team subscription.
+/// Stripe prorates the difference; the new count applies once the
+/// subscription webhook confirms it.
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateTeamSeatsRequest {
+    pub organization_id: String,
+    pub seat_quantity: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamSeatsResponse {
+    pub mode: String,
+    pub previous_seat_quantity: i32,
+    pub seat_quantity: i32,
+    pub message: Option<String>,
+}
+
+/// One-time purchase of credit packs. Without `organizationId` the credits
+/// go to the caller's personal wallet.
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCreditCheckoutRequest {
+    pub pack_quantity: i32,
+    pub organization_id: Option<String>,
+    pub success_url: Option<String>,
+    pub cancel_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreditCheckoutResponse {
+    pub mode: String,
+    pub checkout_url: Option<String>,
+    pub credits: i32,
+    pub message: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateBillingPortalSessionRequest {
@@ -38,3 +77,61 @@ pub struct StripeWebhookResponse {
     pub duplicate: bool,
     pub event_type: Option<String>,
 }
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceQuery {
+    /// List the organization's invoices instead of the caller's own.
+    pub organization_id: Option<String>,
+    /// Number of invoices, 20 by default and at most 100.
+    pub limit: Option<i64>,
+}
+
+/// One invoice of the Stripe customer. Amounts are in the currency's
+/// smallest unit, as Stripe reports them.
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Invoice {
+    pub id: String,
+    pub number: Option<String>,
+    pub status: String,
+    pub currency: String,
+    pub amount_due: i64,
+    pub amount_paid: i64,
+    pub period_start: Option<String>,
+    pub period_end: Option<String>,
+    pub created_at: String,
+    pub hosted_invoice_url: Option<String>,
+    pub invoice_pdf_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceListResponse {
+    pub mode: String,
+    pub invoices: Vec<Invoice>,
+}
+
+/// A persisted webhook event, as listed for replay.
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StripeEventSummary {
+    pub event_id: String,
+    pub event_type: String,
+    pub status: String,
+    pub source: String,
+    pub last_error: Option<String>,
+    pub replay_count: i32,
+    pub received_at: String,
+    pub processed_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StripeEventReplayResult {
+    pub event_id: String,
+    /// `processed`, `failed`, or `skipped` when the event was already
+    /// processed or does not exist.
+    pub outcome: String,
+    pub error: Option<String>,
+}
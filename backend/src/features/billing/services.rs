@@ -1,5 +1,5 @@
 use axum::http::StatusCode;
-use chrono::{TimeZone, Utc};
+use chrono::{DateTime, Duration, TimeZone, Utc};
 use hmac::{Hmac, Mac};
 use serde::Deserialize;
 use serde_json::Value;
@@ -7,14 +7,20 @@ use sha2::Sha256;
 use sqlx::{PgPool, Row};
 
 use crate::error::{client_error, AppError};
+use crate::features::credits::models::LedgerReference;
+use crate::features::credits::services::CreditService;
 use crate::features::entitlements::models::{Entitlement, PlanCode};
 use crate::features::entitlements::repository::EntitlementRepository;
 use crate::features::feature_flags::services::FeatureFlagService;
+use crate::features::organizations::repository::OrganizationRepository;
 use crate::shared::helpers::{next_id, now_ts};
 
+use super::lifecycle::notify_team_plan_lapsed;
 use super::models::{
-    BillingPortalSessionResponse, CreateBillingPortalSessionRequest, CreateTeamCheckoutRequest,
-    StripeWebhookResponse, TeamCheckoutResponse,
+    BillingPortalSessionResponse, CreateBillingPortalSessionRequest, CreateCreditCheckoutRequest,
+    CreateTeamCheckoutRequest, CreditCheckoutResponse, Invoice, InvoiceListResponse, InvoiceQuery,
+    StripeEventReplayResult, StripeEventSummary, StripeWebhookResponse, TeamCheckoutResponse,
+    TeamSeatsResponse, UpdateTeamSeatsRequest,
 };
 
 #[derive(Clone)]
@@ -38,6 +44,13 @@ struct StripeBillingPortalSessionResponse {
     url: Option<String>,
 }
 
+/// The organization's running Team subscription, as far as seat changes go.
+struct TeamSubscriptionSeats {
+    id: String,
+    provider_subscription_id: String,
+    seat_quantity: i32,
+}
+
 #[derive(Debug, Deserialize)]
 struct StripeErrorEnvelope {
     error: StripeErrorObject,
@@ -71,6 +84,19 @@ const DEFAULT_STRIPE_API_BASE_URL: &str = "https://api.stripe.com";
 const STRIPE_SIGNATURE_TOLERANCE_SECONDS: i64 = 300;
 const TEAM_MEMBER_COUNT_MIN: i32 = 1;
 const TEAM_MEMBER_COUNT_MAX: i32 = 10;
+const CREDIT_PACK_SIZE: i32 = 500;
+const CREDIT_PACK_QUANTITY_MIN: i32 = 1;
+const CREDIT_PACK_QUANTITY_MAX: i32 = 20;
+const CREDIT_PURCHASE_TYPE: &str = "credits";
+const STRIPE_EVENT_LIST_LIMIT_MAX: i64 = 500;
+/// `received` events younger than this may still be in the webhook handler,
+/// so replays leave them alone.
+const STRIPE_EVENT_REPLAY_MIN_AGE_SECONDS: i64 = 300;
+const INVOICE_LIST_LIMIT_DEFAULT: i64 = 20;
+const INVOICE_LIST_LIMIT_MAX: i64 = 100;
+const MOCK_INVOICE_BASE_URL: &str = "https://billing.pm-journey.local/invoices";
+const CREDIT_PURCHASE_REASON: &str = "purchase";
+const DEFAULT_PAST_DUE_GRACE_DAYS: i64 = 7;
 
 fn parse_billing_provider(raw: &str) -> Option<BillingProvider> {
     match raw.trim().to_ascii_lowercase().as_str() {
@@ -126,6 +152,34 @@ fn build_mock_team_checkout_url(
     url
 }
 
+fn build_mock_credit_checkout_url(
+    user_id: &str,
+    scope_type: &str,
+    scope_id: &str,
+    pack_quantity: i32,
+    checkout_ref: &str,
+    success_url: Option<&str>,
+    cancel_url: Option<&str>,
+) -> String {
+    let mut url = std::env::var("BILLING_MOCK_CHECKOUT_BASE_URL")
+        .unwrap_or_else(|_| "https://billing.pm-journey.local/checkout".to_string());
+    append_query(&mut url, "purchase", CREDIT_PURCHASE_TYPE);
+    append_query(&mut url, "userId", user_id);
+    append_query(&mut url, "scopeType", scope_type);
+    append_query(&mut url, "scopeId", scope_id);
+    append_query(&mut url, "packQuantity", &pack_quantity.to_string());
+    append_query(&mut url, "checkoutRef", checkout_ref);
+
+    if let Some(success_url) = success_url.and_then(trim_non_empty) {
+        append_query(&mut url, "successUrl", &success_url);
+    }
+    if let Some(cancel_url) = cancel_url.and_then(trim_non_empty) {
+        append_query(&mut url, "cancelUrl", &cancel_url);
+    }
+
+    url
+}
+
 fn trim_non_empty(value: &str) -> Option<String> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
@@ -145,6 +199,25 @@ fn env_non_empty(key: &str) -> Option<String> {
         .and_then(|value| trim_non_empty(&value))
 }
 
+/// What a mock Team invoice charges per seat (JPY), from
+/// BILLING_MOCK_TEAM_SEAT_AMOUNT_JPY; Stripe mode reports the real amounts.
+fn mock_team_seat_amount() -> i64 {
+    env_non_empty("BILLING_MOCK_TEAM_SEAT_AMOUNT_JPY")
+        .and_then(|value| value.parse::<i64>().ok())
+        .filter(|value| *value >= 0)
+        .unwrap_or(0)
+}
+
+/// Invoice status for a mock subscription's current period and whether it
+/// was paid, following what Stripe would show for that subscription state.
+fn mock_invoice_status(subscription_status: &str) -> (&'static str, bool) {
+    match subscription_status {
+        "active" | "trialing" => ("paid", true),
+        "canceled" => ("void", false),
+        _ => ("open", false),
+    }
+}
+
 fn required_env_non_empty(key: &str) -> Result<String, AppError> {
     env_non_empty(key).ok_or_else(|| {
         client_error(format!(
@@ -173,6 +246,18 @@ fn resolve_stripe_team_price_id() -> Result<String, AppError> {
     required_env_non_empty("STRIPE_PRICE_ID_TEAM")
 }
 
+/// Whether STRIPE_PRICE_ID_TEAM is billed per seat. Flat team prices keep a
+/// line item quantity of 1 and only carry the seat count in metadata.
+fn stripe_team_price_is_per_seat() -> bool {
+    std::env::var("STRIPE_TEAM_PRICE_PER_SEAT")
+        .map(|value| value == "true" || value == "1")
+        .unwrap_or(false)
+}
+
+fn resolve_stripe_credits_price_id() -> Result<String, AppError> {
+    required_env_non_empty("STRIPE_PRICE_ID_CREDITS")
+}
+
 fn resolve_stripe_webhook_secret() -> Result<String, AppError> {
     required_env_non_empty("STRIPE_WEBHOOK_SECRET")
 }
@@ -195,7 +280,7 @@ fn default_checkout_return_url(status: &str) -> String {
     )
 }
 
-fn default_portal_return_url() -> String {
+pub(super) fn default_portal_return_url() -> String {
     let app_base_url =
         env_non_empty("APP_BASE_URL").unwrap_or_else(|| DEFAULT_APP_BASE_URL.to_string());
     format!("{}/settings/billing", app_base_url.trim_end_matches('/'))
@@ -352,6 +437,11 @@ fn parse_positive_i32(value: &Value) -> Option<i32> {
         .filter(|parsed| *parsed > 0)
 }
 
+fn is_credit_purchase(object: &Value) -> bool {
+    object.get("mode").and_then(|value| value.as_str()) == Some("payment")
+        && parse_metadata_string(object, "purchase_type").as_deref() == Some(CREDIT_PURCHASE_TYPE)
+}
+
 fn parse_seat_quantity_from_metadata(object: &Value) -> Option<i32> {
     object
         .get("metadata")
@@ -377,6 +467,76 @@ fn parse_team_seat_quantity(object: &Value) -> Option<i32> {
         .map(|seat_quantity| seat_quantity.min(TEAM_MEMBER_COUNT_MAX))
 }
 
+/// The subscription item that carries the Team seat count: the one billed at
+/// the Team price, falling back to the only item on the subscription.
+fn find_team_subscription_item_id(
+    subscription: &Value,
+    team_price_id: Option<&str>,
+) -> Option<String> {
+    let items = subscription
+        .get("items")
+        .and_then(|value| value.get("data"))
+        .and_then(|value| value.as_array())?;
+    let priced_item = team_price_id.and_then(|price_id| {
+        items.iter().find(|item| {
+            item.get("price")
+                .and_then(|price| price.get("id"))
+                .and_then(|value| value.as_str())
+                == Some(price_id)
+        })
+    });
+    let item = priced_item.or(match items.as_slice() {
+        [only] => Some(only),
+        _ => None,
+    })?;
+    item.get("id")
+        .and_then(|value| value.as_str())
+        .and_then(trim_non_empty)
+}
+
+/// Read a Stripe API response as JSON, mapping Stripe client errors to 422
+/// and everything else to 502 under `failure_code`.
+async fn read_stripe_json(
+    response: Result<reqwest::Response, reqwest::Error>,
+    failure_code: &str,
+) -> Result<Value, AppError> {
+    let response = response.map_err(|error| {
+        AppError::new(
+            StatusCode::BAD_GATEWAY,
+            anyhow::anyhow!("STRIPE_REQUEST_FAILED: {error}"),
+        )
+    })?;
+    let status = response.status();
+    let response_body = response.text().await.map_err(|error| {
+        AppError::new(
+            StatusCode::BAD_GATEWAY,
+            anyhow::anyhow!("STRIPE_RESPONSE_READ_FAILED: {error}"),
+        )
+    })?;
+
+    if !status.is_success() {
+        let app_status = if status.is_client_error() {
+            StatusCode::UNPROCESSABLE_ENTITY
+        } else {
+            StatusCode::BAD_GATEWAY
+        };
+        return Err(AppError::new(
+            app_status,
+            anyhow::anyhow!(
+                "{failure_code}: {}",
+                parse_stripe_error_message(&response_body)
+            ),
+        ));
+    }
+
+    serde_json::from_str::<Value>(&response_body).map_err(|error| {
+        AppError::new(
+            StatusCode::BAD_GATEWAY,
+            anyhow::anyhow!("STRIPE_RESPONSE_PARSE_FAILED: {error}"),
+        )
+    })
+}
+
 fn normalize_subscription_status(raw: Option<&str>) -> &'static str {
     match raw.unwrap_or_default() {
         "active" => "active",
@@ -392,10 +552,112 @@ fn is_subscription_entitled_status(status: &str) -> bool {
     matches!(status, "active" | "trialing")
 }
 
+/// Dunning window during which a past_due subscription keeps its plan.
+fn resolve_past_due_grace() -> Duration {
+    let days = env_non_empty("BILLING_PAST_DUE_GRACE_DAYS")
+        .and_then(|value| value.parse::<i64>().ok())
+        .filter(|days| *days >= 0)
+        .unwrap_or(DEFAULT_PAST_DUE_GRACE_DAYS);
+    Duration::days(days)
+}
+
+/// Whether a subscription still grants its plan, and until when.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SubscriptionAccess {
+    /// `valid_until` is set once access is scheduled to end: at the period
+    /// end of a cancelling subscription or when the past_due grace runs out.
+    Entitled {
+        valid_until: Option<DateTime<Utc>>,
+    },
+    Lapsed,
+}
+
+fn subscription_access(
+    status: &str,
+    past_due_since: Option<DateTime<Utc>>,
+    cancel_at_period_end: bool,
+    current_period_end: Option<DateTime<Utc>>,
+    grace: Duration,
+    now: DateTime<Utc>,
+) -> SubscriptionAccess {
+    let grace_end = if is_subscription_entitled_status(status) {
+        None
+    } else if status == "past_due" {
+        Some(past_due_since.unwrap_or(now) + grace)
+    } else {
+        return SubscriptionAccess::Lapsed;
+    };
+    let period_end = current_period_end.filter(|_| cancel_at_period_end);
+    let valid_until = match (grace_end, period_end) {
+        (Some(grace_end), Some(period_end)) => Some(grace_end.min(period_end)),
+        (grace_end, period_end) => grace_end.or(period_end),
+    };
+
+    if valid_until.is_some_and(|until| until <= now) {
+        return SubscriptionAccess::Lapsed;
+    }
+    SubscriptionAccess::Entitled { valid_until }
+}
+
 fn parse_unix_timestamp_to_utc(value: Option<i64>) -> Option<chrono::DateTime<Utc>> {
     value.and_then(|unix_seconds| Utc.timestamp_opt(unix_seconds, 0).single())
 }
 
+fn unix_timestamp_to_rfc3339(value: Option<&Value>) -> Option<String> {
+    parse_unix_timestamp_to_utc(value.and_then(|value| value.as_i64()))
+        .map(|timestamp| timestamp.to_rfc3339())
+}
+
+/// Map a Stripe invoice object. The billed period comes from the first line
+/// item: the invoice-level `period_start`/`period_end` of a subscription
+/// invoice describe the previous period, so they are only a fallback.
+fn parse_stripe_invoice(object: &Value) -> Option<Invoice> {
+    let id = object
+        .get("id")
+        .and_then(|value| value.as_str())
+        .and_then(trim_non_empty)?;
+    let line_period = object
+        .get("lines")
+        .and_then(|value| value.get("data"))
+        .and_then(|value| value.as_array())
+        .and_then(|lines| lines.first())
+        .and_then(|line| line.get("period"));
+    let string_field = |key: &str| {
+        object
+            .get(key)
+            .and_then(|value| value.as_str())
+            .and_then(trim_non_empty)
+    };
+
+    Some(Invoice {
+        id,
+        number: string_field("number"),
+        status: string_field("status").unwrap_or_else(|| "draft".to_string()),
+        currency: string_field("currency").unwrap_or_default(),
+        amount_due: object
+            .get("amount_due")
+            .and_then(|value| value.as_i64())
+            .unwrap_or(0),
+        amount_paid: object
+            .get("amount_paid")
+            .and_then(|value| value.as_i64())
+            .unwrap_or(0),
+        period_start: unix_timestamp_to_rfc3339(
+            line_period
+                .and_then(|period| period.get("start"))
+                .or_else(|| object.get("period_start")),
+        ),
+        period_end: unix_timestamp_to_rfc3339(
+            line_period
+                .and_then(|period| period.get("end"))
+                .or_else(|| object.get("period_end")),
+        ),
+        created_at: unix_timestamp_to_rfc3339(object.get("created")).unwrap_or_default(),
+        hosted_invoice_url: string_field("hosted_invoice_url"),
+        invoice_pdf_url: string_field("invoice_pdf"),
+    })
+}
+
 impl BillingService {
     pub fn new(pool: PgPool) -> Self {
         Self { pool }
@@ -440,10 +702,11 @@ impl BillingService {
         Ok(row.and_then(|record| record.try_get::<Option<String>, _>("role").ok().flatten()))
     }
 
-    async fn ensure_team_checkout_permission(
+    async fn ensure_org_checkout_permission(
         &self,
         organization_id: &str,
         user_id: &str,
+        checkout_kind: &str,
     ) -> Result<(), AppError> {
         let role = self
             .find_org_role_for_user(organization_id, user_id)
@@ -451,14 +714,18 @@ impl BillingService {
             .ok_or_else(|| {
                 AppError::new(
                     StatusCode::FORBIDDEN,
-                    anyhow::anyhow!("FORBIDDEN_ROLE: insufficient permission for team checkout"),
+                    anyhow::anyhow!(
+                        "FORBIDDEN_ROLE: insufficient permission for {checkout_kind} checkout"
+                    ),
                 )
             })?;
 
         if !matches!(role.as_str(), "owner" | "admin" | "manager") {
             return Err(AppError::new(
                 StatusCode::FORBIDDEN,
-                anyhow::anyhow!("FORBIDDEN_ROLE: insufficient permission for team checkout"),
+                anyhow::anyhow!(
+                    "FORBIDDEN_ROLE: insufficient permission for {checkout_kind} checkout"
+                ),
             ));
         }
 
@@ -475,7 +742,7 @@ impl BillingService {
             FROM subscriptions
             WHERE organization_id = $1
               AND plan_code = 'TEAM'
-              AND status IN ('active', 'trialing')
+              AND status IN ('active', 'trialing', 'past_due')
             LIMIT 1
             "#,
         )
@@ -560,6 +827,35 @@ impl BillingService {
             .flatten())
     }
 
+    async fn find_stripe_customer_for_org(
+        &self,
+        organization_id: &str,
+    ) -> Result<Option<String>, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT provider_customer_id
+            FROM billing_customers
+            WHERE organization_id = $1 AND provider = 'stripe'
+            LIMIT 1
+            "#,
+        )
+        .bind(organization_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|error| {
+            anyhow::anyhow!("Failed to find Stripe customer for organization: {error}")
+        })?;
+
+        Ok(row
+            .and_then(|record| {
+                record
+                    .try_get::<Option<String>, _>("provider_customer_id")
+                    .ok()
+                    .flatten()
+            })
+            .and_then(|value| trim_non_empty(&value)))
+    }
+
     async fn upsert_billing_customer(
         &self,
         user_id: &str,
@@ -839,14 +1135,60 @@ impl BillingService {
         Ok(subscription_id)
     }
 
+    /// Stamp when the subscription entered past_due (kept across repeated
+    /// past_due events, cleared once it leaves that state) and return it.
+    async fn record_past_due_since(
+        &self,
+        subscription_row_id: &str,
+    ) -> Result<Option<DateTime<Utc>>, AppError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE subscriptions
+            SET past_due_since = CASE
+                WHEN status = 'past_due' THEN COALESCE(past_due_since, NOW())
+                ELSE NULL
+            END
+            WHERE id = $1
+            RETURNING past_due_since
+            "#,
+        )
+        .bind(subscription_row_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!("Failed to record past_due start: {error}"))?;
+
+        Ok(row
+            .try_get::<Option<DateTime<Utc>>, _>("past_due_since")
+            .ok()
+            .flatten())
+    }
+
+    async fn resolve_subscription_access(
+        &self,
+        subscription_row_id: &str,
+        status: &str,
+        cancel_at_period_end: bool,
+        current_period_end: Option<DateTime<Utc>>,
+    ) -> Result<SubscriptionAccess, AppError> {
+        let past_due_since = self.record_past_due_since(subscription_row_id).await?;
+        Ok(subscription_access(
+            status,
+            past_due_since,
+            cancel_at_period_end,
+            current_period_end,
+            resolve_past_due_grace(),
+            Utc::now(),
+        ))
+    }
+
     async fn sync_user_entitlement_from_subscription(
         &self,
         user_id: &str,
         subscription_id: &str,
         plan_code: &PlanCode,
-        status: &str,
+        access: SubscriptionAccess,
     ) -> Result<(), AppError> {
-        if is_subscription_entitled_status(status) {
+        if let SubscriptionAccess::Entitled { valid_until } = access {
             sqlx::query(
                 r#"
                 UPDATE entitlements
@@ -876,8 +1218,7 @@ impl BillingService {
                   AND source_subscription_id = $2
                   AND status = 'active'
                   AND plan_code = $3
-                  AND valid_from <= NOW()
-                  AND (valid_until IS NULL OR valid_until > NOW())
+                ORDER BY created_at DESC
                 LIMIT 1
                 "#,
             )
@@ -890,7 +1231,19 @@ impl BillingService {
                 anyhow::anyhow!("Failed to check existing user entitlement: {}", error)
             })?;
 
-            if existing.is_none() {
+            if let Some(existing) = existing {
+                let entitlement_id = existing.try_get::<String, _>("id").map_err(|error| {
+                    anyhow::anyhow!("Failed to read user entitlement id: {error}")
+                })?;
+                sqlx::query("UPDATE entitlements SET valid_until = $2 WHERE id = $1")
+                    .bind(&entitlement_id)
+                    .bind(valid_until)
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|error| {
+                        anyhow::anyhow!("Failed to update user entitlement window: {error}")
+                    })?;
+            } else {
                 let entitlement_repo = EntitlementRepository::new(self.pool.clone());
                 let entitlement = Entitlement {
                     id: next_id("entitlement"),
@@ -899,7 +1252,7 @@ impl BillingService {
                     plan_code: plan_code.clone(),
                     status: "active".to_string(),
                     valid_from: now_ts(),
-                    valid_until: None,
+                    valid_until: valid_until.map(|until| until.to_rfc3339()),
                     source_subscription_id: Some(subscription_id.to_string()),
                 };
                 entitlement_repo
@@ -937,9 +1290,9 @@ impl BillingService {
         organization_id: &str,
         subscription_id: &str,
         plan_code: &PlanCode,
-        status: &str,
+        access: SubscriptionAccess,
     ) -> Result<(), AppError> {
-        if is_subscription_entitled_status(status) {
+        if let SubscriptionAccess::Entitled { valid_until } = access {
             sqlx::query(
                 r#"
                 UPDATE entitlements
@@ -969,8 +1322,7 @@ impl BillingService {
                   AND source_subscription_id = $2
                   AND status = 'active'
                   AND plan_code = $3
-                  AND valid_from <= NOW()
-                  AND (valid_until IS NULL OR valid_until > NOW())
+                ORDER BY created_at DESC
                 LIMIT 1
                 "#,
             )
@@ -986,7 +1338,19 @@ impl BillingService {
                 )
             })?;
 
-            if existing.is_none() {
+            if let Some(existing) = existing {
+                let entitlement_id = existing.try_get::<String, _>("id").map_err(|error| {
+                    anyhow::anyhow!("Failed to read organization entitlement id: {error}")
+                })?;
+                sqlx::query("UPDATE entitlements SET valid_until = $2 WHERE id = $1")
+                    .bind(&entitlement_id)
+                    .bind(valid_until)
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|error| {
+                        anyhow::anyhow!("Failed to update organization entitlement window: {error}")
+                    })?;
+            } else {
                 let entitlement_repo = EntitlementRepository::new(self.pool.clone());
                 let entitlement = Entitlement {
                     id: next_id("entitlement"),
@@ -995,7 +1359,7 @@ impl BillingService {
                     plan_code: plan_code.clone(),
                     status: "active".to_string(),
                     valid_from: now_ts(),
-                    valid_until: None,
+                    valid_until: valid_until.map(|until| until.to_rfc3339()),
                     source_subscription_id: Some(subscription_id.to_string()),
                 };
                 entitlement_repo
@@ -1009,7 +1373,7 @@ impl BillingService {
             return Ok(());
         }
 
-        sqlx::query(
+        let revoked = sqlx::query(
             r#"
             UPDATE entitlements
             SET status = 'revoked', valid_until = COALESCE(valid_until, NOW())
@@ -1017,16 +1381,24 @@ impl BillingService {
               AND scope_id = $1
               AND source_subscription_id = $2
               AND status = 'active'
+            RETURNING id
             "#,
         )
         .bind(organization_id)
         .bind(subscription_id)
-        .execute(&self.pool)
+        .fetch_all(&self.pool)
         .await
         .map_err(|error| {
             anyhow::anyhow!("Failed to deactivate organization entitlements: {}", error)
         })?;
 
+        for row in revoked {
+            let entitlement_id = row.try_get::<String, _>("id").map_err(|error| {
+                anyhow::anyhow!("Failed to read organization entitlement id: {error}")
+            })?;
+            notify_team_plan_lapsed(&self.pool, organization_id, &entitlement_id).await?;
+        }
+
         Ok(())
     }
 
@@ -1038,8 +1410,6 @@ impl BillingService {
         success_url: Option<&str>,
         cancel_url: Option<&str>,
     ) -> Result<String, AppError> {
-        let secret_key = resolve_stripe_secret_key()?;
-        let api_base_url = resolve_stripe_api_base_url()?;
         let team_price_id = resolve_stripe_team_price_id()?;
         let (success_url, cancel_url) =
             resolve_checkout_return_urls_from_options(success_url, cancel_url)?;
@@ -1049,7 +1419,14 @@ impl BillingService {
         let mut form = vec![
             ("mode".to_string(), "subscription".to_string()),
             ("line_items[0][price]".to_string(), team_price_id.clone()),
-            ("line_items[0][quantity]".to_string(), "1".to_string()),
+            (
+                "line_items[0][quantity]".to_string(),
+                if stripe_team_price_is_per_seat() {
+                    seat_quantity_string.clone()
+                } else {
+                    "1".to_string()
+                },
+            ),
             ("success_url".to_string(), success_url),
             ("cancel_url".to_string(), cancel_url),
             ("client_reference_id".to_string(), user_id.to_string()),
@@ -1087,7 +1464,66 @@ impl BillingService {
             form.push(("customer_email".to_string(), email));
         }
 
-        let idempotency_key = next_id("stripe-team-checkout");
+        self.create_stripe_checkout_session_url(&form, "stripe-team-checkout")
+            .await
+    }
+
+    async fn create_stripe_credit_checkout_url(
+        &self,
+        user_id: &str,
+        scope_type: &str,
+        scope_id: &str,
+        pack_quantity: i32,
+        success_url: Option<&str>,
+        cancel_url: Option<&str>,
+    ) -> Result<String, AppError> {
+        let credits_price_id = resolve_stripe_credits_price_id()?;
+        let (success_url, cancel_url) =
+            resolve_checkout_return_urls_from_options(success_url, cancel_url)?;
+        let user_email = self.find_user_email(user_id).await?;
+
+        let mut form = vec![
+            ("mode".to_string(), "payment".to_string()),
+            ("line_items[0][price]".to_string(), credits_price_id),
+            (
+                "line_items[0][quantity]".to_string(),
+                pack_quantity.to_string(),
+            ),
+            ("success_url".to_string(), success_url),
+            ("cancel_url".to_string(), cancel_url),
+            ("client_reference_id".to_string(), user_id.to_string()),
+            ("metadata[user_id]".to_string(), user_id.to_string()),
+            (
+                "metadata[purchase_type]".to_string(),
+                CREDIT_PURCHASE_TYPE.to_string(),
+            ),
+            ("metadata[scope_type]".to_string(), scope_type.to_string()),
+            ("metadata[scope_id]".to_string(), scope_id.to_string()),
+            (
+                "metadata[pack_quantity]".to_string(),
+                pack_quantity.to_string(),
+            ),
+            (
+                "metadata[credits]".to_string(),
+                (pack_quantity * CREDIT_PACK_SIZE).to_string(),
+            ),
+        ];
+        if let Some(email) = user_email {
+            form.push(("customer_email".to_string(), email));
+        }
+
+        self.create_stripe_checkout_session_url(&form, "stripe-credit-checkout")
+            .await
+    }
+
+    async fn create_stripe_checkout_session_url(
+        &self,
+        form: &[(String, String)],
+        idempotency_prefix: &str,
+    ) -> Result<String, AppError> {
+        let secret_key = resolve_stripe_secret_key()?;
+        let api_base_url = resolve_stripe_api_base_url()?;
+        let idempotency_key = next_id(idempotency_prefix);
         let endpoint = format!(
             "{}/v1/checkout/sessions",
             api_base_url.trim_end_matches('/')
@@ -1097,7 +1533,7 @@ impl BillingService {
             .post(endpoint)
             .bearer_auth(secret_key)
             .header("Idempotency-Key", idempotency_key)
-            .form(&form)
+            .form(form)
             .send()
             .await
             .map_err(|error| {
@@ -1231,51 +1667,261 @@ impl BillingService {
             })
     }
 
-    async fn record_stripe_event(
+    async fn find_team_subscription_seats(
         &self,
-        event: &StripeEventEnvelope,
-        payload: &Value,
-    ) -> Result<bool, AppError> {
-        let result = sqlx::query(
+        organization_id: &str,
+    ) -> Result<Option<TeamSubscriptionSeats>, AppError> {
+        let row = sqlx::query(
             r#"
-            INSERT INTO stripe_events (event_id, event_type, payload, status)
-            VALUES ($1, $2, $3, 'received')
-            ON CONFLICT (event_id) DO NOTHING
+            SELECT id, provider_subscription_id, seat_quantity
+            FROM subscriptions
+            WHERE organization_id = $1
+              AND plan_code = 'TEAM'
+              AND status IN ('active', 'trialing', 'past_due')
+            ORDER BY updated_at DESC
+            LIMIT 1
             "#,
         )
-        .bind(&event.id)
-        .bind(&event.event_type)
-        .bind(payload)
-        .execute(&self.pool)
+        .bind(organization_id)
+        .fetch_optional(&self.pool)
         .await
-        .map_err(|error| anyhow::anyhow!("Failed to persist Stripe event: {}", error))?;
+        .map_err(|error| anyhow::anyhow!("Failed to load team subscription: {error}"))?;
+
+        Ok(row.map(|record| TeamSubscriptionSeats {
+            id: record.try_get("id").unwrap_or_default(),
+            provider_subscription_id: record
+                .try_get::<Option<String>, _>("provider_subscription_id")
+                .ok()
+                .flatten()
+                .unwrap_or_default(),
+            seat_quantity: record
+                .try_get::<Option<i32>, _>("seat_quantity")
+                .ok()
+                .flatten()
+                .unwrap_or(0),
+        }))
+    }
 
-        Ok(result.rows_affected() > 0)
+    /// Seats already spoken for: active members plus pending invitations.
+    async fn count_seats_in_use(&self, organization_id: &str) -> Result<i64, AppError> {
+        let repo = OrganizationRepository::new(self.pool.clone());
+        let active = repo
+            .count_active_members(organization_id)
+            .await
+            .map_err(|error| anyhow::anyhow!("Failed to count active members: {error}"))?;
+        let pending = repo
+            .count_pending_invitations(organization_id)
+            .await
+            .map_err(|error| anyhow::anyhow!("Failed to count pending invitations: {error}"))?;
+
+        Ok(active + pending)
     }
 
-    async fn mark_stripe_event_processed(&self, event_id: &str) -> Result<(), AppError> {
-        sqlx::query(
-            r#"
-            UPDATE stripe_events
-            SET status = 'processed', processed_at = NOW(), last_error = NULL
-            WHERE event_id = $1
-            "#,
+    /// Set the seat count on the Stripe subscription: the seat item quantity
+    /// with proration for per-seat prices, the metadata alone for flat ones.
+    /// The local seat count is left to the `customer.subscription.updated`
+    /// webhook, so Stripe stays the source of truth. Retries for the same
+    /// target quantity share an idempotency key.
+    async fn update_stripe_subscription_seats(
+        &self,
+        provider_subscription_id: &str,
+        seat_quantity: i32,
+    ) -> Result<(), AppError> {
+        let secret_key = resolve_stripe_secret_key()?;
+        let api_base_url = resolve_stripe_api_base_url()?;
+        let endpoint = format!(
+            "{}/v1/subscriptions/{provider_subscription_id}",
+            api_base_url.trim_end_matches('/')
+        );
+        let client = reqwest::Client::new();
+
+        let subscription = read_stripe_json(
+            client.get(&endpoint).bearer_auth(&secret_key).send().await,
+            "STRIPE_SEAT_UPDATE_FAILED",
         )
-        .bind(event_id)
-        .execute(&self.pool)
-        .await
-        .map_err(|error| anyhow::anyhow!("Failed to mark Stripe event processed: {}", error))?;
+        .await?;
+        let team_price_id = env_non_empty("STRIPE_PRICE_ID_TEAM");
+        let item_id = find_team_subscription_item_id(&subscription, team_price_id.as_deref())
+            .ok_or_else(|| {
+                AppError::new(
+                    StatusCode::BAD_GATEWAY,
+                    anyhow::anyhow!(
+                        "STRIPE_SEAT_UPDATE_FAILED: subscription has no team seat item"
+                    ),
+                )
+            })?;
+
+        let seat_quantity_string = seat_quantity.to_string();
+        let mut form = vec![("metadata[seat_quantity]", seat_quantity_string.clone())];
+        if stripe_team_price_is_per_seat() {
+            form.push(("items[0][id]", item_id));
+            form.push(("items[0][quantity]", seat_quantity_string));
+            form.push(("proration_behavior", "create_prorations".to_string()));
+        }
+        read_stripe_json(
+            client
+                .post(&endpoint)
+                .bearer_auth(&secret_key)
+                .header(
+                    "Idempotency-Key",
+                    format!("stripe-seat-update-{provider_subscription_id}-{seat_quantity}"),
+                )
+                .form(&form)
+                .send()
+                .await,
+            "STRIPE_SEAT_UPDATE_FAILED",
+        )
+        .await?;
 
         Ok(())
     }
 
-    async fn mark_stripe_event_failed(
+    async fn list_stripe_invoices(
         &self,
-        event_id: &str,
-        message: &str,
-    ) -> Result<(), AppError> {
-        sqlx::query(
-            r#"
+        customer_id: &str,
+        limit: i64,
+    ) -> Result<Vec<Invoice>, AppError> {
+        let secret_key = resolve_stripe_secret_key()?;
+        let api_base_url = resolve_stripe_api_base_url()?;
+        let endpoint = format!("{}/v1/invoices", api_base_url.trim_end_matches('/'));
+
+        let response = read_stripe_json(
+            reqwest::Client::new()
+                .get(endpoint)
+                .bearer_auth(secret_key)
+                .query(&[
+                    ("customer", customer_id.to_string()),
+                    ("limit", limit.to_string()),
+                ])
+                .send()
+                .await,
+            "STRIPE_INVOICE_LIST_FAILED",
+        )
+        .await?;
+
+        Ok(response
+            .get("data")
+            .and_then(|value| value.as_array())
+            .map(|invoices| invoices.iter().filter_map(parse_stripe_invoice).collect())
+            .unwrap_or_default())
+    }
+
+    /// Mock provider invoices: one per local subscription of the scope, for
+    /// its current period.
+    async fn list_mock_invoices(
+        &self,
+        user_id: &str,
+        organization_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Invoice>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, status, seat_quantity, current_period_start, current_period_end, created_at
+            FROM subscriptions
+            WHERE CASE
+                WHEN $1::TEXT IS NOT NULL THEN organization_id = $1
+                ELSE user_id = $2
+            END
+            ORDER BY COALESCE(current_period_start, created_at) DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(organization_id)
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!("Failed to list subscriptions for invoices: {error}"))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let subscription_id: String = row.try_get("id").unwrap_or_default();
+                let status: String = row.try_get("status").unwrap_or_default();
+                let seats = row
+                    .try_get::<Option<i32>, _>("seat_quantity")
+                    .ok()
+                    .flatten()
+                    .unwrap_or(1);
+                // Trial periods are invoiced at zero, as Stripe does.
+                let amount_due = if status == "trialing" {
+                    0
+                } else {
+                    i64::from(seats) * mock_team_seat_amount()
+                };
+                let (invoice_status, paid) = mock_invoice_status(&status);
+                let invoice_id = format!("mock_in_{subscription_id}");
+                let timestamp = |key: &str| {
+                    row.try_get::<Option<DateTime<Utc>>, _>(key)
+                        .ok()
+                        .flatten()
+                        .map(|value| value.to_rfc3339())
+                };
+
+                Invoice {
+                    number: Some(format!("MOCK-{subscription_id}")),
+                    status: invoice_status.to_string(),
+                    currency: "jpy".to_string(),
+                    amount_due,
+                    amount_paid: if paid { amount_due } else { 0 },
+                    period_start: timestamp("current_period_start"),
+                    period_end: timestamp("current_period_end"),
+                    created_at: timestamp("created_at").unwrap_or_default(),
+                    hosted_invoice_url: Some(format!("{MOCK_INVOICE_BASE_URL}/{invoice_id}")),
+                    invoice_pdf_url: Some(format!("{MOCK_INVOICE_BASE_URL}/{invoice_id}.pdf")),
+                    id: invoice_id,
+                }
+            })
+            .collect())
+    }
+
+    async fn record_stripe_event(
+        &self,
+        event: &StripeEventEnvelope,
+        payload: &Value,
+        source: &str,
+    ) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO stripe_events (event_id, event_type, payload, status, source)
+            VALUES ($1, $2, $3, 'received', $4)
+            ON CONFLICT (event_id) DO NOTHING
+            "#,
+        )
+        .bind(&event.id)
+        .bind(&event.event_type)
+        .bind(payload)
+        .bind(source)
+        .execute(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!("Failed to persist Stripe event: {}", error))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn mark_stripe_event_processed(&self, event_id: &str) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE stripe_events
+            SET status = 'processed', processed_at = NOW(), last_error = NULL
+            WHERE event_id = $1
+            "#,
+        )
+        .bind(event_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!("Failed to mark Stripe event processed: {}", error))?;
+
+        Ok(())
+    }
+
+    async fn mark_stripe_event_failed(
+        &self,
+        event_id: &str,
+        message: &str,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
             UPDATE stripe_events
             SET status = 'failed', processed_at = NOW(), last_error = $2
             WHERE event_id = $1
@@ -1342,7 +1988,7 @@ impl BillingService {
                 &organization_id,
                 &subscription_row_id,
                 &PlanCode::Team,
-                status,
+                SubscriptionAccess::Entitled { valid_until: None },
             )
             .await?;
             return Ok(());
@@ -1376,13 +2022,72 @@ impl BillingService {
             &user_id,
             &subscription_row_id,
             &plan_code,
-            status,
+            SubscriptionAccess::Entitled { valid_until: None },
         )
         .await?;
 
         Ok(())
     }
 
+    async fn handle_credit_checkout_completed(&self, object: &Value) -> Result<(), AppError> {
+        // Delayed payment methods complete the session before the money
+        // arrives; those are credited on `async_payment_succeeded`.
+        if object
+            .get("payment_status")
+            .and_then(|value| value.as_str())
+            != Some("paid")
+        {
+            return Ok(());
+        }
+
+        let session_id = object
+            .get("id")
+            .and_then(|value| value.as_str())
+            .and_then(trim_non_empty)
+            .ok_or_else(|| {
+                client_error("STRIPE_WEBHOOK_PAYLOAD_INVALID: missing checkout session id")
+            })?;
+        let scope_type = parse_metadata_string(object, "scope_type")
+            .filter(|value| matches!(value.as_str(), "user" | "organization"))
+            .ok_or_else(|| {
+                client_error("STRIPE_WEBHOOK_PAYLOAD_INVALID: missing credit scope_type")
+            })?;
+        let scope_id = parse_metadata_string(object, "scope_id").ok_or_else(|| {
+            client_error("STRIPE_WEBHOOK_PAYLOAD_INVALID: missing credit scope_id")
+        })?;
+        let credits = object
+            .get("metadata")
+            .and_then(|value| value.get("credits"))
+            .and_then(parse_positive_i32)
+            .ok_or_else(|| client_error("STRIPE_WEBHOOK_PAYLOAD_INVALID: missing credits"))?;
+
+        let purchased_by = parse_metadata_string(object, "user_id");
+
+        CreditService::new(self.pool.clone())
+            .grant_purchased_credits(
+                &scope_type,
+                &scope_id,
+                credits,
+                LedgerReference {
+                    reason: CREDIT_PURCHASE_REASON,
+                    reference_type: Some("checkout_session"),
+                    reference_id: Some(&session_id),
+                    actor_user_id: purchased_by.as_deref(),
+                },
+                serde_json::json!({
+                    "provider": "stripe",
+                    "purchasedBy": purchased_by,
+                    "packQuantity": object
+                        .get("metadata")
+                        .and_then(|value| value.get("pack_quantity"))
+                        .and_then(parse_positive_i32),
+                }),
+            )
+            .await?;
+
+        Ok(())
+    }
+
     async fn handle_customer_subscription_event(&self, object: &Value) -> Result<(), AppError> {
         let subscription_id = object
             .get("id")
@@ -1441,11 +2146,19 @@ impl BillingService {
                     cancel_at_period_end,
                 )
                 .await?;
+            let access = self
+                .resolve_subscription_access(
+                    &subscription_row_id,
+                    status,
+                    cancel_at_period_end,
+                    current_period_end,
+                )
+                .await?;
             self.sync_org_entitlement_from_subscription(
                 &organization_id,
                 &subscription_row_id,
                 &PlanCode::Team,
-                status,
+                access,
             )
             .await?;
             return Ok(());
@@ -1468,11 +2181,19 @@ impl BillingService {
                 cancel_at_period_end,
             )
             .await?;
+        let access = self
+            .resolve_subscription_access(
+                &subscription_row_id,
+                status,
+                cancel_at_period_end,
+                current_period_end,
+            )
+            .await?;
         self.sync_user_entitlement_from_subscription(
             &user_id,
             &subscription_row_id,
             &plan_code,
-            status,
+            access,
         )
         .await?;
 
@@ -1481,6 +2202,12 @@ impl BillingService {
 
     async fn process_stripe_event(&self, event: &StripeEventEnvelope) -> Result<(), AppError> {
         match event.event_type.as_str() {
+            "checkout.session.completed" | "checkout.session.async_payment_succeeded"
+                if is_credit_purchase(&event.data.object) =>
+            {
+                self.handle_credit_checkout_completed(&event.data.object)
+                    .await?
+            }
             "checkout.session.completed" => {
                 self.handle_checkout_session_completed(&event.data.object)
                     .await?
@@ -1513,7 +2240,7 @@ impl BillingService {
 
         let organization_id = trim_non_empty(&body.organization_id)
             .ok_or_else(|| client_error("ORGANIZATION_ID_REQUIRED: organizationId is required"))?;
-        self.ensure_team_checkout_permission(&organization_id, user_id)
+        self.ensure_org_checkout_permission(&organization_id, user_id, "team")
             .await?;
         if body.seat_quantity < TEAM_MEMBER_COUNT_MIN || body.seat_quantity > TEAM_MEMBER_COUNT_MAX
         {
@@ -1572,6 +2299,278 @@ impl BillingService {
         }
     }
 
+    /// Add or remove seats on the organization's Team subscription. Seats
+    /// cannot drop below active members plus pending invitations.
+    pub async fn update_team_seats(
+        &self,
+        user_id: &str,
+        body: UpdateTeamSeatsRequest,
+    ) -> Result<TeamSeatsResponse, AppError> {
+        if !FeatureFlagService::new().is_billing_enabled() {
+            return Err(AppError::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                anyhow::anyhow!("BILLING_DISABLED: billing feature is disabled"),
+            ));
+        }
+
+        let organization_id = trim_non_empty(&body.organization_id)
+            .ok_or_else(|| client_error("ORGANIZATION_ID_REQUIRED: organizationId is required"))?;
+        let role = self
+            .find_org_role_for_user(&organization_id, user_id)
+            .await?;
+        if !matches!(role.as_deref(), Some("owner" | "admin")) {
+            return Err(AppError::new(
+                StatusCode::FORBIDDEN,
+                anyhow::anyhow!("FORBIDDEN_ROLE: only owners and admins can change seats"),
+            ));
+        }
+        if body.seat_quantity < TEAM_MEMBER_COUNT_MIN || body.seat_quantity > TEAM_MEMBER_COUNT_MAX
+        {
+            return Err(client_error(
+                "SEAT_QUANTITY_INVALID: seatQuantity must be between 1 and 10",
+            ));
+        }
+
+        let subscription = self
+            .find_team_subscription_seats(&organization_id)
+            .await?
+            .ok_or_else(|| {
+                client_error(
+                    "TEAM_SUBSCRIPTION_REQUIRED: organization has no active team subscription; use POST /billing/checkout/team",
+                )
+            })?;
+        let seats_in_use = self.count_seats_in_use(&organization_id).await?;
+        if i64::from(body.seat_quantity) < seats_in_use {
+            return Err(client_error(format!(
+                "SEAT_QUANTITY_BELOW_USAGE: {seats_in_use} seats are taken by active members and pending invitations"
+            )));
+        }
+
+        let previous_seat_quantity = subscription.seat_quantity;
+        if body.seat_quantity == previous_seat_quantity {
+            return Ok(TeamSeatsResponse {
+                mode: "none".to_string(),
+                previous_seat_quantity,
+                seat_quantity: body.seat_quantity,
+                message: Some(
+                    "SEAT_QUANTITY_UNCHANGED: subscription already has this many seats".to_string(),
+                ),
+            });
+        }
+
+        match resolve_billing_provider()? {
+            BillingProvider::Mock => {
+                sqlx::query(
+                    r#"
+                    UPDATE subscriptions
+                    SET seat_quantity = $2, updated_at = NOW()
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(&subscription.id)
+                .bind(body.seat_quantity)
+                .execute(&self.pool)
+                .await
+                .map_err(|error| anyhow::anyhow!("Failed to update seat quantity: {error}"))?;
+
+                Ok(TeamSeatsResponse {
+                    mode: "mock".to_string(),
+                    previous_seat_quantity,
+                    seat_quantity: body.seat_quantity,
+                    message: Some("SEATS_UPDATED: mock subscription seats updated".to_string()),
+                })
+            }
+            BillingProvider::Stripe => {
+                self.update_stripe_subscription_seats(
+                    &subscription.provider_subscription_id,
+                    body.seat_quantity,
+                )
+                .await?;
+
+                Ok(TeamSeatsResponse {
+                    mode: "stripe".to_string(),
+                    previous_seat_quantity,
+                    seat_quantity: body.seat_quantity,
+                    message: Some(
+                        "SEATS_UPDATE_REQUESTED: stripe subscription updated with proration; seats apply once the webhook arrives"
+                            .to_string(),
+                    ),
+                })
+            }
+        }
+    }
+
+    pub async fn create_credit_checkout(
+        &self,
+        user_id: &str,
+        body: CreateCreditCheckoutRequest,
+    ) -> Result<CreditCheckoutResponse, AppError> {
+        if !FeatureFlagService::new().is_billing_enabled() {
+            return Err(AppError::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                anyhow::anyhow!("BILLING_DISABLED: billing feature is disabled"),
+            ));
+        }
+
+        let (scope_type, scope_id) = match option_trim_non_empty(body.organization_id.as_deref()) {
+            Some(organization_id) => {
+                self.ensure_org_checkout_permission(&organization_id, user_id, "credit")
+                    .await?;
+                ("organization", organization_id)
+            }
+            None => ("user", user_id.to_string()),
+        };
+        if body.pack_quantity < CREDIT_PACK_QUANTITY_MIN
+            || body.pack_quantity > CREDIT_PACK_QUANTITY_MAX
+        {
+            return Err(client_error(
+                "PACK_QUANTITY_INVALID: packQuantity must be between 1 and 20",
+            ));
+        }
+        let credits = body.pack_quantity * CREDIT_PACK_SIZE;
+
+        match resolve_billing_provider()? {
+            BillingProvider::Mock => Ok(CreditCheckoutResponse {
+                mode: "mock".to_string(),
+                checkout_url: Some(build_mock_credit_checkout_url(
+                    user_id,
+                    scope_type,
+                    &scope_id,
+                    body.pack_quantity,
+                    &next_id("checkout"),
+                    body.success_url.as_deref(),
+                    body.cancel_url.as_deref(),
+                )),
+                credits,
+                message: Some("CHECKOUT_SESSION_CREATED: mock checkout url issued".to_string()),
+            }),
+            BillingProvider::Stripe => {
+                let checkout_url = self
+                    .create_stripe_credit_checkout_url(
+                        user_id,
+                        scope_type,
+                        &scope_id,
+                        body.pack_quantity,
+                        body.success_url.as_deref(),
+                        body.cancel_url.as_deref(),
+                    )
+                    .await?;
+                Ok(CreditCheckoutResponse {
+                    mode: "stripe".to_string(),
+                    checkout_url: Some(checkout_url),
+                    credits,
+                    message: Some(
+                        "CHECKOUT_SESSION_CREATED: stripe checkout session created".to_string(),
+                    ),
+                })
+            }
+        }
+    }
+
+    /// Point the organization's Stripe customer at a new owner after an
+    /// ownership transfer, so receipts and portal links reach them. The
+    /// `billing_customers` row stays keyed by organization; the customer's
+    /// email and `owner_user_id` metadata move. A no-op under the mock
+    /// provider or before the organization has a Stripe customer.
+    pub async fn reassign_org_billing_customer(
+        &self,
+        organization_id: &str,
+        owner_user_id: &str,
+    ) -> Result<(), AppError> {
+        if !matches!(resolve_billing_provider()?, BillingProvider::Stripe) {
+            return Ok(());
+        }
+        let Some(customer_id) = self.find_stripe_customer_for_org(organization_id).await? else {
+            return Ok(());
+        };
+
+        let secret_key = resolve_stripe_secret_key()?;
+        let api_base_url = resolve_stripe_api_base_url()?;
+        let endpoint = format!(
+            "{}/v1/customers/{customer_id}",
+            api_base_url.trim_end_matches('/')
+        );
+        let mut form = vec![
+            ("metadata[organization_id]", organization_id.to_string()),
+            ("metadata[owner_user_id]", owner_user_id.to_string()),
+        ];
+        if let Some(email) = self.find_user_email(owner_user_id).await? {
+            form.push(("email", email));
+        }
+        read_stripe_json(
+            reqwest::Client::new()
+                .post(&endpoint)
+                .bearer_auth(&secret_key)
+                .form(&form)
+                .send()
+                .await,
+            "STRIPE_CUSTOMER_UPDATE_FAILED",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Invoices of the caller's Stripe customer, or of the organization's
+    /// when `organizationId` is given (owners, admins and managers only).
+    /// Newest first; empty until a checkout has created a customer.
+    pub async fn list_invoices(
+        &self,
+        user_id: &str,
+        query: &InvoiceQuery,
+    ) -> Result<InvoiceListResponse, AppError> {
+        if !FeatureFlagService::new().is_billing_enabled() {
+            return Err(AppError::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                anyhow::anyhow!("BILLING_DISABLED: billing feature is disabled"),
+            ));
+        }
+
+        let limit = query
+            .limit
+            .unwrap_or(INVOICE_LIST_LIMIT_DEFAULT)
+            .clamp(1, INVOICE_LIST_LIMIT_MAX);
+        let organization_id = query.organization_id.as_deref().and_then(trim_non_empty);
+        if let Some(organization_id) = organization_id.as_deref() {
+            let role = self
+                .find_org_role_for_user(organization_id, user_id)
+                .await?;
+            if !matches!(role.as_deref(), Some("owner" | "admin" | "manager")) {
+                return Err(AppError::new(
+                    StatusCode::FORBIDDEN,
+                    anyhow::anyhow!(
+                        "FORBIDDEN_ROLE: insufficient permission to view organization invoices"
+                    ),
+                ));
+            }
+        }
+
+        match resolve_billing_provider()? {
+            BillingProvider::Mock => Ok(InvoiceListResponse {
+                mode: "mock".to_string(),
+                invoices: self
+                    .list_mock_invoices(user_id, organization_id.as_deref(), limit)
+                    .await?,
+            }),
+            BillingProvider::Stripe => {
+                let customer_id = match organization_id.as_deref() {
+                    Some(organization_id) => {
+                        self.find_stripe_customer_for_org(organization_id).await?
+                    }
+                    None => self.find_stripe_customer_for_user(user_id).await?,
+                };
+                let invoices = match customer_id {
+                    Some(customer_id) => self.list_stripe_invoices(&customer_id, limit).await?,
+                    None => Vec::new(),
+                };
+                Ok(InvoiceListResponse {
+                    mode: "stripe".to_string(),
+                    invoices,
+                })
+            }
+        }
+    }
+
     pub async fn create_billing_portal_session(
         &self,
         user_id: &str,
@@ -1621,12 +2620,167 @@ impl BillingService {
         })?;
         verify_stripe_webhook_signature(payload, signature_header, &stripe_webhook_secret)?;
 
+        self.ingest_stripe_event(payload, "webhook").await
+    }
+
+    /// Record and process an event file without a signature, for debugging
+    /// webhook handling locally. Only the mock provider accepts imports.
+    pub async fn import_stripe_event(
+        &self,
+        payload: &[u8],
+    ) -> Result<StripeWebhookResponse, AppError> {
+        if !matches!(resolve_billing_provider()?, BillingProvider::Mock) {
+            return Err(client_error(
+                "BILLING_PROVIDER_UNSUPPORTED: importing stripe events requires BILLING_PROVIDER=mock",
+            ));
+        }
+
+        self.ingest_stripe_event(payload, "import").await
+    }
+
+    /// Webhook events that are not processed yet: `failed` ones and those
+    /// stuck in `received`, oldest first. `status` narrows to one of the two.
+    pub async fn list_unprocessed_stripe_events(
+        &self,
+        status: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<StripeEventSummary>, AppError> {
+        let statuses: Vec<String> = match status {
+            None => vec!["failed".to_string(), "received".to_string()],
+            Some(status @ ("failed" | "received")) => vec![status.to_string()],
+            Some(other) => {
+                return Err(client_error(format!(
+                    "STRIPE_EVENT_STATUS_INVALID: expected failed or received, got {other}"
+                )))
+            }
+        };
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                event_id, event_type, status, source, last_error, replay_count,
+                received_at, processed_at
+            FROM stripe_events
+            WHERE status = ANY($1)
+            ORDER BY received_at ASC
+            LIMIT $2
+            "#,
+        )
+        .bind(&statuses)
+        .bind(limit.clamp(1, STRIPE_EVENT_LIST_LIMIT_MAX))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!("Failed to list Stripe events: {error}"))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| StripeEventSummary {
+                event_id: row.try_get("event_id").unwrap_or_default(),
+                event_type: row.try_get("event_type").unwrap_or_default(),
+                status: row.try_get("status").unwrap_or_default(),
+                source: row.try_get("source").unwrap_or_default(),
+                last_error: row.try_get("last_error").ok().flatten(),
+                replay_count: row.try_get("replay_count").unwrap_or(0),
+                received_at: row
+                    .try_get::<DateTime<Utc>, _>("received_at")
+                    .map(|value| value.to_rfc3339())
+                    .unwrap_or_default(),
+                processed_at: row
+                    .try_get::<Option<DateTime<Utc>>, _>("processed_at")
+                    .ok()
+                    .flatten()
+                    .map(|value| value.to_rfc3339()),
+            })
+            .collect())
+    }
+
+    /// Run stored events through the webhook handlers again. Processed
+    /// events are skipped, so replaying the same ids twice is harmless, and
+    /// so are `received` events the live webhook may still be handling.
+    pub async fn replay_stripe_events(
+        &self,
+        event_ids: &[String],
+    ) -> Result<Vec<StripeEventReplayResult>, AppError> {
+        let mut results = Vec::with_capacity(event_ids.len());
+        for event_id in event_ids {
+            results.push(self.replay_stripe_event(event_id).await?);
+        }
+        Ok(results)
+    }
+
+    async fn replay_stripe_event(
+        &self,
+        event_id: &str,
+    ) -> Result<StripeEventReplayResult, AppError> {
+        let claimed = sqlx::query(
+            r#"
+            UPDATE stripe_events
+            SET replay_count = replay_count + 1
+            WHERE event_id = $1
+              AND (
+                  status = 'failed'
+                  OR (status = 'received' AND received_at < NOW() - make_interval(secs => $2))
+              )
+            RETURNING payload
+            "#,
+        )
+        .bind(event_id)
+        .bind(STRIPE_EVENT_REPLAY_MIN_AGE_SECONDS as f64)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!("Failed to claim Stripe event for replay: {error}"))?;
+        let Some(claimed) = claimed else {
+            return Ok(StripeEventReplayResult {
+                event_id: event_id.to_string(),
+                outcome: "skipped".to_string(),
+                error: None,
+            });
+        };
+
+        let payload: Value = claimed
+            .try_get("payload")
+            .map_err(|error| anyhow::anyhow!("Failed to read Stripe event payload: {error}"))?;
+        let processed = match serde_json::from_value::<StripeEventEnvelope>(payload) {
+            Ok(event) => self.process_stripe_event(&event).await,
+            Err(error) => Err(client_error(format!(
+                "STRIPE_WEBHOOK_PAYLOAD_INVALID: {error}"
+            ))),
+        };
+
+        match processed {
+            Ok(()) => {
+                self.mark_stripe_event_processed(event_id).await?;
+                Ok(StripeEventReplayResult {
+                    event_id: event_id.to_string(),
+                    outcome: "processed".to_string(),
+                    error: None,
+                })
+            }
+            Err(process_error) => {
+                let error_message = format!("{process_error:?}");
+                self.mark_stripe_event_failed(event_id, &error_message)
+                    .await?;
+                Ok(StripeEventReplayResult {
+                    event_id: event_id.to_string(),
+                    outcome: "failed".to_string(),
+                    error: Some(error_message),
+                })
+            }
+        }
+    }
+
+    async fn ingest_stripe_event(
+        &self,
+        payload: &[u8],
+        source: &str,
+    ) -> Result<StripeWebhookResponse, AppError> {
         let payload_json = serde_json::from_slice::<Value>(payload)
             .map_err(|error| client_error(format!("STRIPE_WEBHOOK_PAYLOAD_INVALID: {}", error)))?;
         let event = serde_json::from_value::<StripeEventEnvelope>(payload_json.clone())
             .map_err(|error| client_error(format!("STRIPE_WEBHOOK_PAYLOAD_INVALID: {}", error)))?;
 
-        let inserted = self.record_stripe_event(&event, &payload_json).await?;
+        let inserted = self
+            .record_stripe_event(&event, &payload_json, source)
+            .await?;
         if !inserted {
             return Ok(StripeWebhookResponse {
                 received: true,
@@ -1659,10 +2813,12 @@ impl BillingService {
 #[cfg(test)]
 mod tests {
     use super::{
-        build_mock_team_checkout_url, compute_stripe_signature, parse_billing_provider,
-        parse_team_seat_quantity, resolve_checkout_return_urls_from_options,
-        verify_stripe_webhook_signature, BillingProvider,
+        build_mock_team_checkout_url, compute_stripe_signature, find_team_subscription_item_id,
+        is_credit_purchase, mock_invoice_status, parse_billing_provider, parse_stripe_invoice,
+        parse_team_seat_quantity, resolve_checkout_return_urls_from_options, subscription_access,
+        verify_stripe_webhook_signature, BillingProvider, SubscriptionAccess,
     };
+    use chrono::{Duration, TimeZone, Utc};
     use serde_json::json;
 
     #[test]
@@ -1771,4 +2927,163 @@ mod tests {
         });
         assert_eq!(parse_team_seat_quantity(&object_from_items), Some(10));
     }
+
+    #[test]
+    fn team_subscription_item_matches_team_price() {
+        let subscription = json!({
+            "items": {
+                "data": [
+                    { "id": "si_addon", "price": { "id": "price_addon" } },
+                    { "id": "si_team", "price": { "id": "price_team" } }
+                ]
+            }
+        });
+        assert_eq!(
+            find_team_subscription_item_id(&subscription, Some("price_team")),
+            Some("si_team".to_string())
+        );
+        assert_eq!(find_team_subscription_item_id(&subscription, None), None);
+
+        let single_item = json!({
+            "items": {
+                "data": [
+                    { "id": "si_only", "price": { "id": "price_legacy" } }
+                ]
+            }
+        });
+        assert_eq!(
+            find_team_subscription_item_id(&single_item, Some("price_team")),
+            Some("si_only".to_string())
+        );
+    }
+
+    #[test]
+    fn stripe_invoice_period_prefers_line_item_period() {
+        let invoice = parse_stripe_invoice(&json!({
+            "id": "in_123",
+            "number": "PMJ-0001",
+            "status": "paid",
+            "currency": "jpy",
+            "amount_due": 9000,
+            "amount_paid": 9000,
+            "created": 1_760_000_000,
+            "period_start": 1_757_000_000,
+            "period_end": 1_760_000_000,
+            "hosted_invoice_url": "https://invoice.stripe.com/i/in_123",
+            "invoice_pdf": "https://pay.stripe.com/invoice/in_123/pdf",
+            "lines": {
+                "data": [
+                    { "period": { "start": 1_760_000_000, "end": 1_762_592_000 } }
+                ]
+            }
+        }))
+        .expect("invoice");
+        assert_eq!(invoice.number.as_deref(), Some("PMJ-0001"));
+        assert_eq!(invoice.amount_paid, 9000);
+        assert_eq!(
+            invoice.period_start.as_deref(),
+            Some("2025-10-09T08:53:20+00:00")
+        );
+        assert_eq!(
+            invoice.period_end.as_deref(),
+            Some("2025-11-08T08:53:20+00:00")
+        );
+        assert_eq!(
+            invoice.invoice_pdf_url.as_deref(),
+            Some("https://pay.stripe.com/invoice/in_123/pdf")
+        );
+
+        let without_lines = parse_stripe_invoice(&json!({
+            "id": "in_456",
+            "period_start": 1_757_000_000,
+            "period_end": 1_760_000_000
+        }))
+        .expect("invoice without lines");
+        assert_eq!(without_lines.status, "draft");
+        assert_eq!(
+            without_lines.period_start.as_deref(),
+            Some("2025-09-04T15:33:20+00:00")
+        );
+        assert!(parse_stripe_invoice(&json!({ "status": "paid" })).is_none());
+    }
+
+    #[test]
+    fn credit_purchase_requires_payment_mode_and_purchase_type() {
+        assert!(is_credit_purchase(&json!({
+            "mode": "payment",
+            "metadata": { "purchase_type": "credits" }
+        })));
+        assert!(!is_credit_purchase(&json!({
+            "mode": "subscription",
+            "metadata": { "purchase_type": "credits" }
+        })));
+        assert!(!is_credit_purchase(&json!({
+            "mode": "payment",
+            "metadata": {}
+        })));
+    }
+
+    #[test]
+    fn cancel_at_period_end_keeps_access_until_period_end() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 17, 12, 0, 0).unwrap();
+        let period_end = now + Duration::days(12);
+        let grace = Duration::days(7);
+
+        assert_eq!(
+            subscription_access("active", None, false, Some(period_end), grace, now),
+            SubscriptionAccess::Entitled { valid_until: None }
+        );
+        assert_eq!(
+            subscription_access("active", None, true, Some(period_end), grace, now),
+            SubscriptionAccess::Entitled {
+                valid_until: Some(period_end)
+            }
+        );
+        assert_eq!(
+            subscription_access("canceled", None, true, Some(period_end), grace, now),
+            SubscriptionAccess::Lapsed
+        );
+    }
+
+    #[test]
+    fn past_due_keeps_access_for_grace_window() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 17, 12, 0, 0).unwrap();
+        let grace = Duration::days(7);
+        let period_end = Some(now + Duration::days(20));
+
+        let since = now - Duration::days(2);
+        assert_eq!(
+            subscription_access("past_due", Some(since), false, period_end, grace, now),
+            SubscriptionAccess::Entitled {
+                valid_until: Some(since + grace)
+            }
+        );
+
+        let cancel_end = now + Duration::days(1);
+        assert_eq!(
+            subscription_access("past_due", Some(since), true, Some(cancel_end), grace, now),
+            SubscriptionAccess::Entitled {
+                valid_until: Some(cancel_end)
+            }
+        );
+
+        let long_ago = now - Duration::days(8);
+        assert_eq!(
+            subscription_access("past_due", Some(long_ago), false, period_end, grace, now),
+            SubscriptionAccess::Lapsed
+        );
+        assert_eq!(
+            subscription_access("past_due", None, false, period_end, Duration::zero(), now),
+            SubscriptionAccess::Lapsed
+        );
+    }
+
+    #[test]
+    fn mock_invoice_status_follows_subscription_state() {
+        assert_eq!(mock_invoice_status("active"), ("paid", true));
+        assert_eq!(mock_invoice_status("trialing"), ("paid", true));
+        assert_eq!(mock_invoice_status("past_due"), ("open", false));
+        assert_eq!(mock_invoice_status("incomplete"), ("open", false));
+        assert_eq!(mock_invoice_status("canceled"), ("void", false));
+    }
 }
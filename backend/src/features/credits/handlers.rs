@@ -1,10 +1,17 @@
-use axum::{extract::State, Json};
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
 
-use crate::error::AppError;
+use crate::error::{client_error, AppError};
 use crate::middleware::auth::AuthUser;
 use crate::state::SharedState;
 
-use super::models::CreditBalanceResponse;
+use super::models::{
+    CreditBalanceResponse, CreditLedgerQuery, CreditUsageBreakdown, CreditUsageQuery,
+};
 
 #[utoipa::path(
     get,
@@ -22,3 +29,130 @@ pub async fn get_my_credits(
         .await?;
     Ok(Json(response))
 }
+
+#[utoipa::path(
+    get,
+    path = "/me/credits/ledger",
+    params(
+        ("limit" = Option<i64>, Query, description = "Page size (default 50, max 200)"),
+        ("offset" = Option<i64>, Query, description = "Entries to skip"),
+        ("from" = Option<String>, Query, description = "First day, YYYY-MM-DD"),
+        ("to" = Option<String>, Query, description = "Last day, YYYY-MM-DD"),
+        ("reason" = Option<String>, Query, description = "Only entries with this reason"),
+        ("format" = Option<String>, Query, description = "`json` (default) or `csv`")
+    ),
+    responses(
+        (status = 200, body = CreditLedgerPage),
+        (status = 200, content_type = "text/csv", body = String)
+    )
+)]
+pub async fn get_my_credit_ledger(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Query(query): Query<CreditLedgerQuery>,
+) -> Result<Response, AppError> {
+    let credits = state.services().credits();
+    match query.format.as_deref().unwrap_or("json") {
+        "json" => Ok(Json(credits.list_my_ledger(&auth.user_id, &query).await?).into_response()),
+        "csv" => Ok(csv_response(
+            credits.export_my_ledger_csv(&auth.user_id, &query).await?,
+        )),
+        other => Err(client_error(format!("unsupported export format: {other}"))),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/me/credits/usage",
+    params(
+        ("from" = Option<String>, Query, description = "First day, YYYY-MM-DD (default: 29 days before `to`)"),
+        ("to" = Option<String>, Query, description = "Last day, YYYY-MM-DD (default: today)")
+    ),
+    responses((status = 200, body = CreditUsageBreakdown))
+)]
+pub async fn get_my_credit_usage(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Query(query): Query<CreditUsageQuery>,
+) -> Result<Json<CreditUsageBreakdown>, AppError> {
+    let usage = state
+        .services()
+        .credits()
+        .get_my_usage(&auth.user_id, &query)
+        .await?;
+    Ok(Json(usage))
+}
+
+#[utoipa::path(
+    get,
+    path = "/organizations/current/credits/ledger",
+    params(
+        ("limit" = Option<i64>, Query, description = "Page size (default 50, max 200)"),
+        ("offset" = Option<i64>, Query, description = "Entries to skip"),
+        ("from" = Option<String>, Query, description = "First day, YYYY-MM-DD"),
+        ("to" = Option<String>, Query, description = "Last day, YYYY-MM-DD"),
+        ("reason" = Option<String>, Query, description = "Only entries with this reason"),
+        ("format" = Option<String>, Query, description = "`json` (default) or `csv`")
+    ),
+    responses(
+        (status = 200, body = CreditLedgerPage),
+        (status = 200, content_type = "text/csv", body = String)
+    )
+)]
+pub async fn get_organization_credit_ledger(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Query(query): Query<CreditLedgerQuery>,
+) -> Result<Response, AppError> {
+    let credits = state.services().credits();
+    match query.format.as_deref().unwrap_or("json") {
+        "json" => Ok(Json(
+            credits
+                .list_organization_ledger(&auth.user_id, &query)
+                .await?,
+        )
+        .into_response()),
+        "csv" => Ok(csv_response(
+            credits
+                .export_organization_ledger_csv(&auth.user_id, &query)
+                .await?,
+        )),
+        other => Err(client_error(format!("unsupported export format: {other}"))),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/organizations/current/credits/usage",
+    params(
+        ("from" = Option<String>, Query, description = "First day, YYYY-MM-DD (default: 29 days before `to`)"),
+        ("to" = Option<String>, Query, description = "Last day, YYYY-MM-DD (default: today)")
+    ),
+    responses((status = 200, body = CreditUsageBreakdown))
+)]
+pub async fn get_organization_credit_usage(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Query(query): Query<CreditUsageQuery>,
+) -> Result<Json<CreditUsageBreakdown>, AppError> {
+    let usage = state
+        .services()
+        .credits()
+        .get_organization_usage(&auth.user_id, &query)
+        .await?;
+    Ok(Json(usage))
+}
+
+fn csv_response(body: String) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"credit-ledger.csv\"",
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
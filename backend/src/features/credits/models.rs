@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -22,9 +23,30 @@ pub struct CreditLedgerEntry {
     pub reason: String,
     pub reference_type: Option<String>,
     pub reference_id: Option<String>,
+    pub actor_user_id: Option<String>,
     pub occurred_at: String,
 }
 
+/// What a ledger entry is for and which user caused it.
+#[derive(Debug, Clone, Copy)]
+pub struct LedgerReference<'a> {
+    pub reason: &'a str,
+    pub reference_type: Option<&'a str>,
+    pub reference_id: Option<&'a str>,
+    pub actor_user_id: Option<&'a str>,
+}
+
+/// Wallet charged for a user's usage together with the plan allowances that
+/// apply to it.
+#[derive(Debug, Clone)]
+pub struct CreditScope {
+    pub scope_type: &'static str,
+    pub scope_id: String,
+    pub actor_user_id: String,
+    pub monthly_credits: i32,
+    pub max_daily_credits: Option<i32>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CreditBalanceResponse {
@@ -32,3 +54,73 @@ pub struct CreditBalanceResponse {
     pub monthly_remaining: i32,
     pub purchased_remaining: i32,
 }
+
+/// Ledger rows to read: `[from, until)` in UTC, optionally one reason and
+/// one member.
+#[derive(Debug, Clone, Default)]
+pub struct LedgerFilter {
+    pub from: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub reason: Option<String>,
+    pub actor_user_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreditLedgerQuery {
+    /// Page size, 50 by default and at most 200. Ignored for CSV export.
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    /// First day to include, `YYYY-MM-DD` (UTC).
+    pub from: Option<String>,
+    /// Last day to include, `YYYY-MM-DD` (UTC).
+    pub to: Option<String>,
+    pub reason: Option<String>,
+    /// `json` (default) or `csv`.
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreditLedgerPage {
+    pub entries: Vec<CreditLedgerEntry>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreditUsageQuery {
+    /// First day to include, `YYYY-MM-DD` (UTC). Defaults to 29 days before `to`.
+    pub from: Option<String>,
+    /// Last day to include, `YYYY-MM-DD` (UTC). Defaults to today.
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreditUsageBucket {
+    pub key: String,
+    pub credits: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreditMemberUsage {
+    pub user_id: Option<String>,
+    pub name: Option<String>,
+    pub credits: i64,
+}
+
+/// Debited credits in a date range grouped by day, reason and member.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreditUsageBreakdown {
+    pub from: String,
+    pub to: String,
+    pub total_debited: i64,
+    pub by_day: Vec<CreditUsageBucket>,
+    pub by_reason: Vec<CreditUsageBucket>,
+    pub by_member: Vec<CreditMemberUsage>,
+}
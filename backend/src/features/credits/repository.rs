@@ -1,4 +1,7 @@
-use super::models::{CreditBalanceResponse, CreditLedgerEntry, CreditWallet};
+use super::models::{
+    CreditBalanceResponse, CreditLedgerEntry, CreditMemberUsage, CreditUsageBucket, CreditWallet,
+    LedgerFilter, LedgerReference,
+};
 use anyhow::{Context, Result};
 use chrono::{DateTime, Duration, Utc};
 use sqlx::{postgres::PgRow, PgPool, Postgres, Row, Transaction};
@@ -118,7 +121,31 @@ impl CreditWalletRepository {
             monthly_reset_at: Some(reset_at.to_rfc3339()),
         };
 
-        self.create_wallet(&wallet).await
+        let mut tx = self.pool.begin().await?;
+        self.create_wallet_in_tx(&mut tx, &wallet).await?;
+        // The ledger only accepts positive amounts; an empty allowance is
+        // visible from the wallet alone.
+        if monthly_credits > 0 {
+            sqlx::query(
+                r#"
+                INSERT INTO credit_ledger (
+                    id, wallet_id, direction, amount, reason, reference_type, reference_id
+                )
+                VALUES ($1, $2, 'credit', $3, 'opening_allowance', 'wallet', $2)
+                "#,
+            )
+            .bind(format!("ledger-{}", uuid::Uuid::new_v4()))
+            .bind(&wallet.id)
+            .bind(monthly_credits)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to insert opening allowance ledger entry")?;
+        }
+        tx.commit().await?;
+
+        self.get_wallet(scope_type, scope_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to retrieve created wallet"))
     }
 
     pub async fn debit_in_tx(
@@ -126,9 +153,7 @@ impl CreditWalletRepository {
         tx: &mut Transaction<'_, Postgres>,
         wallet_id: &str,
         amount: i32,
-        reason: &str,
-        reference_type: Option<&str>,
-        reference_id: Option<&str>,
+        reference: LedgerReference<'_>,
     ) -> Result<CreditLedgerEntry> {
         let wallet = sqlx::query(
             r#"
@@ -173,34 +198,87 @@ impl CreditWalletRepository {
         sqlx::query(
             r#"
             INSERT INTO credit_ledger (
-                id, wallet_id, direction, amount, reason, reference_type, reference_id, occurred_at
+                id, wallet_id, direction, amount, reason, reference_type, reference_id,
+                actor_user_id, occurred_at
             )
-            VALUES ($1, $2, 'debit', $3, $4, $5, $6, $7)
+            VALUES ($1, $2, 'debit', $3, $4, $5, $6, $7, $8)
             "#,
         )
         .bind(&ledger_id)
         .bind(wallet_id)
         .bind(amount)
-        .bind(reason)
-        .bind(reference_type)
-        .bind(reference_id)
+        .bind(reference.reason)
+        .bind(reference.reference_type)
+        .bind(reference.reference_id)
+        .bind(reference.actor_user_id)
         .bind(occurred_at)
         .execute(&mut **tx)
         .await
         .context("Failed to insert credit ledger entry")?;
 
-        Ok(CreditLedgerEntry {
-            id: ledger_id,
-            wallet_id: wallet_id.to_string(),
-            direction: "debit".to_string(),
+        Ok(Self::ledger_entry(
+            ledger_id,
+            wallet_id,
+            "debit",
             amount,
-            reason: reason.to_string(),
-            reference_type: reference_type.map(str::to_string),
-            reference_id: reference_id.map(str::to_string),
-            occurred_at: occurred_at.to_rfc3339(),
+            reference,
+            occurred_at,
+        ))
+    }
+
+    /// Lock the wallet row for the rest of the transaction and read its balance.
+    pub async fn lock_balance_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        wallet_id: &str,
+    ) -> Result<CreditBalanceResponse> {
+        let wallet = sqlx::query(
+            r#"
+            SELECT monthly_credits, purchased_credits
+            FROM credit_wallets
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(wallet_id)
+        .fetch_one(&mut **tx)
+        .await
+        .context("Failed to lock credit wallet")?;
+
+        let monthly_remaining: i32 = wallet.try_get("monthly_credits").unwrap_or(0);
+        let purchased_remaining: i32 = wallet.try_get("purchased_credits").unwrap_or(0);
+
+        Ok(CreditBalanceResponse {
+            available: monthly_remaining + purchased_remaining,
+            monthly_remaining,
+            purchased_remaining,
         })
     }
 
+    pub async fn daily_usage_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        wallet_id: &str,
+    ) -> Result<i32> {
+        let result = sqlx::query(
+            r#"
+            SELECT COALESCE(SUM(amount), 0) as total
+            FROM credit_ledger
+            WHERE wallet_id = $1
+                AND direction = 'debit'
+                AND occurred_at >= CURRENT_DATE
+                AND occurred_at < CURRENT_DATE + INTERVAL '1 day'
+            "#,
+        )
+        .bind(wallet_id)
+        .fetch_one(&mut **tx)
+        .await
+        .context("Failed to fetch daily usage")?;
+
+        let total: i64 = result.try_get("total").unwrap_or(0_i64);
+        Ok(total as i32)
+    }
+
     pub async fn get_balance(&self, wallet_id: &str) -> Result<CreditBalanceResponse> {
         let wallet = sqlx::query(
             r#"
@@ -245,22 +323,369 @@ impl CreditWalletRepository {
         Ok(total as i32)
     }
 
-    #[allow(dead_code)]
-    pub async fn reset_expired_wallets(&self) -> Result<u64> {
-        let result = sqlx::query(
+    pub async fn list_wallets_due_for_reset(&self) -> Result<Vec<String>> {
+        let rows = sqlx::query(
             r#"
-            UPDATE credit_wallets
-            SET monthly_credits = monthly_credits,
-                monthly_reset_at = monthly_reset_at + INTERVAL '1 month'
+            SELECT id
+            FROM credit_wallets
             WHERE monthly_reset_at IS NOT NULL
                 AND monthly_reset_at <= NOW()
+            ORDER BY monthly_reset_at ASC
             "#,
         )
-        .execute(&self.pool)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list wallets due for reset")?;
+
+        Ok(rows.into_iter().map(|row| row.get("id")).collect())
+    }
+
+    /// Lock a wallet that is still due for its monthly reset. Returns `None`
+    /// when another run already reset it.
+    pub async fn lock_due_wallet_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        wallet_id: &str,
+    ) -> Result<Option<(CreditWallet, DateTime<Utc>)>> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                id,
+                scope_type,
+                scope_id,
+                monthly_credits,
+                purchased_credits,
+                monthly_reset_at AS due_at,
+                to_char(monthly_reset_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as monthly_reset_at
+            FROM credit_wallets
+            WHERE id = $1
+                AND monthly_reset_at IS NOT NULL
+                AND monthly_reset_at <= NOW()
+            FOR UPDATE
+            "#,
+        )
+        .bind(wallet_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .context("Failed to lock credit wallet for reset")?;
+
+        Ok(row.map(|row| {
+            let due_at: DateTime<Utc> = row.get("due_at");
+            (Self::map_wallet_row(row), due_at)
+        }))
+    }
+
+    /// Replace the monthly allowance, move the reset date forward and record
+    /// a `monthly_reset` ledger entry for the period that started at `due_at`.
+    pub async fn apply_monthly_reset_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        wallet: &CreditWallet,
+        monthly_credits: i32,
+        due_at: DateTime<Utc>,
+        next_reset_at: DateTime<Utc>,
+        plan_code: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE credit_wallets
+            SET monthly_credits = $1,
+                monthly_reset_at = $2
+            WHERE id = $3
+            "#,
+        )
+        .bind(monthly_credits)
+        .bind(next_reset_at)
+        .bind(&wallet.id)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to reset credit wallet")?;
+
+        // The ledger only accepts positive amounts; an empty allowance is
+        // visible from the wallet alone.
+        if monthly_credits > 0 {
+            let metadata = serde_json::json!({
+                "planCode": plan_code,
+                "previousMonthlyCredits": wallet.monthly_credits,
+                "nextResetAt": next_reset_at.to_rfc3339(),
+            });
+            sqlx::query(
+                r#"
+                INSERT INTO credit_ledger (
+                    id, wallet_id, direction, amount, reason, reference_type, reference_id,
+                    occurred_at, metadata
+                )
+                VALUES ($1, $2, 'credit', $3, 'monthly_reset', 'reset_period', $4, NOW(), $5)
+                ON CONFLICT DO NOTHING
+                "#,
+            )
+            .bind(format!("ledger-{}", uuid::Uuid::new_v4()))
+            .bind(&wallet.id)
+            .bind(monthly_credits)
+            .bind(due_at.format("%Y-%m-%d").to_string())
+            .bind(metadata)
+            .execute(&mut **tx)
+            .await
+            .context("Failed to insert monthly reset ledger entry")?;
+        }
+
+        Ok(())
+    }
+
+    /// Add purchased credits and record the matching ledger entry. Returns
+    /// `None` when a purchase with the same reference was already credited.
+    pub async fn add_purchased_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        wallet_id: &str,
+        amount: i32,
+        reference: LedgerReference<'_>,
+        metadata: serde_json::Value,
+    ) -> Result<Option<CreditLedgerEntry>> {
+        let ledger_id = format!("ledger-{}", uuid::Uuid::new_v4());
+        let occurred_at = Utc::now();
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO credit_ledger (
+                id, wallet_id, direction, amount, reason, reference_type, reference_id,
+                actor_user_id, occurred_at, metadata
+            )
+            VALUES ($1, $2, 'credit', $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(&ledger_id)
+        .bind(wallet_id)
+        .bind(amount)
+        .bind(reference.reason)
+        .bind(reference.reference_type)
+        .bind(reference.reference_id)
+        .bind(reference.actor_user_id)
+        .bind(occurred_at)
+        .bind(metadata)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to insert purchase ledger entry")?;
+        if inserted.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE credit_wallets
+            SET purchased_credits = purchased_credits + $1
+            WHERE id = $2
+            "#,
+        )
+        .bind(amount)
+        .bind(wallet_id)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to add purchased credits")?;
+
+        Ok(Some(Self::ledger_entry(
+            ledger_id,
+            wallet_id,
+            "credit",
+            amount,
+            reference,
+            occurred_at,
+        )))
+    }
+
+    fn ledger_entry(
+        id: String,
+        wallet_id: &str,
+        direction: &str,
+        amount: i32,
+        reference: LedgerReference<'_>,
+        occurred_at: DateTime<Utc>,
+    ) -> CreditLedgerEntry {
+        CreditLedgerEntry {
+            id,
+            wallet_id: wallet_id.to_string(),
+            direction: direction.to_string(),
+            amount,
+            reason: reference.reason.to_string(),
+            reference_type: reference.reference_type.map(str::to_string),
+            reference_id: reference.reference_id.map(str::to_string),
+            actor_user_id: reference.actor_user_id.map(str::to_string),
+            occurred_at: occurred_at.to_rfc3339(),
+        }
+    }
+
+    fn map_ledger_row(row: PgRow) -> CreditLedgerEntry {
+        CreditLedgerEntry {
+            id: row.try_get("id").unwrap_or_default(),
+            wallet_id: row.try_get("wallet_id").unwrap_or_default(),
+            direction: row.try_get("direction").unwrap_or_default(),
+            amount: row.try_get("amount").unwrap_or(0),
+            reason: row.try_get("reason").unwrap_or_default(),
+            reference_type: row.try_get("reference_type").ok().flatten(),
+            reference_id: row.try_get("reference_id").ok().flatten(),
+            actor_user_id: row.try_get("actor_user_id").ok().flatten(),
+            occurred_at: row
+                .try_get::<DateTime<Utc>, _>("occurred_at")
+                .map(|value| value.to_rfc3339())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Ledger entries of a wallet, newest first. `actor_user_id` narrows the
+    /// list to one member's entries; `limit` of `None` returns every match.
+    pub async fn list_ledger(
+        &self,
+        wallet_id: &str,
+        filter: &LedgerFilter,
+        limit: Option<i64>,
+        offset: i64,
+    ) -> Result<Vec<CreditLedgerEntry>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, wallet_id, direction, amount, reason, reference_type, reference_id,
+                   actor_user_id, occurred_at
+            FROM credit_ledger
+            WHERE wallet_id = $1
+              AND ($2::TIMESTAMPTZ IS NULL OR occurred_at >= $2)
+              AND ($3::TIMESTAMPTZ IS NULL OR occurred_at < $3)
+              AND ($4::TEXT IS NULL OR reason = $4)
+              AND ($5::TEXT IS NULL OR actor_user_id = $5)
+            ORDER BY occurred_at DESC, id DESC
+            LIMIT $6 OFFSET $7
+            "#,
+        )
+        .bind(wallet_id)
+        .bind(filter.from)
+        .bind(filter.until)
+        .bind(filter.reason.as_deref())
+        .bind(filter.actor_user_id.as_deref())
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list credit ledger")?;
+
+        Ok(rows.into_iter().map(Self::map_ledger_row).collect())
+    }
+
+    pub async fn count_ledger(&self, wallet_id: &str, filter: &LedgerFilter) -> Result<i64> {
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS total
+            FROM credit_ledger
+            WHERE wallet_id = $1
+              AND ($2::TIMESTAMPTZ IS NULL OR occurred_at >= $2)
+              AND ($3::TIMESTAMPTZ IS NULL OR occurred_at < $3)
+              AND ($4::TEXT IS NULL OR reason = $4)
+              AND ($5::TEXT IS NULL OR actor_user_id = $5)
+            "#,
+        )
+        .bind(wallet_id)
+        .bind(filter.from)
+        .bind(filter.until)
+        .bind(filter.reason.as_deref())
+        .bind(filter.actor_user_id.as_deref())
+        .fetch_one(&self.pool)
+        .await
+        .context("Failed to count credit ledger")?;
+
+        Ok(row.try_get("total").unwrap_or(0))
+    }
+
+    /// Debited credits per UTC day and per reason for the filter's range.
+    pub async fn usage_buckets(
+        &self,
+        wallet_id: &str,
+        filter: &LedgerFilter,
+    ) -> Result<(Vec<CreditUsageBucket>, Vec<CreditUsageBucket>)> {
+        let by_day = sqlx::query(
+            r#"
+            SELECT to_char(occurred_at AT TIME ZONE 'UTC', 'YYYY-MM-DD') AS key,
+                   SUM(amount)::BIGINT AS credits
+            FROM credit_ledger
+            WHERE wallet_id = $1
+              AND direction = 'debit'
+              AND occurred_at >= $2 AND occurred_at < $3
+              AND ($4::TEXT IS NULL OR actor_user_id = $4)
+            GROUP BY 1
+            ORDER BY 1
+            "#,
+        )
+        .bind(wallet_id)
+        .bind(filter.from)
+        .bind(filter.until)
+        .bind(filter.actor_user_id.as_deref())
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to group credit usage by day")?;
+
+        let by_reason = sqlx::query(
+            r#"
+            SELECT reason AS key, SUM(amount)::BIGINT AS credits
+            FROM credit_ledger
+            WHERE wallet_id = $1
+              AND direction = 'debit'
+              AND occurred_at >= $2 AND occurred_at < $3
+              AND ($4::TEXT IS NULL OR actor_user_id = $4)
+            GROUP BY reason
+            ORDER BY credits DESC, reason
+            "#,
+        )
+        .bind(wallet_id)
+        .bind(filter.from)
+        .bind(filter.until)
+        .bind(filter.actor_user_id.as_deref())
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to group credit usage by reason")?;
+
+        let map_bucket = |row: PgRow| CreditUsageBucket {
+            key: row.try_get("key").unwrap_or_default(),
+            credits: row.try_get("credits").unwrap_or(0),
+        };
+        Ok((
+            by_day.into_iter().map(map_bucket).collect(),
+            by_reason.into_iter().map(map_bucket).collect(),
+        ))
+    }
+
+    /// Debited credits per member for the filter's range. Entries without a
+    /// known actor are grouped under `user_id = None`.
+    pub async fn usage_by_member(
+        &self,
+        wallet_id: &str,
+        filter: &LedgerFilter,
+    ) -> Result<Vec<CreditMemberUsage>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT l.actor_user_id, u.name, SUM(l.amount)::BIGINT AS credits
+            FROM credit_ledger l
+            LEFT JOIN users u ON u.id = l.actor_user_id
+            WHERE l.wallet_id = $1
+              AND l.direction = 'debit'
+              AND l.occurred_at >= $2 AND l.occurred_at < $3
+              AND ($4::TEXT IS NULL OR l.actor_user_id = $4)
+            GROUP BY l.actor_user_id, u.name
+            ORDER BY credits DESC, l.actor_user_id
+            "#,
+        )
+        .bind(wallet_id)
+        .bind(filter.from)
+        .bind(filter.until)
+        .bind(filter.actor_user_id.as_deref())
+        .fetch_all(&self.pool)
         .await
-        .context("Failed to reset expired wallets")?;
+        .context("Failed to group credit usage by member")?;
 
-        Ok(result.rows_affected())
+        Ok(rows
+            .into_iter()
+            .map(|row| CreditMemberUsage {
+                user_id: row.try_get("actor_user_id").ok().flatten(),
+                name: row.try_get("name").ok().flatten(),
+                credits: row.try_get("credits").unwrap_or(0),
+            })
+            .collect())
     }
 
     #[allow(dead_code)]
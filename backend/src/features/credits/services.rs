@@ -1,93 +1,124 @@
-use sqlx::PgPool;
+use chrono::{DateTime, Duration, Months, NaiveDate, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
 
-use crate::error::{anyhow_error, payment_required_error, too_many_requests_error, AppError};
+use crate::error::{
+    anyhow_error, client_error, forbidden_error, payment_required_error, too_many_requests_error,
+    AppError,
+};
+use crate::features::entitlements::models::EffectivePlan;
+use crate::features::entitlements::services::EntitlementService;
+use crate::features::organizations::services::OrganizationService;
+use crate::shared::admin_override::is_admin_override_user;
 
-use super::models::{CreditBalanceResponse, CreditLedgerEntry, CreditWallet};
+use super::models::{
+    CreditBalanceResponse, CreditLedgerEntry, CreditLedgerPage, CreditLedgerQuery, CreditScope,
+    CreditUsageBreakdown, CreditUsageQuery, CreditWallet, LedgerFilter, LedgerReference,
+};
 use super::repository::CreditWalletRepository;
 
+/// Credits charged for one agent reply.
+pub const AGENT_REPLY_CREDIT_COST: i32 = 1;
+/// Credits charged per evaluation sample; calibrated runs pay per sample.
+pub const EVALUATION_CREDIT_COST: i32 = 3;
+
+const LEDGER_PAGE_SIZE_DEFAULT: i64 = 50;
+const LEDGER_PAGE_SIZE_MAX: i64 = 200;
+const USAGE_RANGE_DAYS_DEFAULT: i64 = 30;
+const USAGE_RANGE_DAYS_MAX: i64 = 366;
+
+/// Wallet whose ledger a caller may read. Members of an organization plan
+/// only see their own entries of the shared wallet.
+struct LedgerView {
+    scope_type: &'static str,
+    scope_id: String,
+    actor_user_id: Option<String>,
+}
+
 #[derive(Clone)]
 pub struct CreditService {
     pool: PgPool,
 }
 
-#[allow(dead_code)]
 impl CreditService {
     pub fn new(pool: PgPool) -> Self {
         Self { pool }
     }
 
-    /// Consume credits from a wallet with daily limit checking
-    pub async fn consume_credit(
-        &self,
-        scope_type: &str,
-        scope_id: &str,
-        amount: i32,
-        reason: &str,
-        reference_type: Option<&str>,
-        reference_id: Option<&str>,
-        daily_limit: Option<i32>,
-    ) -> Result<CreditLedgerEntry, AppError> {
-        let wallet_repo = CreditWalletRepository::new(self.pool.clone());
+    /// Resolve the wallet a user's usage is charged to. Plans granted by an
+    /// organization draw from the organization wallet. Admin override users
+    /// are never charged.
+    pub fn scope_for(user_id: &str, effective_plan: &EffectivePlan) -> Option<CreditScope> {
+        if is_admin_override_user(user_id) {
+            return None;
+        }
 
-        // Get wallet
-        let wallet = wallet_repo
-            .get_wallet(scope_type, scope_id)
-            .await
-            .map_err(|e| anyhow_error(&format!("Failed to fetch wallet: {}", e)))?
-            .ok_or_else(|| anyhow_error("Wallet not found"))?;
+        let limits = EntitlementService::plan_limits(&effective_plan.plan_code);
+        let (scope_type, scope_id) = match &effective_plan.organization_id {
+            Some(organization_id) => ("organization", organization_id.clone()),
+            None => ("user", user_id.to_string()),
+        };
+
+        Some(CreditScope {
+            scope_type,
+            scope_id,
+            actor_user_id: user_id.to_string(),
+            monthly_credits: limits.monthly_credits,
+            max_daily_credits: limits.max_daily_credits,
+        })
+    }
 
-        // Check daily limit if specified
-        if let Some(limit) = daily_limit {
+    /// Fail before any model call when the scope cannot afford `amount`.
+    pub async fn ensure_available(&self, scope: &CreditScope, amount: i32) -> Result<(), AppError> {
+        let wallet_repo = CreditWalletRepository::new(self.pool.clone());
+        let wallet = self
+            .ensure_wallet(scope.scope_type, &scope.scope_id, scope.monthly_credits)
+            .await?;
+
+        if let Some(limit) = scope.max_daily_credits {
             let daily_usage = wallet_repo
                 .daily_usage(&wallet.id)
                 .await
-                .map_err(|e| anyhow_error(&format!("Failed to check daily usage: {}", e)))?;
-
-            if daily_usage + amount > limit {
-                return Err(too_many_requests_error(format!(
-                    "CREDIT_DAILY_LIMIT: Daily credit limit reached. Used: {}, Limit: {}",
-                    daily_usage, limit
-                )));
-            }
+                .map_err(|e| anyhow_error(format!("Failed to check daily usage: {e}")))?;
+            check_daily_limit(daily_usage, amount, limit)?;
         }
 
-        // Check balance
         let balance = wallet_repo
             .get_balance(&wallet.id)
             .await
-            .map_err(|e| anyhow_error(&format!("Failed to get balance: {}", e)))?;
-
-        if balance.available < amount {
-            return Err(payment_required_error(format!(
-                "CREDIT_EXHAUSTED: Insufficient credits. Available: {}, Required: {}",
-                balance.available, amount
-            )));
-        }
+            .map_err(|e| anyhow_error(format!("Failed to get balance: {e}")))?;
+        check_balance(&balance, amount)
+    }
 
-        // Debit in transaction
-        let mut tx = self
-            .pool
-            .begin()
-            .await
-            .map_err(|e| anyhow_error(&format!("Failed to begin transaction: {}", e)))?;
+    /// Debit `amount` from the scope's wallet inside the caller's transaction,
+    /// so the ledger entry commits together with the usage it references.
+    pub async fn consume_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        scope: &CreditScope,
+        amount: i32,
+        reason: &str,
+        reference_type: &str,
+        reference_id: &str,
+    ) -> Result<CreditLedgerEntry, AppError> {
+        let wallet_repo = CreditWalletRepository::new(self.pool.clone());
+        let wallet = self
+            .ensure_wallet(scope.scope_type, &scope.scope_id, scope.monthly_credits)
+            .await?;
 
-        let entry = wallet_repo
-            .debit_in_tx(
-                &mut tx,
-                &wallet.id,
-                amount,
+        debit_within_limits(
+            &wallet_repo,
+            tx,
+            &wallet.id,
+            amount,
+            LedgerReference {
                 reason,
-                reference_type,
-                reference_id,
-            )
-            .await
-            .map_err(|e| anyhow_error(&format!("Failed to debit credits: {}", e)))?;
-
-        tx.commit()
-            .await
-            .map_err(|e| anyhow_error(&format!("Failed to commit transaction: {}", e)))?;
-
-        Ok(entry)
+                reference_type: Some(reference_type),
+                reference_id: Some(reference_id),
+                actor_user_id: Some(&scope.actor_user_id),
+            },
+            scope.max_daily_credits,
+        )
+        .await
     }
 
     /// Ensure a wallet exists for the given scope
@@ -105,51 +136,541 @@ impl CreditService {
             .map_err(|e| anyhow_error(&format!("Failed to ensure wallet: {}", e)))
     }
 
-    /// Get balance for a user's wallet
-    pub async fn get_my_balance(
+    /// Credit a completed purchase to the scope's purchased balance. Replays
+    /// of the same reference are ignored and return `None`.
+    pub async fn grant_purchased_credits(
         &self,
         scope_type: &str,
         scope_id: &str,
-    ) -> Result<CreditBalanceResponse, AppError> {
+        amount: i32,
+        reference: LedgerReference<'_>,
+        metadata: serde_json::Value,
+    ) -> Result<Option<CreditLedgerEntry>, AppError> {
+        let plan_code = EntitlementService::new(self.pool.clone())
+            .plan_for_scope(scope_type, scope_id)
+            .await?;
+        let limits = EntitlementService::plan_limits(&plan_code);
+        let wallet = self
+            .ensure_wallet(scope_type, scope_id, limits.monthly_credits)
+            .await?;
+
         let wallet_repo = CreditWalletRepository::new(self.pool.clone());
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to begin transaction: {e}")))?;
+        let entry = wallet_repo
+            .add_purchased_in_tx(&mut tx, &wallet.id, amount, reference, metadata)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to credit purchase: {e}")))?;
+        tx.commit()
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to commit transaction: {e}")))?;
+
+        Ok(entry)
+    }
+
+    /// Reset monthly allowances for wallets past their reset date, using the
+    /// plan of each wallet's scope. Safe to run repeatedly or from several
+    /// processes: each wallet is re-checked under a row lock and the ledger
+    /// accepts one `monthly_reset` entry per period.
+    pub async fn run_monthly_reset(&self) -> Result<u64, AppError> {
+        let wallet_repo = CreditWalletRepository::new(self.pool.clone());
+        let entitlement_service = EntitlementService::new(self.pool.clone());
 
-        let wallet = wallet_repo
-            .get_wallet(scope_type, scope_id)
+        let wallet_ids = wallet_repo
+            .list_wallets_due_for_reset()
             .await
-            .map_err(|e| anyhow_error(&format!("Failed to fetch wallet: {}", e)))?
-            .ok_or_else(|| anyhow_error("Wallet not found"))?;
+            .map_err(|e| anyhow_error(format!("Failed to list wallets due for reset: {e}")))?;
 
-        wallet_repo
-            .get_balance(&wallet.id)
+        let mut reset_count = 0;
+        for wallet_id in wallet_ids {
+            let mut tx = self
+                .pool
+                .begin()
+                .await
+                .map_err(|e| anyhow_error(format!("Failed to begin transaction: {e}")))?;
+            let Some((wallet, due_at)) = wallet_repo
+                .lock_due_wallet_in_tx(&mut tx, &wallet_id)
+                .await
+                .map_err(|e| anyhow_error(format!("Failed to lock wallet: {e}")))?
+            else {
+                continue;
+            };
+
+            let plan_code = entitlement_service
+                .plan_for_scope(&wallet.scope_type, &wallet.scope_id)
+                .await?;
+            let limits = EntitlementService::plan_limits(&plan_code);
+            let next_reset_at = next_reset_at(due_at, Utc::now());
+
+            wallet_repo
+                .apply_monthly_reset_in_tx(
+                    &mut tx,
+                    &wallet,
+                    limits.monthly_credits,
+                    due_at,
+                    next_reset_at,
+                    plan_code.as_str(),
+                )
+                .await
+                .map_err(|e| anyhow_error(format!("Failed to reset wallet: {e}")))?;
+            tx.commit()
+                .await
+                .map_err(|e| anyhow_error(format!("Failed to commit transaction: {e}")))?;
+            reset_count += 1;
+        }
+
+        Ok(reset_count)
+    }
+
+    /// Ledger of the wallet that pays for the caller's usage.
+    pub async fn list_my_ledger(
+        &self,
+        user_id: &str,
+        query: &CreditLedgerQuery,
+    ) -> Result<CreditLedgerPage, AppError> {
+        let view = self.my_ledger_view(user_id).await?;
+        self.ledger_page(&view, query).await
+    }
+
+    pub async fn export_my_ledger_csv(
+        &self,
+        user_id: &str,
+        query: &CreditLedgerQuery,
+    ) -> Result<String, AppError> {
+        let view = self.my_ledger_view(user_id).await?;
+        self.ledger_csv(&view, query).await
+    }
+
+    pub async fn get_my_usage(
+        &self,
+        user_id: &str,
+        query: &CreditUsageQuery,
+    ) -> Result<CreditUsageBreakdown, AppError> {
+        let view = self.my_ledger_view(user_id).await?;
+        self.usage_breakdown(&view, query).await
+    }
+
+    /// Ledger of the caller's current organization wallet, for org managers.
+    pub async fn list_organization_ledger(
+        &self,
+        user_id: &str,
+        query: &CreditLedgerQuery,
+    ) -> Result<CreditLedgerPage, AppError> {
+        let view = self.organization_ledger_view(user_id).await?;
+        self.ledger_page(&view, query).await
+    }
+
+    pub async fn export_organization_ledger_csv(
+        &self,
+        user_id: &str,
+        query: &CreditLedgerQuery,
+    ) -> Result<String, AppError> {
+        let view = self.organization_ledger_view(user_id).await?;
+        self.ledger_csv(&view, query).await
+    }
+
+    pub async fn get_organization_usage(
+        &self,
+        user_id: &str,
+        query: &CreditUsageQuery,
+    ) -> Result<CreditUsageBreakdown, AppError> {
+        let view = self.organization_ledger_view(user_id).await?;
+        self.usage_breakdown(&view, query).await
+    }
+
+    async fn my_ledger_view(&self, user_id: &str) -> Result<LedgerView, AppError> {
+        let effective_plan = EntitlementService::new(self.pool.clone())
+            .resolve_effective_plan(user_id)
+            .await?;
+
+        Ok(match effective_plan.organization_id {
+            Some(organization_id) => LedgerView {
+                scope_type: "organization",
+                scope_id: organization_id,
+                actor_user_id: Some(user_id.to_string()),
+            },
+            None => LedgerView {
+                scope_type: "user",
+                scope_id: user_id.to_string(),
+                actor_user_id: None,
+            },
+        })
+    }
+
+    async fn organization_ledger_view(&self, user_id: &str) -> Result<LedgerView, AppError> {
+        let (organization, membership) = OrganizationService::new(self.pool.clone())
+            .resolve_current_org_context(user_id)
+            .await?;
+        if !matches!(membership.role.as_str(), "owner" | "admin" | "manager") {
+            return Err(forbidden_error(
+                "FORBIDDEN_ROLE: insufficient permission for organization credit history",
+            ));
+        }
+
+        Ok(LedgerView {
+            scope_type: "organization",
+            scope_id: organization.id,
+            actor_user_id: None,
+        })
+    }
+
+    async fn view_wallet(&self, view: &LedgerView) -> Result<Option<CreditWallet>, AppError> {
+        CreditWalletRepository::new(self.pool.clone())
+            .get_wallet(view.scope_type, &view.scope_id)
             .await
-            .map_err(|e| anyhow_error(&format!("Failed to get balance: {}", e)))
+            .map_err(|e| anyhow_error(format!("Failed to fetch wallet: {e}")))
     }
 
-    /// Run monthly reset for all wallets
-    #[allow(dead_code)]
-    pub async fn run_monthly_reset(&self) -> Result<u64, AppError> {
+    async fn ledger_page(
+        &self,
+        view: &LedgerView,
+        query: &CreditLedgerQuery,
+    ) -> Result<CreditLedgerPage, AppError> {
+        let filter = ledger_filter(view, query)?;
+        let limit = query
+            .limit
+            .unwrap_or(LEDGER_PAGE_SIZE_DEFAULT)
+            .clamp(1, LEDGER_PAGE_SIZE_MAX);
+        let offset = query.offset.unwrap_or(0).max(0);
+
+        let Some(wallet) = self.view_wallet(view).await? else {
+            return Ok(CreditLedgerPage {
+                entries: Vec::new(),
+                total: 0,
+                limit,
+                offset,
+            });
+        };
+
         let wallet_repo = CreditWalletRepository::new(self.pool.clone());
+        let entries = wallet_repo
+            .list_ledger(&wallet.id, &filter, Some(limit), offset)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to list credit ledger: {e}")))?;
+        let total = wallet_repo
+            .count_ledger(&wallet.id, &filter)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to count credit ledger: {e}")))?;
 
-        // This is a simplified version - in production, you'd want to:
-        // 1. Fetch wallets that need reset
-        // 2. For each wallet, determine correct monthly_credits from entitlement
-        // 3. Update wallet with new credits and reset date
-        // 4. Insert ledger entry for "monthly_reset"
+        Ok(CreditLedgerPage {
+            entries,
+            total,
+            limit,
+            offset,
+        })
+    }
 
-        wallet_repo
-            .reset_expired_wallets()
+    async fn ledger_csv(
+        &self,
+        view: &LedgerView,
+        query: &CreditLedgerQuery,
+    ) -> Result<String, AppError> {
+        let filter = ledger_filter(view, query)?;
+        let entries = match self.view_wallet(view).await? {
+            Some(wallet) => CreditWalletRepository::new(self.pool.clone())
+                .list_ledger(&wallet.id, &filter, None, 0)
+                .await
+                .map_err(|e| anyhow_error(format!("Failed to list credit ledger: {e}")))?,
+            None => Vec::new(),
+        };
+
+        render_ledger_csv(&entries)
+    }
+
+    async fn usage_breakdown(
+        &self,
+        view: &LedgerView,
+        query: &CreditUsageQuery,
+    ) -> Result<CreditUsageBreakdown, AppError> {
+        let (from, to) = usage_range(query, Utc::now().date_naive())?;
+        let mut breakdown = CreditUsageBreakdown {
+            from: from.to_string(),
+            to: to.to_string(),
+            total_debited: 0,
+            by_day: Vec::new(),
+            by_reason: Vec::new(),
+            by_member: Vec::new(),
+        };
+        let Some(wallet) = self.view_wallet(view).await? else {
+            return Ok(breakdown);
+        };
+
+        let filter = LedgerFilter {
+            from: Some(day_start(from)),
+            until: Some(day_start(to) + Duration::days(1)),
+            reason: None,
+            actor_user_id: view.actor_user_id.clone(),
+        };
+        let wallet_repo = CreditWalletRepository::new(self.pool.clone());
+        let (by_day, by_reason) = wallet_repo
+            .usage_buckets(&wallet.id, &filter)
             .await
-            .map_err(|e| anyhow_error(&format!("Failed to reset wallets: {}", e)))
+            .map_err(|e| anyhow_error(format!("Failed to load credit usage: {e}")))?;
+        let by_member = wallet_repo
+            .usage_by_member(&wallet.id, &filter)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to load credit usage: {e}")))?;
+
+        breakdown.total_debited = by_reason.iter().map(|bucket| bucket.credits).sum();
+        breakdown.by_day = by_day;
+        breakdown.by_reason = by_reason;
+        breakdown.by_member = by_member;
+        Ok(breakdown)
     }
 
     /// Get credits for a user (wrapper that resolves effective plan scope)
     pub async fn get_my_credits(&self, user_id: &str) -> Result<CreditBalanceResponse, AppError> {
-        let _ = user_id;
-        // Ultra-simple launch mode: app-level credit wallets are disabled.
-        Ok(CreditBalanceResponse {
-            available: 0,
-            monthly_remaining: 0,
-            purchased_remaining: 0,
-        })
+        let effective_plan = EntitlementService::new(self.pool.clone())
+            .resolve_effective_plan(user_id)
+            .await?;
+        let Some(scope) = Self::scope_for(user_id, &effective_plan) else {
+            return Ok(CreditBalanceResponse {
+                available: 0,
+                monthly_remaining: 0,
+                purchased_remaining: 0,
+            });
+        };
+
+        let wallet = self
+            .ensure_wallet(scope.scope_type, &scope.scope_id, scope.monthly_credits)
+            .await?;
+        CreditWalletRepository::new(self.pool.clone())
+            .get_balance(&wallet.id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to get balance: {e}")))
+    }
+}
+
+async fn debit_within_limits(
+    wallet_repo: &CreditWalletRepository,
+    tx: &mut Transaction<'_, Postgres>,
+    wallet_id: &str,
+    amount: i32,
+    reference: LedgerReference<'_>,
+    daily_limit: Option<i32>,
+) -> Result<CreditLedgerEntry, AppError> {
+    // Lock first so concurrent debits see each other's usage.
+    let balance = wallet_repo
+        .lock_balance_in_tx(tx, wallet_id)
+        .await
+        .map_err(|e| anyhow_error(format!("Failed to get balance: {e}")))?;
+
+    if let Some(limit) = daily_limit {
+        let daily_usage = wallet_repo
+            .daily_usage_in_tx(tx, wallet_id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to check daily usage: {e}")))?;
+        check_daily_limit(daily_usage, amount, limit)?;
+    }
+    check_balance(&balance, amount)?;
+
+    wallet_repo
+        .debit_in_tx(tx, wallet_id, amount, reference)
+        .await
+        .map_err(|e| anyhow_error(format!("Failed to debit credits: {e}")))
+}
+
+fn day_start(day: NaiveDate) -> DateTime<Utc> {
+    day.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()
+}
+
+fn parse_day(raw: Option<&str>, field: &str) -> Result<Option<NaiveDate>, AppError> {
+    let Some(raw) = raw.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Ok(None);
+    };
+    NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+        .map(Some)
+        .map_err(|_| client_error(format!("INVALID_DATE: {field} must be YYYY-MM-DD")))
+}
+
+fn ledger_filter(view: &LedgerView, query: &CreditLedgerQuery) -> Result<LedgerFilter, AppError> {
+    let from = parse_day(query.from.as_deref(), "from")?;
+    let to = parse_day(query.to.as_deref(), "to")?;
+    if let (Some(from), Some(to)) = (from, to) {
+        if from > to {
+            return Err(client_error("INVALID_DATE: from must not be after to"));
+        }
+    }
+
+    Ok(LedgerFilter {
+        from: from.map(day_start),
+        until: to.map(|day| day_start(day) + Duration::days(1)),
+        reason: query
+            .reason
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string),
+        actor_user_id: view.actor_user_id.clone(),
+    })
+}
+
+/// Inclusive day range for a usage breakdown, defaulting to the last 30 days.
+fn usage_range(
+    query: &CreditUsageQuery,
+    today: NaiveDate,
+) -> Result<(NaiveDate, NaiveDate), AppError> {
+    let to = parse_day(query.to.as_deref(), "to")?.unwrap_or(today);
+    let from = parse_day(query.from.as_deref(), "from")?
+        .unwrap_or(to - Duration::days(USAGE_RANGE_DAYS_DEFAULT - 1));
+    if from > to {
+        return Err(client_error("INVALID_DATE: from must not be after to"));
+    }
+    if (to - from).num_days() >= USAGE_RANGE_DAYS_MAX {
+        return Err(client_error(format!(
+            "INVALID_DATE: usage range must not exceed {USAGE_RANGE_DAYS_MAX} days"
+        )));
+    }
+    Ok((from, to))
+}
+
+fn render_ledger_csv(entries: &[CreditLedgerEntry]) -> Result<String, AppError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record([
+            "occurred_at",
+            "direction",
+            "amount",
+            "reason",
+            "reference_type",
+            "reference_id",
+            "actor_user_id",
+            "entry_id",
+        ])
+        .map_err(|e| anyhow_error(format!("Failed to write CSV: {e}")))?;
+    for entry in entries {
+        writer
+            .write_record([
+                entry.occurred_at.as_str(),
+                entry.direction.as_str(),
+                &entry.amount.to_string(),
+                entry.reason.as_str(),
+                entry.reference_type.as_deref().unwrap_or_default(),
+                entry.reference_id.as_deref().unwrap_or_default(),
+                entry.actor_user_id.as_deref().unwrap_or_default(),
+                entry.id.as_str(),
+            ])
+            .map_err(|e| anyhow_error(format!("Failed to write CSV: {e}")))?;
+    }
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| anyhow_error(format!("Failed to write CSV: {e}")))?;
+    String::from_utf8(bytes).map_err(|e| anyhow_error(format!("Failed to write CSV: {e}")))
+}
+
+/// First monthly boundary after `now`, counted from the missed reset date so
+/// wallets keep their billing day even after downtime.
+fn next_reset_at(due_at: DateTime<Utc>, now: DateTime<Utc>) -> DateTime<Utc> {
+    let mut next = due_at;
+    while next <= now {
+        next = next
+            .checked_add_months(Months::new(1))
+            .unwrap_or(now + chrono::Duration::days(30));
+    }
+    next
+}
+
+fn check_daily_limit(daily_usage: i32, amount: i32, limit: i32) -> Result<(), AppError> {
+    if daily_usage + amount > limit {
+        return Err(too_many_requests_error(format!(
+            "CREDIT_DAILY_LIMIT: Daily credit limit reached. Used: {daily_usage}, Limit: {limit}"
+        )));
+    }
+    Ok(())
+}
+
+fn check_balance(balance: &CreditBalanceResponse, amount: i32) -> Result<(), AppError> {
+    if balance.available < amount {
+        return Err(payment_required_error(format!(
+            "CREDIT_EXHAUSTED: Insufficient credits. Available: {}, Required: {amount}",
+            balance.available
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    use super::{next_reset_at, render_ledger_csv, usage_range};
+    use crate::features::credits::models::{CreditLedgerEntry, CreditUsageQuery};
+
+    #[test]
+    fn next_reset_is_one_month_after_due_date() {
+        let due = Utc.with_ymd_and_hms(2026, 9, 15, 0, 0, 0).unwrap();
+        let now = Utc.with_ymd_and_hms(2026, 9, 15, 6, 0, 0).unwrap();
+        assert_eq!(
+            next_reset_at(due, now),
+            Utc.with_ymd_and_hms(2026, 10, 15, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn next_reset_skips_missed_months() {
+        let due = Utc.with_ymd_and_hms(2026, 6, 1, 0, 0, 0).unwrap();
+        let now = Utc.with_ymd_and_hms(2026, 9, 10, 0, 0, 0).unwrap();
+        assert_eq!(
+            next_reset_at(due, now),
+            Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn usage_range_defaults_to_last_thirty_days() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        let query = CreditUsageQuery {
+            from: None,
+            to: None,
+        };
+        let (from, to) = usage_range(&query, today).expect("default range");
+        assert_eq!(from, NaiveDate::from_ymd_opt(2026, 9, 18).unwrap());
+        assert_eq!(to, today);
+    }
+
+    #[test]
+    fn usage_range_rejects_reversed_and_malformed_dates() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        let reversed = CreditUsageQuery {
+            from: Some("2026-10-10".to_string()),
+            to: Some("2026-10-01".to_string()),
+        };
+        assert!(usage_range(&reversed, today).is_err());
+        let malformed = CreditUsageQuery {
+            from: Some("10/01/2026".to_string()),
+            to: None,
+        };
+        assert!(usage_range(&malformed, today).is_err());
+    }
+
+    #[test]
+    fn ledger_csv_has_header_and_one_row_per_entry() {
+        let csv = render_ledger_csv(&[CreditLedgerEntry {
+            id: "ledger-1".to_string(),
+            wallet_id: "wallet-1".to_string(),
+            direction: "credit".to_string(),
+            amount: 500,
+            reason: "purchase".to_string(),
+            reference_type: Some("checkout_session".to_string()),
+            reference_id: Some("cs_test, 1".to_string()),
+            actor_user_id: None,
+            occurred_at: "2026-10-17T00:00:00+00:00".to_string(),
+        }])
+        .expect("csv");
+
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "occurred_at,direction,amount,reason,reference_type,reference_id,actor_user_id,entry_id"
+        );
+        assert_eq!(
+            lines[1],
+            "2026-10-17T00:00:00+00:00,credit,500,purchase,checkout_session,\"cs_test, 1\",,ledger-1"
+        );
+        assert_eq!(lines.len(), 2);
     }
 }
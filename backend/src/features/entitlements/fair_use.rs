@@ -1,17 +1,47 @@
+use axum::http::{HeaderMap, HeaderValue};
+use chrono::{DateTime, Duration, Utc};
 use sqlx::{PgPool, Row};
 
 use crate::error::{anyhow_error, too_many_requests_error, AppError};
+use crate::features::organizations::repository::OrganizationRepository;
 use crate::shared::admin_override::is_admin_override_user;
 
 use super::models::PlanCode;
 
+pub const RATE_LIMIT_LIMIT_HEADER: &str = "x-ratelimit-limit";
+pub const RATE_LIMIT_REMAINING_HEADER: &str = "x-ratelimit-remaining";
+pub const RATE_LIMIT_RESET_HEADER: &str = "x-ratelimit-reset";
+pub const RATE_LIMIT_SCOPE_HEADER: &str = "x-ratelimit-scope";
+
 #[derive(Clone, Copy)]
 struct DailyFairUseLimit {
     agent_replies_per_day: i64,
     evaluations_per_day: i64,
 }
 
-fn env_i64(key: &str, default: i64) -> i64 {
+#[derive(Debug, Clone, Copy)]
+pub enum DailyUsage {
+    AgentReplies,
+    Evaluations,
+}
+
+impl DailyUsage {
+    pub fn label(self) -> &'static str {
+        match self {
+            DailyUsage::AgentReplies => "chat",
+            DailyUsage::Evaluations => "evaluation",
+        }
+    }
+
+    fn limit(self, limit: DailyFairUseLimit) -> i64 {
+        match self {
+            DailyUsage::AgentReplies => limit.agent_replies_per_day,
+            DailyUsage::Evaluations => limit.evaluations_per_day,
+        }
+    }
+}
+
+pub(super) fn env_i64(key: &str, default: i64) -> i64 {
     std::env::var(key)
         .ok()
         .and_then(|value| value.parse::<i64>().ok())
@@ -19,90 +49,131 @@ fn env_i64(key: &str, default: i64) -> i64 {
         .unwrap_or(default)
 }
 
-fn resolve_daily_limit(plan_code: &PlanCode) -> DailyFairUseLimit {
+/// Team pool for one cap: the per-seat key times the seats. Deployments that
+/// still only set the older pool-wide key keep that exact pool until they
+/// move to the per-seat key.
+fn team_pool_limit(per_seat_key: &str, legacy_key: &str, default_per_seat: i64, seats: i64) -> i64 {
+    if std::env::var(per_seat_key).is_err() {
+        if let Some(legacy) = std::env::var(legacy_key)
+            .ok()
+            .and_then(|value| value.parse::<i64>().ok())
+            .filter(|value| *value > 0)
+        {
+            tracing::warn!("{legacy_key} is deprecated; set {per_seat_key} instead");
+            return legacy;
+        }
+    }
+    env_i64(per_seat_key, default_per_seat) * seats
+}
+
+/// Daily pool for the plan. Team pools grow linearly with the paid seats so
+/// larger organizations are not squeezed into a small-team allowance.
+fn resolve_daily_limit(plan_code: &PlanCode, seats: i64) -> DailyFairUseLimit {
     match plan_code {
         PlanCode::Free => DailyFairUseLimit {
             agent_replies_per_day: env_i64("FAIR_USE_FREE_AGENT_REPLIES_PER_DAY", 20),
             evaluations_per_day: env_i64("FAIR_USE_FREE_EVALUATIONS_PER_DAY", 3),
         },
-        PlanCode::Team => DailyFairUseLimit {
-            // Simplified v1 behavior: flat team fair-use limits (no seat-scaling math).
-            agent_replies_per_day: env_i64("FAIR_USE_TEAM_AGENT_REPLIES_PER_DAY", 800),
-            evaluations_per_day: env_i64("FAIR_USE_TEAM_EVALUATIONS_PER_DAY", 160),
-        },
+        PlanCode::Team => {
+            let seats = seats.max(1);
+            DailyFairUseLimit {
+                agent_replies_per_day: team_pool_limit(
+                    "FAIR_USE_TEAM_AGENT_REPLIES_PER_SEAT_PER_DAY",
+                    "FAIR_USE_TEAM_AGENT_REPLIES_PER_DAY",
+                    400,
+                    seats,
+                ),
+                evaluations_per_day: team_pool_limit(
+                    "FAIR_USE_TEAM_EVALUATIONS_PER_SEAT_PER_DAY",
+                    "FAIR_USE_TEAM_EVALUATIONS_PER_DAY",
+                    80,
+                    seats,
+                ),
+            }
+        }
     }
 }
 
-async fn count_agent_replies_today(
+/// Cap for a single member of a team pool, so one user cannot drain the
+/// allowance the other seats pay for.
+fn resolve_member_limit() -> DailyFairUseLimit {
+    DailyFairUseLimit {
+        agent_replies_per_day: env_i64("FAIR_USE_TEAM_MEMBER_AGENT_REPLIES_PER_DAY", 800),
+        evaluations_per_day: env_i64("FAIR_USE_TEAM_MEMBER_EVALUATIONS_PER_DAY", 160),
+    }
+}
+
+/// Start of the current fair-use day and the moment it resets (UTC midnight).
+fn fair_use_day(now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+    let day_start = now
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .unwrap_or_default()
+        .and_utc();
+    (day_start, day_start + Duration::days(1))
+}
+
+/// Count today's usage. `organization_id` and `user_id` each narrow the
+/// count when present; a member of a team pool passes both.
+async fn count_usage_today(
     pool: &PgPool,
-    scope_type: &str,
-    scope_id: &str,
+    usage: DailyUsage,
+    organization_id: Option<&str>,
+    user_id: Option<&str>,
+    since: DateTime<Utc>,
 ) -> Result<i64, AppError> {
-    let sql = match scope_type {
-        "organization" => {
+    let sql = match usage {
+        DailyUsage::AgentReplies => {
             r#"
             SELECT COUNT(*)::BIGINT AS count
             FROM messages m
             INNER JOIN sessions s ON s.id = m.session_id
             WHERE m.role = 'agent'
-              AND m.created_at >= DATE_TRUNC('day', NOW())
-              AND s.organization_id = $1
+              AND m.created_at >= $1
+              AND ($2::TEXT IS NULL OR s.organization_id = $2)
+              AND ($3::TEXT IS NULL OR s.user_id = $3)
             "#
         }
-        _ => {
+        DailyUsage::Evaluations => {
             r#"
             SELECT COUNT(*)::BIGINT AS count
-            FROM messages m
-            INNER JOIN sessions s ON s.id = m.session_id
-            WHERE m.role = 'agent'
-              AND m.created_at >= DATE_TRUNC('day', NOW())
-              AND s.user_id = $1
+            FROM evaluations e
+            INNER JOIN sessions s ON s.id = e.session_id
+            WHERE e.created_at >= $1
+              AND ($2::TEXT IS NULL OR s.organization_id = $2)
+              AND ($3::TEXT IS NULL OR s.user_id = $3)
             "#
         }
     };
 
     let row = sqlx::query(sql)
-        .bind(scope_id)
+        .bind(since)
+        .bind(organization_id)
+        .bind(user_id)
         .fetch_one(pool)
         .await
-        .map_err(|e| anyhow_error(format!("Failed to count agent replies: {}", e)))?;
+        .map_err(|e| anyhow_error(format!("Failed to count {} usage: {e}", usage.label())))?;
 
     Ok(row.try_get::<i64, _>("count").unwrap_or(0))
 }
 
-async fn count_evaluations_today(
-    pool: &PgPool,
-    scope_type: &str,
-    scope_id: &str,
-) -> Result<i64, AppError> {
-    let sql = match scope_type {
-        "organization" => {
-            r#"
-            SELECT COUNT(*)::BIGINT AS count
-            FROM evaluations e
-            INNER JOIN sessions s ON s.id = e.session_id
-            WHERE e.created_at >= DATE_TRUNC('day', NOW())
-              AND s.organization_id = $1
-            "#
-        }
-        _ => {
-            r#"
-            SELECT COUNT(*)::BIGINT AS count
-            FROM evaluations e
-            INNER JOIN sessions s ON s.id = e.session_id
-            WHERE e.created_at >= DATE_TRUNC('day', NOW())
-              AND s.user_id = $1
-            "#
-        }
-    };
-
-    let row = sqlx::query(sql)
-        .bind(scope_id)
-        .fetch_one(pool)
+/// Seats paying for an organization's pool: the active Team subscription's
+/// quantity, or the active member count for entitlements granted without one.
+async fn resolve_seat_count(pool: &PgPool, organization_id: &str) -> Result<i64, AppError> {
+    let repo = OrganizationRepository::new(pool.clone());
+    let seat_limit = repo
+        .get_active_seat_limit(organization_id)
         .await
-        .map_err(|e| anyhow_error(format!("Failed to count evaluations: {}", e)))?;
+        .map_err(|e| anyhow_error(format!("Failed to fetch seat limit: {e}")))?;
+    let seats = match seat_limit {
+        Some(seats) => i64::from(seats),
+        None => repo
+            .count_active_members(organization_id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to count members: {e}")))?,
+    };
 
-    Ok(row.try_get::<i64, _>("count").unwrap_or(0))
+    Ok(seats.max(1))
 }
 
 fn resolve_scope<'a>(
@@ -118,64 +189,183 @@ fn resolve_scope<'a>(
     ("user", user_id)
 }
 
-pub async fn enforce_chat_daily_limit(
+fn check_cap(
+    usage: DailyUsage,
+    cap: &str,
+    used: i64,
+    limit: i64,
+    resets_at: DateTime<Utc>,
+) -> Result<(), AppError> {
+    if used >= limit {
+        return Err(too_many_requests_error(format!(
+            "FAIR_USE_LIMIT_REACHED: {} daily limit reached for {cap}. Used: {used}, Limit: {limit}, Resets at: {}",
+            usage.label(),
+            resets_at.to_rfc3339()
+        )));
+    }
+    Ok(())
+}
+
+/// Caps that apply to the caller today as `(cap, used, limit)`, in the order
+/// they are enforced: the organization pool before the member's own share.
+async fn daily_caps(
     pool: &PgPool,
+    usage: DailyUsage,
     plan_code: &PlanCode,
     user_id: &str,
     organization_id: Option<&str>,
-) -> Result<(), AppError> {
-    if is_admin_override_user(user_id) {
-        return Ok(());
+    day_start: DateTime<Utc>,
+) -> Result<Vec<(&'static str, i64, i64)>, AppError> {
+    let (scope_type, scope_id) = resolve_scope(plan_code, user_id, organization_id);
+    if scope_type == "user" {
+        let limit = usage.limit(resolve_daily_limit(plan_code, 1));
+        let used = count_usage_today(pool, usage, None, Some(user_id), day_start).await?;
+        return Ok(vec![("user", used, limit)]);
     }
 
-    let limit = resolve_daily_limit(plan_code);
+    let seats = resolve_seat_count(pool, scope_id).await?;
+    let pool_limit = usage.limit(resolve_daily_limit(plan_code, seats));
+    let org_used = count_usage_today(pool, usage, Some(scope_id), None, day_start).await?;
+    let member_limit = usage.limit(resolve_member_limit()).min(pool_limit);
+    let member_used =
+        count_usage_today(pool, usage, Some(scope_id), Some(user_id), day_start).await?;
 
-    let (scope_type, scope_id) = resolve_scope(plan_code, user_id, organization_id);
-    let used = count_agent_replies_today(pool, scope_type, scope_id).await?;
+    Ok(vec![
+        ("organization", org_used, pool_limit),
+        ("member", member_used, member_limit),
+    ])
+}
 
-    if used >= limit.agent_replies_per_day {
-        return Err(too_many_requests_error(format!(
-            "FAIR_USE_LIMIT_REACHED: chat daily limit reached for {}. Used: {}, Limit: {}",
-            scope_type, used, limit.agent_replies_per_day
-        )));
+/// Today's standing against the cap closest to being reached.
+#[derive(Debug, Clone)]
+pub struct DailyQuota {
+    pub cap: &'static str,
+    pub used: i64,
+    /// `None` when the caller is exempt from fair-use limits.
+    pub limit: Option<i64>,
+    pub resets_at: DateTime<Utc>,
+}
+
+impl DailyQuota {
+    pub fn remaining(&self) -> Option<i64> {
+        self.limit.map(|limit| (limit - self.used).max(0))
     }
 
-    Ok(())
+    /// `X-RateLimit-*` headers describing this quota; empty when unlimited.
+    pub fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let (Some(limit), Some(remaining)) = (self.limit, self.remaining()) else {
+            return headers;
+        };
+        headers.insert(RATE_LIMIT_LIMIT_HEADER, HeaderValue::from(limit));
+        headers.insert(RATE_LIMIT_REMAINING_HEADER, HeaderValue::from(remaining));
+        headers.insert(
+            RATE_LIMIT_RESET_HEADER,
+            HeaderValue::from(self.resets_at.timestamp()),
+        );
+        headers.insert(RATE_LIMIT_SCOPE_HEADER, HeaderValue::from_static(self.cap));
+        headers
+    }
 }
 
-pub async fn enforce_evaluation_daily_limit(
+fn tightest_cap(caps: &[(&'static str, i64, i64)]) -> Option<(&'static str, i64, i64)> {
+    caps.iter()
+        .copied()
+        .min_by_key(|(_, used, limit)| limit - used)
+}
+
+pub async fn daily_quota(
     pool: &PgPool,
+    usage: DailyUsage,
     plan_code: &PlanCode,
     user_id: &str,
     organization_id: Option<&str>,
-) -> Result<(), AppError> {
+) -> Result<DailyQuota, AppError> {
+    let (day_start, resets_at) = fair_use_day(Utc::now());
     if is_admin_override_user(user_id) {
-        return Ok(());
+        let used = count_usage_today(pool, usage, None, Some(user_id), day_start).await?;
+        return Ok(DailyQuota {
+            cap: "user",
+            used,
+            limit: None,
+            resets_at,
+        });
     }
 
-    let limit = resolve_daily_limit(plan_code);
-
-    let (scope_type, scope_id) = resolve_scope(plan_code, user_id, organization_id);
-    let used = count_evaluations_today(pool, scope_type, scope_id).await?;
+    let caps = daily_caps(pool, usage, plan_code, user_id, organization_id, day_start).await?;
+    let (cap, used, limit) = tightest_cap(&caps).unwrap_or(("user", 0, 0));
+    Ok(DailyQuota {
+        cap,
+        used,
+        limit: Some(limit),
+        resets_at,
+    })
+}
 
-    if used >= limit.evaluations_per_day {
-        return Err(too_many_requests_error(format!(
-            "FAIR_USE_LIMIT_REACHED: evaluation daily limit reached for {}. Used: {}, Limit: {}",
-            scope_type, used, limit.evaluations_per_day
-        )));
+async fn enforce_daily_limit(
+    pool: &PgPool,
+    usage: DailyUsage,
+    plan_code: &PlanCode,
+    user_id: &str,
+    organization_id: Option<&str>,
+) -> Result<(), AppError> {
+    if is_admin_override_user(user_id) {
+        return Ok(());
     }
 
+    let (day_start, resets_at) = fair_use_day(Utc::now());
+    let caps = daily_caps(pool, usage, plan_code, user_id, organization_id, day_start).await?;
+    for (cap, used, limit) in caps {
+        check_cap(usage, cap, used, limit, resets_at)?;
+    }
     Ok(())
 }
 
+pub async fn enforce_chat_daily_limit(
+    pool: &PgPool,
+    plan_code: &PlanCode,
+    user_id: &str,
+    organization_id: Option<&str>,
+) -> Result<(), AppError> {
+    enforce_daily_limit(
+        pool,
+        DailyUsage::AgentReplies,
+        plan_code,
+        user_id,
+        organization_id,
+    )
+    .await
+}
+
+pub async fn enforce_evaluation_daily_limit(
+    pool: &PgPool,
+    plan_code: &PlanCode,
+    user_id: &str,
+    organization_id: Option<&str>,
+) -> Result<(), AppError> {
+    enforce_daily_limit(
+        pool,
+        DailyUsage::Evaluations,
+        plan_code,
+        user_id,
+        organization_id,
+    )
+    .await
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{resolve_daily_limit, resolve_scope};
+    use chrono::{TimeZone, Utc};
+
+    use super::{
+        check_cap, fair_use_day, resolve_daily_limit, resolve_scope, team_pool_limit, tightest_cap,
+        DailyQuota, DailyUsage,
+    };
     use crate::features::entitlements::models::PlanCode;
 
     #[test]
     fn free_plan_has_fair_use_limits() {
-        let limit = resolve_daily_limit(&PlanCode::Free);
+        let limit = resolve_daily_limit(&PlanCode::Free, 1);
         assert!(limit.agent_replies_per_day > 0);
         assert!(limit.evaluations_per_day > 0);
     }
@@ -187,7 +377,7 @@ mod tests {
             std::env::set_var("FAIR_USE_FREE_EVALUATIONS_PER_DAY", "4");
         }
 
-        let limit = resolve_daily_limit(&PlanCode::Free);
+        let limit = resolve_daily_limit(&PlanCode::Free, 1);
         assert_eq!(limit.agent_replies_per_day, 11);
         assert_eq!(limit.evaluations_per_day, 4);
 
@@ -210,4 +400,118 @@ mod tests {
         assert_eq!(scope_type, "user");
         assert_eq!(scope_id, "user-1");
     }
+
+    #[test]
+    fn team_plan_limits_scale_with_seats() {
+        let two_seats = resolve_daily_limit(&PlanCode::Team, 2);
+        let ten_seats = resolve_daily_limit(&PlanCode::Team, 10);
+        assert_eq!(
+            ten_seats.agent_replies_per_day,
+            two_seats.agent_replies_per_day * 5
+        );
+        assert_eq!(
+            ten_seats.evaluations_per_day,
+            two_seats.evaluations_per_day * 5
+        );
+        assert_eq!(
+            resolve_daily_limit(&PlanCode::Team, 0).agent_replies_per_day,
+            resolve_daily_limit(&PlanCode::Team, 1).agent_replies_per_day
+        );
+    }
+
+    #[test]
+    fn team_pool_falls_back_to_legacy_pool_key() {
+        unsafe {
+            std::env::set_var("FAIR_USE_TEST_LEGACY_PER_DAY", "900");
+        }
+        assert_eq!(
+            team_pool_limit(
+                "FAIR_USE_TEST_PER_SEAT_PER_DAY",
+                "FAIR_USE_TEST_LEGACY_PER_DAY",
+                10,
+                3
+            ),
+            900
+        );
+
+        unsafe {
+            std::env::set_var("FAIR_USE_TEST_PER_SEAT_PER_DAY", "20");
+        }
+        assert_eq!(
+            team_pool_limit(
+                "FAIR_USE_TEST_PER_SEAT_PER_DAY",
+                "FAIR_USE_TEST_LEGACY_PER_DAY",
+                10,
+                3
+            ),
+            60
+        );
+
+        unsafe {
+            std::env::remove_var("FAIR_USE_TEST_LEGACY_PER_DAY");
+            std::env::remove_var("FAIR_USE_TEST_PER_SEAT_PER_DAY");
+        }
+    }
+
+    #[test]
+    fn fair_use_day_resets_at_next_utc_midnight() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 17, 15, 30, 0).unwrap();
+        let (day_start, resets_at) = fair_use_day(now);
+        assert_eq!(
+            day_start,
+            Utc.with_ymd_and_hms(2026, 10, 17, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            resets_at,
+            Utc.with_ymd_and_hms(2026, 10, 18, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn cap_error_names_cap_and_reset_time() {
+        let resets_at = Utc.with_ymd_and_hms(2026, 10, 18, 0, 0, 0).unwrap();
+        assert!(check_cap(DailyUsage::AgentReplies, "member", 9, 10, resets_at).is_ok());
+
+        let error = check_cap(DailyUsage::AgentReplies, "member", 10, 10, resets_at)
+            .expect_err("cap reached")
+            .to_string();
+        assert!(error.starts_with("FAIR_USE_LIMIT_REACHED: chat daily limit reached for member."));
+        assert!(error.contains("Limit: 10"));
+        assert!(error.contains("Resets at: 2026-10-18T00:00:00+00:00"));
+    }
+
+    #[test]
+    fn tightest_cap_prefers_least_remaining() {
+        let caps = [("organization", 10, 40), ("member", 18, 20)];
+        assert_eq!(tightest_cap(&caps), Some(("member", 18, 20)));
+
+        let caps = [("organization", 39, 40), ("member", 18, 20)];
+        assert_eq!(tightest_cap(&caps), Some(("organization", 39, 40)));
+    }
+
+    #[test]
+    fn quota_headers_report_remaining_and_reset() {
+        let resets_at = Utc.with_ymd_and_hms(2026, 10, 18, 0, 0, 0).unwrap();
+        let quota = DailyQuota {
+            cap: "member",
+            used: 25,
+            limit: Some(20),
+            resets_at,
+        };
+        let headers = quota.headers();
+        assert_eq!(headers["x-ratelimit-limit"], "20");
+        assert_eq!(headers["x-ratelimit-remaining"], "0");
+        assert_eq!(
+            headers["x-ratelimit-reset"],
+            resets_at.timestamp().to_string().as_str()
+        );
+        assert_eq!(headers["x-ratelimit-scope"], "member");
+
+        let unlimited = DailyQuota {
+            limit: None,
+            ..quota
+        };
+        assert_eq!(unlimited.remaining(), None);
+        assert!(unlimited.headers().is_empty());
+    }
 }
@@ -4,7 +4,7 @@ use crate::error::AppError;
 use crate::middleware::auth::AuthUser;
 use crate::state::SharedState;
 
-use super::models::EntitlementResponse;
+use super::models::{EntitlementResponse, UsageResponse};
 
 #[utoipa::path(
     get,
@@ -22,3 +22,20 @@ pub async fn get_my_entitlements(
         .await?;
     Ok(Json(response))
 }
+
+#[utoipa::path(
+    get,
+    path = "/me/usage",
+    responses((status = 200, body = UsageResponse))
+)]
+pub async fn get_my_usage(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> Result<Json<UsageResponse>, AppError> {
+    let response = state
+        .services()
+        .entitlements()
+        .get_my_usage(&auth.user_id)
+        .await?;
+    Ok(Json(response))
+}
@@ -73,3 +73,26 @@ pub struct EntitlementResponse {
     pub team_features: bool,
     pub organization_id: Option<String>,
 }
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyUsageQuota {
+    pub used: i64,
+    /// `null` when the caller is exempt from fair-use limits.
+    pub limit: Option<i64>,
+    pub remaining: Option<i64>,
+    /// Cap closest to being reached: `user`, `organization` or `member`.
+    pub cap: String,
+}
+
+// Response type for GET /me/usage
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageResponse {
+    pub plan_code: PlanCode,
+    pub organization_id: Option<String>,
+    pub enforced: bool,
+    pub agent_replies: DailyUsageQuota,
+    pub evaluations: DailyUsageQuota,
+    pub resets_at: String,
+}
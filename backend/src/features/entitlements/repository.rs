@@ -100,6 +100,38 @@ impl EntitlementRepository {
         Ok(row.and_then(Self::map_row))
     }
 
+    /// Most recent subscription-backed Team entitlement of an organization
+    /// that is no longer in force (cancelled, expired or revoked).
+    pub async fn find_lapsed_team_for_org(&self, org_id: &str) -> Result<Option<Entitlement>> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                id,
+                scope_type,
+                scope_id,
+                plan_code,
+                status,
+                to_char(valid_from, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as valid_from,
+                to_char(valid_until, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as valid_until,
+                source_subscription_id
+            FROM entitlements
+            WHERE scope_type = 'organization'
+                AND scope_id = $1
+                AND plan_code = 'TEAM'
+                AND source_subscription_id IS NOT NULL
+                AND (status <> 'active' OR valid_until <= NOW())
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(org_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to fetch lapsed team entitlement for organization")?;
+
+        Ok(row.and_then(Self::map_row))
+    }
+
     pub async fn create(&self, entitlement: &Entitlement) -> Result<Entitlement> {
         let mut tx = self.pool.begin().await?;
         self.create_in_tx(&mut tx, entitlement).await?;
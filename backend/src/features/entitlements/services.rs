@@ -1,3 +1,4 @@
+use axum::http::HeaderMap;
 use sqlx::PgPool;
 
 use crate::error::{anyhow_error, AppError};
@@ -7,7 +8,11 @@ use crate::models::ScenarioDiscipline;
 use crate::shared::admin_override::is_admin_override_user;
 use crate::shared::helpers::{next_id, now_ts};
 
-use super::models::{EffectivePlan, EntitlementResponse, PlanCode, PlanLimits, ScenarioAccess};
+use super::fair_use::{daily_quota, env_i64, DailyQuota, DailyUsage};
+use super::models::{
+    DailyUsageQuota, EffectivePlan, EntitlementResponse, PlanCode, PlanLimits, ScenarioAccess,
+    UsageResponse,
+};
 use super::repository::EntitlementRepository;
 
 const FREE_SCENARIO_IDS: &[&str] = &[
@@ -87,12 +92,21 @@ impl EntitlementService {
         }
 
         // Next priority: active org membership with active org entitlement.
+        // Once the user has switched to an organization, only its plan counts.
         let org_repo = OrganizationRepository::new(self.pool.clone());
-        let active_memberships = org_repo
+        let mut active_memberships = org_repo
             .list_active_orgs_for_user(user_id)
             .await
             .map_err(|e| anyhow_error(&format!("Failed to fetch active memberships: {}", e)))?;
+        let current_org_id = org_repo
+            .find_current_org_id_for_user(user_id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to fetch current organization: {e}")))?;
+        if let Some(current_org_id) = current_org_id.as_deref() {
+            active_memberships.retain(|membership| membership.organization_id == current_org_id);
+        }
 
+        let mut lapsed_team_entitlement_id = None;
         for membership in active_memberships {
             if let Some(entitlement) = entitlement_repo
                 .find_active_for_org(&membership.organization_id)
@@ -113,6 +127,34 @@ impl EntitlementService {
                     organization_id,
                 });
             }
+
+            if lapsed_team_entitlement_id.is_none() {
+                lapsed_team_entitlement_id = entitlement_repo
+                    .find_lapsed_team_for_org(&membership.organization_id)
+                    .await
+                    .map_err(|e| anyhow_error(format!("Failed to fetch lapsed org plan: {e}")))?
+                    .map(|lapsed| lapsed.id);
+            }
+        }
+
+        // Members of an organization whose Team plan lapsed drop to Free without
+        // provisioning an entitlement of their own, so renewing restores Team.
+        if let Some(entitlement_id) = lapsed_team_entitlement_id {
+            return Ok(EffectivePlan {
+                plan_code: PlanCode::Free,
+                source_entitlement_id: entitlement_id,
+                organization_id: None,
+            });
+        }
+
+        // An organization the user switched to without a plan of its own means
+        // Free; a provisioned user entitlement would outrank switching back.
+        if let Some(current_org_id) = current_org_id {
+            return Ok(EffectivePlan {
+                plan_code: PlanCode::Free,
+                source_entitlement_id: format!("organization-without-plan:{current_org_id}"),
+                organization_id: None,
+            });
         }
 
         // Fallback: auto-provision a default entitlement for first-time users.
@@ -140,6 +182,26 @@ impl EntitlementService {
         })
     }
 
+    /// Plan of the entitlement held directly by a user or organization scope,
+    /// falling back to Free when the scope has no active entitlement.
+    pub async fn plan_for_scope(
+        &self,
+        scope_type: &str,
+        scope_id: &str,
+    ) -> Result<PlanCode, AppError> {
+        let team_features_enabled = FeatureFlagService::new().is_team_features_enabled();
+        let entitlement_repo = EntitlementRepository::new(self.pool.clone());
+        let entitlement = match scope_type {
+            "organization" => entitlement_repo.find_active_for_org(scope_id).await,
+            _ => entitlement_repo.find_active_for_user(scope_id).await,
+        }
+        .map_err(|e| anyhow_error(format!("Failed to fetch entitlement: {e}")))?;
+
+        Ok(entitlement
+            .map(|e| normalize_plan_for_launch(e.plan_code, team_features_enabled))
+            .unwrap_or(PlanCode::Free))
+    }
+
     /// Check if a user can access a specific scenario based on their plan
     pub fn can_access_scenario(
         plan_code: &PlanCode,
@@ -152,19 +214,20 @@ impl EntitlementService {
         }
     }
 
-    /// Get plan limits based on plan code
+    /// Get plan limits based on plan code. Credit allowances come from the
+    /// `CREDITS_*` env keys.
     pub fn plan_limits(plan_code: &PlanCode) -> PlanLimits {
         match plan_code {
             PlanCode::Free => PlanLimits {
-                monthly_credits: 0,
-                max_daily_credits: None,
+                monthly_credits: env_i64("CREDITS_FREE_MONTHLY", 100) as i32,
+                max_daily_credits: Some(env_i64("CREDITS_FREE_MAX_PER_DAY", 30) as i32),
                 scenario_access: ScenarioAccess::FreeOnly,
                 history_retention_days: Some(30),
                 team_features: false,
             },
             PlanCode::Team => PlanLimits {
-                monthly_credits: 0,
-                max_daily_credits: None,
+                monthly_credits: env_i64("CREDITS_TEAM_MONTHLY", 10000) as i32,
+                max_daily_credits: Some(env_i64("CREDITS_TEAM_MAX_PER_DAY", 1500) as i32),
                 scenario_access: ScenarioAccess::All,
                 history_retention_days: None,
                 team_features: true,
@@ -191,6 +254,74 @@ impl EntitlementService {
             organization_id: effective_plan.organization_id,
         })
     }
+
+    /// Today's fair-use counts and limits for GET /me/usage
+    pub async fn get_my_usage(&self, user_id: &str) -> Result<UsageResponse, AppError> {
+        let effective_plan = self.resolve_effective_plan(user_id).await?;
+        let organization_id = effective_plan.organization_id.as_deref();
+        let agent_replies = daily_quota(
+            &self.pool,
+            DailyUsage::AgentReplies,
+            &effective_plan.plan_code,
+            user_id,
+            organization_id,
+        )
+        .await?;
+        let evaluations = daily_quota(
+            &self.pool,
+            DailyUsage::Evaluations,
+            &effective_plan.plan_code,
+            user_id,
+            organization_id,
+        )
+        .await?;
+
+        Ok(UsageResponse {
+            enforced: FeatureFlagService::new().is_entitlement_enforced(),
+            resets_at: agent_replies.resets_at.to_rfc3339(),
+            agent_replies: to_usage_quota(&agent_replies),
+            evaluations: to_usage_quota(&evaluations),
+            plan_code: effective_plan.plan_code,
+            organization_id: effective_plan.organization_id,
+        })
+    }
+
+    /// `X-RateLimit-*` headers for the caller's daily quota. Headers are an
+    /// advisory extra on an already successful response, so lookup failures
+    /// are logged and yield no headers rather than an error.
+    pub async fn rate_limit_headers(&self, user_id: &str, usage: DailyUsage) -> HeaderMap {
+        if !FeatureFlagService::new().is_entitlement_enforced() {
+            return HeaderMap::new();
+        }
+
+        let quota = async {
+            let plan = self.resolve_effective_plan(user_id).await?;
+            daily_quota(
+                &self.pool,
+                usage,
+                &plan.plan_code,
+                user_id,
+                plan.organization_id.as_deref(),
+            )
+            .await
+        };
+        match quota.await {
+            Ok(quota) => quota.headers(),
+            Err(e) => {
+                tracing::warn!("Failed to resolve {} quota headers: {e}", usage.label());
+                HeaderMap::new()
+            }
+        }
+    }
+}
+
+fn to_usage_quota(quota: &DailyQuota) -> DailyUsageQuota {
+    DailyUsageQuota {
+        used: quota.used,
+        limit: quota.limit,
+        remaining: quota.remaining(),
+        cap: quota.cap.to_string(),
+    }
 }
 
 #[cfg(test)]
@@ -1,30 +1,73 @@
 use axum::{
     extract::{Path, State},
+    http::HeaderMap,
     Json,
 };
 
 use crate::error::AppError;
+use crate::features::entitlements::fair_use::DailyUsage;
 use crate::middleware::auth::AuthUser;
 use crate::state::SharedState;
 
-use super::models::{Evaluation, EvaluationRequest};
+use super::models::{EvaluationAttempt, EvaluationRequest};
 
 #[utoipa::path(
     post,
     path = "/sessions/{id}/evaluate",
     request_body = EvaluationRequest,
-    responses((status = 200, body = Evaluation))
+    responses((status = 200, body = EvaluationAttempt))
 )]
 pub async fn evaluate_session(
     State(state): State<SharedState>,
     auth: AuthUser,
     Path(id): Path<String>,
     Json(body): Json<EvaluationRequest>,
-) -> Result<Json<Evaluation>, AppError> {
+) -> Result<(HeaderMap, Json<EvaluationAttempt>), AppError> {
     let eval = state
         .services()
         .evaluations()
         .evaluate_session(&id, &auth.user_id, body)
         .await?;
+    let headers = state
+        .services()
+        .entitlements()
+        .rate_limit_headers(&auth.user_id, DailyUsage::Evaluations)
+        .await;
+    Ok((headers, Json(eval)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/sessions/{id}/evaluations",
+    responses((status = 200, body = Vec<EvaluationAttempt>))
+)]
+pub async fn list_session_evaluations(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<EvaluationAttempt>>, AppError> {
+    let attempts = state
+        .services()
+        .evaluations()
+        .list_session_evaluations(&id, &auth.user_id)
+        .await?;
+    Ok(Json(attempts))
+}
+
+#[utoipa::path(
+    post,
+    path = "/sessions/{id}/evaluations/{attempt}/official",
+    responses((status = 200, body = EvaluationAttempt))
+)]
+pub async fn mark_official_evaluation(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path((session_id, attempt)): Path<(String, i32)>,
+) -> Result<Json<EvaluationAttempt>, AppError> {
+    let eval = state
+        .services()
+        .evaluations()
+        .mark_official_evaluation(&session_id, attempt, &auth.user_id)
+        .await?;
     Ok(Json(eval))
 }
@@ -1,4 +1,4 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 pub use crate::models::Evaluation;
@@ -29,4 +29,23 @@ pub struct EvaluationRequest {
     pub requirement_definition_context: Option<String>,
     pub incident_response_context: Option<String>,
     pub business_execution_context: Option<String>,
+    /// Number of evaluation samples to aggregate (calibrated mode when > 1).
+    pub calibration_samples: Option<u32>,
+}
+
+/// One stored evaluation run for a session. A session can be evaluated
+/// several times; exactly one attempt is official and counts toward
+/// organization progress.
+#[derive(Debug, Serialize, ToSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EvaluationAttempt {
+    pub id: i32,
+    #[serde(flatten)]
+    pub evaluation: Evaluation,
+    pub attempt: i32,
+    pub model_id: Option<String>,
+    pub is_official: bool,
+    /// Samples aggregated into this attempt; 1 for an uncalibrated run.
+    pub sample_count: i32,
+    pub created_at: String,
 }
@@ -1,6 +1,11 @@
+use super::models::EvaluationAttempt;
 use crate::models::{Evaluation, EvaluationCategory};
 use anyhow::{Context, Result};
-use sqlx::{PgPool, Postgres, Row, Transaction};
+use sqlx::{postgres::PgRow, PgPool, Postgres, Row, Transaction};
+
+const ATTEMPT_COLUMNS: &str =
+    "id, session_id, attempt, model_id, sample_count, is_official, overall_score, passing, \
+     categories, summary, improvement_advice, created_at";
 
 #[derive(Clone)]
 pub struct EvaluationRepository {
@@ -15,7 +20,7 @@ impl EvaluationRepository {
     #[allow(dead_code)]
     pub async fn create(&self, evaluation: &Evaluation) -> Result<Evaluation> {
         let mut tx = self.pool.begin().await?;
-        self.create_in_tx(&mut tx, evaluation).await?;
+        self.create_in_tx(&mut tx, evaluation, None, 1).await?;
         tx.commit().await?;
 
         self.get_by_session(&evaluation.session_id)
@@ -23,67 +28,176 @@ impl EvaluationRepository {
             .ok_or_else(|| anyhow::anyhow!("Failed to retrieve created evaluation"))
     }
 
+    /// Store a new attempt for the session. The first attempt of a session
+    /// becomes the official one.
     pub async fn create_in_tx(
         &self,
         tx: &mut Transaction<'_, Postgres>,
         evaluation: &Evaluation,
-    ) -> Result<()> {
+        model_id: Option<&str>,
+        sample_count: i32,
+    ) -> Result<EvaluationAttempt> {
         let categories = serde_json::to_value(&evaluation.categories)?;
 
-        sqlx::query(
+        let row = sqlx::query(&format!(
             r#"
             INSERT INTO evaluations (
-                session_id, overall_score, passing, categories, summary, improvement_advice
+                session_id, attempt, model_id, sample_count, is_official,
+                overall_score, passing, categories, summary, improvement_advice
             )
-            VALUES ($1, $2, $3, $4, $5, $6)
-            ON CONFLICT (session_id) DO UPDATE
-            SET overall_score = EXCLUDED.overall_score,
-                passing = EXCLUDED.passing,
-                categories = EXCLUDED.categories,
-                summary = EXCLUDED.summary,
-                improvement_advice = EXCLUDED.improvement_advice,
-                created_at = NOW()
-            "#,
-        )
+            SELECT
+                $1,
+                COALESCE(MAX(attempt), 0) + 1,
+                $7,
+                $8,
+                NOT COALESCE(BOOL_OR(is_official), FALSE),
+                $2, $3, $4, $5, $6
+            FROM evaluations
+            WHERE session_id = $1
+            RETURNING {ATTEMPT_COLUMNS}
+            "#
+        ))
         .bind(&evaluation.session_id)
         .bind(evaluation.overall_score)
         .bind(evaluation.passing)
         .bind(categories)
         .bind(&evaluation.summary)
         .bind(&evaluation.improvement_advice)
-        .execute(&mut **tx)
+        .bind(model_id)
+        .bind(sample_count)
+        .fetch_one(&mut **tx)
         .await
         .context("Failed to insert evaluation")?;
 
-        Ok(())
+        Ok(Self::map_attempt_row(row))
     }
 
+    /// Official evaluation of the session, or the latest attempt when none
+    /// is marked official.
     pub async fn get_by_session(&self, session_id: &str) -> Result<Option<Evaluation>> {
-        let row = sqlx::query(
+        let row = sqlx::query(&format!(
             r#"
-            SELECT session_id, overall_score, passing, categories, summary, improvement_advice
+            SELECT {ATTEMPT_COLUMNS}
             FROM evaluations
             WHERE session_id = $1
-            "#,
-        )
+            ORDER BY is_official DESC, attempt DESC
+            LIMIT 1
+            "#
+        ))
         .bind(session_id)
         .fetch_optional(&self.pool)
         .await
         .context("Failed to fetch evaluation")?;
 
-        Ok(row.map(|r| {
-            let categories: Vec<EvaluationCategory> =
-                serde_json::from_value(r.get("categories")).unwrap_or_default();
-
-            Evaluation {
-                session_id: r.get("session_id"),
-                overall_score: r.get("overall_score"),
-                passing: r.get("passing"),
-                categories,
-                summary: r.get("summary"),
-                improvement_advice: r.get("improvement_advice"),
-            }
-        }))
+        Ok(row.as_ref().map(Self::map_evaluation_row))
+    }
+
+    pub async fn get_attempt(
+        &self,
+        session_id: &str,
+        attempt: i32,
+    ) -> Result<Option<EvaluationAttempt>> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {ATTEMPT_COLUMNS}
+            FROM evaluations
+            WHERE session_id = $1 AND attempt = $2
+            "#
+        ))
+        .bind(session_id)
+        .bind(attempt)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to fetch evaluation attempt")?;
+
+        Ok(row.map(Self::map_attempt_row))
+    }
+
+    pub async fn list_by_session(&self, session_id: &str) -> Result<Vec<EvaluationAttempt>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {ATTEMPT_COLUMNS}
+            FROM evaluations
+            WHERE session_id = $1
+            ORDER BY attempt ASC
+            "#
+        ))
+        .bind(session_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list evaluation attempts")?;
+
+        Ok(rows.into_iter().map(Self::map_attempt_row).collect())
+    }
+
+    /// Make `attempt` the official evaluation of the session. Returns false
+    /// when the attempt does not exist.
+    pub async fn set_official(&self, session_id: &str, attempt: i32) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let exists = sqlx::query(
+            "SELECT 1 FROM evaluations WHERE session_id = $1 AND attempt = $2 FOR UPDATE",
+        )
+        .bind(session_id)
+        .bind(attempt)
+        .fetch_optional(&mut *tx)
+        .await
+        .context("Failed to fetch evaluation attempt")?
+        .is_some();
+        if !exists {
+            return Ok(false);
+        }
+
+        // Clear first: the partial unique index allows one official row.
+        sqlx::query(
+            "UPDATE evaluations SET is_official = FALSE WHERE session_id = $1 AND is_official",
+        )
+        .bind(session_id)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to clear official evaluation")?;
+        sqlx::query(
+            "UPDATE evaluations SET is_official = TRUE WHERE session_id = $1 AND attempt = $2",
+        )
+        .bind(session_id)
+        .bind(attempt)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to mark official evaluation")?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    fn map_evaluation_row(r: &PgRow) -> Evaluation {
+        let categories: Vec<EvaluationCategory> =
+            serde_json::from_value(r.get("categories")).unwrap_or_default();
+
+        Evaluation {
+            session_id: r.get("session_id"),
+            overall_score: r.get("overall_score"),
+            passing: r.get("passing"),
+            categories,
+            summary: r.get("summary"),
+            improvement_advice: r.get("improvement_advice"),
+        }
+    }
+
+    fn map_attempt_row(r: PgRow) -> EvaluationAttempt {
+        let created_at = r
+            .try_get::<chrono::DateTime<chrono::Utc>, _>("created_at")
+            .map(|v| v.to_rfc3339())
+            .unwrap_or_default();
+
+        EvaluationAttempt {
+            id: r.get("id"),
+            evaluation: Self::map_evaluation_row(&r),
+            attempt: r.get("attempt"),
+            model_id: r.try_get::<Option<String>, _>("model_id").unwrap_or(None),
+            is_official: r.get("is_official"),
+            sample_count: r.get("sample_count"),
+            created_at,
+        }
     }
 
     #[allow(dead_code)]
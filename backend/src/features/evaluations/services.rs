@@ -1,22 +1,24 @@
-use reqwest::Client;
+use axum::http::StatusCode;
 use serde::Deserialize;
 use serde_json::json;
 use sqlx::PgPool;
 use tracing::warn;
 
 use crate::error::{anyhow_error, client_error, forbidden_error, AppError};
+use crate::features::credits::services::{CreditService, EVALUATION_CREDIT_COST};
 use crate::features::entitlements::fair_use::enforce_evaluation_daily_limit;
 use crate::features::entitlements::models::PlanCode;
 use crate::features::entitlements::services::EntitlementService;
 use crate::features::evaluations::repository::EvaluationRepository;
 use crate::features::feature_flags::services::FeatureFlagService;
 use crate::features::messages::repository::MessageRepository;
+use crate::features::scenarios::services::ScenarioService;
 use crate::features::sessions::authorization::authorize_session_access;
 use crate::features::sessions::repository::SessionRepository;
-use crate::models::{default_scenarios, Evaluation, EvaluationCategory, Message, MessageRole};
-use crate::shared::gemini::resolve_eval_credentials;
+use crate::models::{Evaluation, EvaluationCategory, Message, MessageRole};
+use crate::shared::llm::{resolve_eval_provider, LlmMessage, LlmProvider, LlmPurpose, LlmRequest};
 
-use super::models::{EvaluationCriterion, EvaluationRequest};
+use super::models::{EvaluationAttempt, EvaluationCriterion, EvaluationRequest};
 
 #[derive(Clone)]
 pub struct EvaluationService {
@@ -32,8 +34,8 @@ impl EvaluationService {
         &self,
         session_id: &str,
         user_id: &str,
-        request: EvaluationRequest,
-    ) -> Result<Evaluation, AppError> {
+        mut request: EvaluationRequest,
+    ) -> Result<EvaluationAttempt, AppError> {
         let access = authorize_session_access(&self.pool, session_id, user_id).await?;
         if !access.can_edit_session() {
             return Err(forbidden_error(
@@ -54,6 +56,23 @@ impl EvaluationService {
 
         let session = access.session;
 
+        let scenario = ScenarioService::new(self.pool.clone())
+            .resolve_session_scenario(&session.scenario_id)
+            .await?;
+        if let Some(scenario) = &scenario {
+            // Fill what the client did not send from the stored scenario, so
+            // custom scenarios are judged by their own bar.
+            if request.passing_score.is_none() {
+                request.passing_score = scenario.passing_score;
+            }
+            if request.scenario_title.is_none() {
+                request.scenario_title = Some(scenario.title.clone());
+            }
+            if request.scenario_description.is_none() {
+                request.scenario_description = Some(scenario.description.clone());
+            }
+        }
+
         let criteria = if let Some(criteria) = request.criteria.clone() {
             if criteria.is_empty() {
                 None
@@ -67,9 +86,7 @@ impl EvaluationService {
         let criteria = if let Some(criteria) = criteria {
             criteria
         } else {
-            default_scenarios()
-                .into_iter()
-                .find(|s| s.id == session.scenario_id)
+            scenario
                 .map(|s| {
                     s.evaluation_criteria
                         .into_iter()
@@ -125,6 +142,14 @@ impl EvaluationService {
         let effective_plan = entitlement_service.resolve_effective_plan(user_id).await?;
         let entitlement_enforced = FeatureFlagService::new().is_entitlement_enforced();
 
+        let credit_service = CreditService::new(self.pool.clone());
+        let credit_scope = if entitlement_enforced {
+            CreditService::scope_for(user_id, &effective_plan)
+        } else {
+            None
+        };
+        let credit_cost = EVALUATION_CREDIT_COST * calibration_sample_count(&request) as i32;
+
         if entitlement_enforced {
             enforce_evaluation_daily_limit(
                 &self.pool,
@@ -134,21 +159,43 @@ impl EvaluationService {
             )
             .await?;
         }
+        if let Some(scope) = &credit_scope {
+            credit_service.ensure_available(scope, credit_cost).await?;
+        }
 
-        // Create evaluation via Gemini
-        let eval = generate_ai_evaluation(
+        // Create evaluation via the plan's LLM provider
+        let generated = generate_ai_evaluation(
             &request,
             &criteria,
             &messages,
             session_id,
+            &session.scenario_id,
             &effective_plan.plan_code,
         )
         .await?;
-        eval_repo
-            .create_in_tx(&mut tx, &eval)
+        let created = eval_repo
+            .create_in_tx(
+                &mut tx,
+                &generated.evaluation,
+                Some(&generated.model_id),
+                generated.sample_count,
+            )
             .await
             .map_err(|e| anyhow_error(&format!("Failed to create evaluation: {}", e)))?;
 
+        if let Some(scope) = &credit_scope {
+            credit_service
+                .consume_in_tx(
+                    &mut tx,
+                    scope,
+                    credit_cost,
+                    "evaluation",
+                    "evaluation",
+                    &created.id.to_string(),
+                )
+                .await?;
+        }
+
         // Persist evaluated state so Team Management can detect completion.
         session_repo
             .mark_evaluated_in_tx(&mut tx, session_id)
@@ -159,8 +206,72 @@ impl EvaluationService {
             .await
             .map_err(|e| anyhow_error(&format!("Failed to commit transaction: {}", e)))?;
 
-        Ok(eval)
+        Ok(created)
     }
+
+    pub async fn list_session_evaluations(
+        &self,
+        session_id: &str,
+        user_id: &str,
+    ) -> Result<Vec<EvaluationAttempt>, AppError> {
+        let access = authorize_session_access(&self.pool, session_id, user_id).await?;
+        if !access.can_view() {
+            return Err(forbidden_error(
+                "FORBIDDEN_ROLE: insufficient permission for evaluation history view",
+            ));
+        }
+
+        EvaluationRepository::new(self.pool.clone())
+            .list_by_session(session_id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to list evaluations: {e}")))
+    }
+
+    pub async fn mark_official_evaluation(
+        &self,
+        session_id: &str,
+        attempt: i32,
+        user_id: &str,
+    ) -> Result<EvaluationAttempt, AppError> {
+        let access = authorize_session_access(&self.pool, session_id, user_id).await?;
+        if !access.can_select_official_evaluation() {
+            return Err(forbidden_error(
+                "FORBIDDEN_ROLE: insufficient permission for official evaluation selection",
+            ));
+        }
+
+        let eval_repo = EvaluationRepository::new(self.pool.clone());
+        let updated = eval_repo
+            .set_official(session_id, attempt)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to mark official evaluation: {e}")))?;
+        if !updated {
+            return Err(AppError::new(
+                StatusCode::NOT_FOUND,
+                anyhow::anyhow!("evaluation attempt not found"),
+            ));
+        }
+
+        eval_repo
+            .get_attempt(session_id, attempt)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to load evaluation: {e}")))?
+            .ok_or_else(|| {
+                AppError::new(
+                    StatusCode::NOT_FOUND,
+                    anyhow::anyhow!("evaluation attempt not found"),
+                )
+            })
+    }
+}
+
+/// Upper bound on samples per calibrated evaluation, to cap LLM cost.
+const MAX_CALIBRATION_SAMPLES: u32 = 5;
+
+struct GeneratedEvaluation {
+    evaluation: Evaluation,
+    model_id: String,
+    sample_count: i32,
 }
 
 #[derive(Deserialize)]
@@ -323,101 +434,52 @@ fn build_evaluation_template_json(criteria: &[EvaluationCriterion]) -> String {
     })
 }
 
-async fn call_gemini_evaluation(
+async fn call_evaluation_model(
+    provider: &dyn LlmProvider,
+    scenario_id: &str,
     system_instruction: String,
     input_text: String,
-    model_id: &str,
-    gemini_key: &str,
+    criteria: &[EvaluationCriterion],
 ) -> Result<String, AppError> {
     let max_output_tokens = std::env::var("GEMINI_EVAL_MAX_OUTPUT_TOKENS")
         .ok()
         .and_then(|value| value.parse::<u32>().ok())
         .unwrap_or(2048);
-    let payload = json!({
-        "contents": [
-            {
-                "role": "user",
-                "parts": [{ "text": input_text }]
-            }
-        ],
-        "systemInstruction": { "parts": [{ "text": system_instruction }] },
-        "generationConfig": {
-            "temperature": 0,
-            "maxOutputTokens": max_output_tokens,
-            "responseMimeType": "application/json"
-        }
-    });
-
-    let url = format!(
-        "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}",
-        model_id, gemini_key
-    );
+    let request = LlmRequest {
+        purpose: LlmPurpose::Evaluation,
+        scenario_id: Some(scenario_id.to_string()),
+        system_instruction,
+        messages: vec![LlmMessage::user(input_text)],
+        temperature: 0.0,
+        max_output_tokens,
+        top_p: None,
+        top_k: None,
+        json_template: Some(build_evaluation_template(criteria)),
+    };
 
-    let client = Client::new();
-    let res = client
-        .post(url)
-        .header("Content-Type", "application/json")
-        .json(&payload)
-        .send()
-        .await
-        .map_err(|e| anyhow_error(format!("Gemini evaluation failed: {}", e)))?;
-
-    if !res.status().is_success() {
-        let status = res.status();
-        let text = res.text().await.unwrap_or_default();
-        return Err(anyhow_error(format!(
-            "Gemini evaluation error {}: {}",
-            status, text
-        )));
-    }
-
-    let data: serde_json::Value = res
-        .json()
-        .await
-        .map_err(|e| anyhow_error(format!("Failed to parse Gemini evaluation response: {}", e)))?;
-
-    let finish_reason = data
-        .get("candidates")
-        .and_then(|v| v.get(0))
-        .and_then(|v| v.get("finishReason"))
-        .and_then(|v| v.as_str())
-        .unwrap_or("unknown");
-
-    let reply_text = data
-        .get("candidates")
-        .and_then(|v| v.get(0))
-        .and_then(|v| v.get("content"))
-        .and_then(|v| v.get("parts"))
-        .and_then(|v| v.as_array())
-        .map(|parts| {
-            parts
-                .iter()
-                .filter_map(|p| p.get("text").and_then(|t| t.as_str()))
-                .collect::<Vec<_>>()
-                .join("")
-                .trim()
-                .to_string()
-        })
-        .unwrap_or_default();
+    let response = provider.generate(&request).await.map_err(|e| {
+        anyhow_error(format!(
+            "Evaluation failed ({}): {e}",
+            provider.provider_name()
+        ))
+    })?;
+    let reply_text = response.text.trim().to_string();
+    let finish_reason = response.finish_reason.as_deref().unwrap_or("unknown");
 
     if reply_text.is_empty() {
-        let candidate_count = data
-            .get("candidates")
-            .and_then(|v| v.as_array())
-            .map(|c| c.len())
-            .unwrap_or(0);
         warn!(
-            model_id = %model_id,
+            provider = provider.provider_name(),
+            model_id = %provider.model_id(),
             finish_reason = %finish_reason,
-            candidate_count,
-            "Gemini evaluation returned empty content"
+            "Evaluation model returned empty content"
         );
-    } else if finish_reason == "MAX_TOKENS" {
+    } else if response.is_truncated() {
         warn!(
-            model_id = %model_id,
+            provider = provider.provider_name(),
+            model_id = %provider.model_id(),
             finish_reason = %finish_reason,
             reply_len = reply_text.len(),
-            "Gemini evaluation output may be truncated"
+            "Evaluation output may be truncated"
         );
     }
 
@@ -429,13 +491,10 @@ async fn generate_ai_evaluation(
     criteria: &[EvaluationCriterion],
     messages: &[Message],
     session_id: &str,
+    scenario_id: &str,
     plan_code: &PlanCode,
-) -> Result<Evaluation, AppError> {
-    let credentials = resolve_eval_credentials(plan_code, None)?;
-    let gemini_key = credentials.api_key;
-    let eval_model = credentials.model_id;
-
-    let system_instruction = build_evaluation_instruction(request, criteria, false);
+) -> Result<GeneratedEvaluation, AppError> {
+    let provider = resolve_eval_provider(plan_code)?;
 
     let transcript = messages
         .iter()
@@ -451,17 +510,60 @@ async fn generate_ai_evaluation(
         .collect::<Vec<_>>()
         .join("\n");
 
-    let reply_text = call_gemini_evaluation(
+    let sample_count = calibration_sample_count(request);
+    let mut outputs = Vec::with_capacity(sample_count);
+    for _ in 0..sample_count {
+        let output = request_evaluation_output(
+            provider.as_ref(),
+            request,
+            criteria,
+            &transcript,
+            session_id,
+            scenario_id,
+        )
+        .await?;
+        outputs.push(output);
+    }
+
+    let evaluation = if sample_count > 1 {
+        build_calibrated_evaluation(request, criteria, outputs, session_id)
+    } else {
+        let output = outputs
+            .pop()
+            .ok_or_else(|| anyhow_error("Evaluation model returned no output"))?;
+        build_evaluation(request, criteria, output, session_id)
+    };
+
+    Ok(GeneratedEvaluation {
+        evaluation,
+        model_id: provider.model_id().to_string(),
+        sample_count: sample_count as i32,
+    })
+}
+
+/// Run one evaluation sample, retrying with a strict prompt and a JSON
+/// repair pass when the model does not return usable JSON.
+async fn request_evaluation_output(
+    provider: &dyn LlmProvider,
+    request: &EvaluationRequest,
+    criteria: &[EvaluationCriterion],
+    transcript: &str,
+    session_id: &str,
+    scenario_id: &str,
+) -> Result<EvaluationOutput, AppError> {
+    let system_instruction = build_evaluation_instruction(request, criteria, false);
+
+    let reply_text = call_evaluation_model(
+        provider,
+        scenario_id,
         system_instruction,
-        transcript.clone(),
-        &eval_model,
-        &gemini_key,
+        transcript.to_string(),
+        criteria,
     )
     .await?;
 
-    let needs_retry = |v: &Option<serde_json::Value>| {
-        v.as_ref().map_or(true, |j| j.get("categories").is_none())
-    };
+    let needs_retry =
+        |v: &Option<serde_json::Value>| v.as_ref().map_or(true, |j| j.get("categories").is_none());
 
     let mut json_value = extract_json_value(&reply_text);
     if needs_retry(&json_value) {
@@ -470,14 +572,15 @@ async fn generate_ai_evaluation(
             attempt = "initial",
             reply_len = reply_text.len(),
             reply_preview = %preview_for_log(&reply_text, 600),
-            "Gemini evaluation returned non-JSON or missing categories"
+            "Evaluation model returned non-JSON or missing categories"
         );
         let strict_instruction = build_evaluation_instruction(request, criteria, true);
-        let strict_reply = call_gemini_evaluation(
+        let strict_reply = call_evaluation_model(
+            provider,
+            scenario_id,
             strict_instruction,
-            transcript.clone(),
-            &eval_model,
-            &gemini_key,
+            transcript.to_string(),
+            criteria,
         )
         .await?;
         json_value = extract_json_value(&strict_reply);
@@ -487,7 +590,7 @@ async fn generate_ai_evaluation(
                 attempt = "strict",
                 reply_len = strict_reply.len(),
                 reply_preview = %preview_for_log(&strict_reply, 600),
-                "Gemini evaluation strict output still invalid or missing categories"
+                "Evaluation model strict output still invalid or missing categories"
             );
             let template = build_evaluation_template_json(criteria);
             let repair_instruction = format!(
@@ -498,9 +601,14 @@ async fn generate_ai_evaluation(
                 "評価ドラフト:\n{}\n\n会話ログ:\n{}",
                 strict_reply, transcript
             );
-            let repaired_reply =
-                call_gemini_evaluation(repair_instruction, repair_input, &eval_model, &gemini_key)
-                    .await?;
+            let repaired_reply = call_evaluation_model(
+                provider,
+                scenario_id,
+                repair_instruction,
+                repair_input,
+                criteria,
+            )
+            .await?;
             json_value = extract_json_value(&repaired_reply);
             if needs_retry(&json_value) {
                 warn!(
@@ -508,27 +616,37 @@ async fn generate_ai_evaluation(
                     attempt = "repair",
                     reply_len = repaired_reply.len(),
                     reply_preview = %preview_for_log(&repaired_reply, 600),
-                    "Gemini evaluation repair output still invalid or missing categories"
+                    "Evaluation model repair output still invalid or missing categories"
                 );
             }
         }
     }
 
     let json_value =
-        json_value.ok_or_else(|| anyhow_error("Gemini evaluation returned invalid JSON"))?;
+        json_value.ok_or_else(|| anyhow_error("Evaluation model returned invalid JSON"))?;
 
     // Log the JSON being parsed for debugging
     tracing::info!("=== EVALUATION JSON ===");
-    tracing::info!("Parsed JSON: {}", serde_json::to_string_pretty(&json_value).unwrap_or_else(|_| "Failed to stringify".to_string()));
+    tracing::info!(
+        "Parsed JSON: {}",
+        serde_json::to_string_pretty(&json_value)
+            .unwrap_or_else(|_| "Failed to stringify".to_string())
+    );
     tracing::info!("======================");
 
-    let output: EvaluationOutput = serde_json::from_value(json_value.clone())
-        .map_err(|e| {
-            tracing::error!("JSON deserialization error: {}", e);
-            tracing::error!("JSON value: {:?}", json_value);
-            anyhow_error(format!("Failed to decode evaluation JSON: {}", e))
-        })?;
+    serde_json::from_value(json_value.clone()).map_err(|e| {
+        tracing::error!("JSON deserialization error: {}", e);
+        tracing::error!("JSON value: {:?}", json_value);
+        anyhow_error(format!("Failed to decode evaluation JSON: {}", e))
+    })
+}
 
+fn build_evaluation(
+    request: &EvaluationRequest,
+    criteria: &[EvaluationCriterion],
+    output: EvaluationOutput,
+    session_id: &str,
+) -> Evaluation {
     let mut categories = Vec::new();
     for criterion in criteria {
         let matched = output.categories.iter().find(|c| c.name == criterion.name);
@@ -545,31 +663,154 @@ async fn generate_ai_evaluation(
             weight: criterion.weight,
             score: Some(score),
             feedback: Some(feedback),
+            score_variance: None,
+            sample_count: None,
         });
     }
 
-    let total_weight: f32 = categories.iter().map(|c| c.weight).sum();
-    let computed_overall = if total_weight > 0.0 {
-        categories
-            .iter()
-            .map(|c| c.score.unwrap_or(0.0) * c.weight)
-            .sum::<f32>()
-            / total_weight
-    } else {
-        0.0
-    };
+    let computed_overall = weighted_overall_score(&categories);
     let overall_score = output.overall_score.unwrap_or(computed_overall).round();
     let passing_score = request.passing_score.unwrap_or(70.0);
     let passing = overall_score >= passing_score;
 
-    Ok(Evaluation {
+    Evaluation {
         session_id: session_id.to_string(),
         overall_score: Some(overall_score),
         passing: Some(passing),
         categories,
         summary: output.summary.filter(|s| !s.trim().is_empty()),
         improvement_advice: output.improvement_advice.filter(|s| !s.trim().is_empty()),
-    })
+    }
+}
+
+/// Aggregate several samples: each category takes the median score of the
+/// samples that scored it, with their variance and count recorded, and the
+/// overall score is recomputed from the weights instead of trusting any
+/// sample's `overallScore`.
+fn build_calibrated_evaluation(
+    request: &EvaluationRequest,
+    criteria: &[EvaluationCriterion],
+    outputs: Vec<EvaluationOutput>,
+    session_id: &str,
+) -> Evaluation {
+    let mut categories = Vec::new();
+    for criterion in criteria {
+        // Samples that left the category unscored do not count towards it.
+        let samples = outputs
+            .iter()
+            .filter_map(|output| {
+                let matched = output.categories.iter().find(|c| c.name == criterion.name);
+                sample_category_score(output, &criterion.name)
+                    .map(|score| (score, matched.and_then(|c| c.feedback.as_deref())))
+            })
+            .collect::<Vec<_>>();
+        let scores = samples.iter().map(|(score, _)| *score).collect::<Vec<_>>();
+        let score = median(&scores);
+        // Keep the comment written for the sample closest to the median.
+        let feedback = samples
+            .iter()
+            .filter(|(_, feedback)| feedback.is_some_and(|f| !f.trim().is_empty()))
+            .min_by(|a, b| (a.0 - score).abs().total_cmp(&(b.0 - score).abs()))
+            .and_then(|(_, feedback)| feedback.map(str::to_string))
+            .unwrap_or_else(|| "評価コメントが不足しています。".to_string());
+        categories.push(EvaluationCategory {
+            name: criterion.name.clone(),
+            weight: criterion.weight,
+            score: Some(score),
+            feedback: Some(feedback),
+            score_variance: Some(variance(&scores)),
+            sample_count: Some(scores.len() as u32),
+        });
+    }
+
+    let overall_score = weighted_overall_score(&categories).round();
+    let passing_score = request.passing_score.unwrap_or(70.0);
+    let passing = overall_score >= passing_score;
+
+    // Summary and advice come from the sample that agrees best overall.
+    let representative = outputs.into_iter().min_by(|a, b| {
+        let distance = |output: &EvaluationOutput| {
+            (sample_overall_score(output, criteria) - overall_score).abs()
+        };
+        distance(a).total_cmp(&distance(b))
+    });
+    let (summary, improvement_advice) = representative
+        .map(|output| (output.summary, output.improvement_advice))
+        .unwrap_or((None, None));
+
+    Evaluation {
+        session_id: session_id.to_string(),
+        overall_score: Some(overall_score),
+        passing: Some(passing),
+        categories,
+        summary: summary.filter(|s| !s.trim().is_empty()),
+        improvement_advice: improvement_advice.filter(|s| !s.trim().is_empty()),
+    }
+}
+
+fn sample_category_score(output: &EvaluationOutput, name: &str) -> Option<f32> {
+    output
+        .categories
+        .iter()
+        .find(|c| c.name == name)
+        .and_then(|c| c.score)
+        .map(|score| score.clamp(0.0, 100.0))
+}
+
+fn sample_overall_score(output: &EvaluationOutput, criteria: &[EvaluationCriterion]) -> f32 {
+    let total_weight: f32 = criteria.iter().map(|c| c.weight).sum();
+    if total_weight > 0.0 {
+        criteria
+            .iter()
+            .map(|c| sample_category_score(output, &c.name).unwrap_or(0.0) * c.weight)
+            .sum::<f32>()
+            / total_weight
+    } else {
+        0.0
+    }
+}
+
+fn calibration_sample_count(request: &EvaluationRequest) -> usize {
+    request
+        .calibration_samples
+        .unwrap_or(1)
+        .clamp(1, MAX_CALIBRATION_SAMPLES) as usize
+}
+
+fn weighted_overall_score(categories: &[EvaluationCategory]) -> f32 {
+    let total_weight: f32 = categories.iter().map(|c| c.weight).sum();
+    if total_weight > 0.0 {
+        categories
+            .iter()
+            .map(|c| c.score.unwrap_or(0.0) * c.weight)
+            .sum::<f32>()
+            / total_weight
+    } else {
+        0.0
+    }
+}
+
+fn median(values: &[f32]) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f32::total_cmp);
+    let mid = sorted.len() / 2;
+    if sorted.len() % 2 == 0 {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+/// Population variance of the sample scores.
+fn variance(values: &[f32]) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mean = values.iter().sum::<f32>() / values.len() as f32;
+    values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / values.len() as f32
 }
 
 #[cfg(test)]
@@ -604,6 +845,7 @@ mod tests {
             requirement_definition_context: None,
             incident_response_context: None,
             business_execution_context: None,
+            calibration_samples: None,
         }
     }
 
@@ -804,4 +1046,93 @@ mod tests {
         assert_eq!(result, "abcde");
         assert!(!result.ends_with('…'));
     }
+
+    // ---------------------------------------------------------------------------
+    // calibrated evaluation
+    // ---------------------------------------------------------------------------
+
+    fn sample_output(scores: &[(&str, f32)], overall: f32, summary: &str) -> EvaluationOutput {
+        EvaluationOutput {
+            categories: scores
+                .iter()
+                .map(|(name, score)| EvaluationOutputCategory {
+                    name: name.to_string(),
+                    score: Some(*score),
+                    feedback: Some(format!("{name}: {score}")),
+                })
+                .collect(),
+            overall_score: Some(overall),
+            summary: Some(summary.to_string()),
+            improvement_advice: None,
+        }
+    }
+
+    #[test]
+    fn median_handles_odd_and_even_counts() {
+        assert_eq!(median(&[90.0, 60.0, 70.0]), 70.0);
+        assert_eq!(median(&[60.0, 90.0, 70.0, 80.0]), 75.0);
+        assert_eq!(median(&[]), 0.0);
+    }
+
+    #[test]
+    fn variance_is_zero_for_identical_scores() {
+        assert_eq!(variance(&[70.0, 70.0, 70.0]), 0.0);
+        assert_eq!(variance(&[60.0, 80.0]), 100.0);
+    }
+
+    #[test]
+    fn calibrated_evaluation_uses_category_medians_and_weights() {
+        let criteria = vec![make_criterion("A", 75.0), make_criterion("B", 25.0)];
+        let outputs = vec![
+            sample_output(&[("A", 50.0), ("B", 100.0)], 99.0, "low"),
+            sample_output(&[("A", 80.0), ("B", 40.0)], 99.0, "middle"),
+            sample_output(&[("A", 100.0), ("B", 40.0)], 99.0, "high"),
+        ];
+
+        let evaluation =
+            build_calibrated_evaluation(&make_request(), &criteria, outputs, "session-1");
+
+        // Medians are A=80, B=40; the model's overallScore of 99 is ignored.
+        assert_eq!(evaluation.overall_score, Some(70.0));
+        assert_eq!(evaluation.passing, Some(true));
+        assert_eq!(evaluation.categories[0].score, Some(80.0));
+        assert_eq!(evaluation.categories[0].feedback.as_deref(), Some("A: 80"));
+        assert_eq!(evaluation.categories[1].score, Some(40.0));
+        assert_eq!(evaluation.categories[1].score_variance, Some(800.0));
+        assert_eq!(evaluation.categories[1].sample_count, Some(3));
+        assert_eq!(evaluation.summary.as_deref(), Some("middle"));
+    }
+
+    #[test]
+    fn calibrated_evaluation_leaves_unscored_samples_out_of_a_category() {
+        let criteria = vec![make_criterion("A", 50.0), make_criterion("B", 50.0)];
+        let outputs = vec![
+            sample_output(&[("A", 80.0), ("B", 60.0)], 70.0, "first"),
+            sample_output(&[("A", 90.0)], 45.0, "second"),
+            sample_output(&[("A", 70.0), ("B", 80.0)], 75.0, "third"),
+        ];
+
+        let evaluation =
+            build_calibrated_evaluation(&make_request(), &criteria, outputs, "session-1");
+
+        assert_eq!(evaluation.categories[0].score, Some(80.0));
+        assert_eq!(evaluation.categories[0].sample_count, Some(3));
+        // B is the median of 60 and 80 only, not of 0, 60 and 80.
+        assert_eq!(evaluation.categories[1].score, Some(70.0));
+        assert_eq!(evaluation.categories[1].score_variance, Some(100.0));
+        assert_eq!(evaluation.categories[1].sample_count, Some(2));
+    }
+
+    #[test]
+    fn calibration_sample_count_is_clamped() {
+        let mut request = make_request();
+        assert_eq!(calibration_sample_count(&request), 1);
+        request.calibration_samples = Some(0);
+        assert_eq!(calibration_sample_count(&request), 1);
+        request.calibration_samples = Some(50);
+        assert_eq!(
+            calibration_sample_count(&request),
+            MAX_CALIBRATION_SAMPLES as usize
+        );
+    }
 }
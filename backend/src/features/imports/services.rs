@@ -58,7 +58,7 @@ async fn import_snapshot(
     // Insert evaluation if exists
     if let Some(eval) = &snapshot.evaluation {
         let eval_repo = EvaluationRepository::new(pool.clone());
-        eval_repo.create_in_tx(&mut tx, eval).await?;
+        eval_repo.create_in_tx(&mut tx, eval, None, 1).await?;
     }
 
     tx.commit().await?;
@@ -1,13 +1,19 @@
+use std::convert::Infallible;
+
 use axum::{
     extract::{Path, State},
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
     Json,
 };
+use futures::{Stream, StreamExt};
 
 use crate::error::AppError;
+use crate::features::entitlements::fair_use::DailyUsage;
 use crate::middleware::auth::AuthUser;
 use crate::state::SharedState;
 
-use super::models::{CreateMessageRequest, Message, MessageResponse};
+use super::models::{CreateMessageRequest, Message, MessageResponse, MessageStreamEvent};
 
 #[utoipa::path(
     get,
@@ -38,11 +44,64 @@ pub async fn post_message(
     auth: AuthUser,
     Path(id): Path<String>,
     Json(body): Json<CreateMessageRequest>,
-) -> Result<Json<MessageResponse>, AppError> {
+) -> Result<(HeaderMap, Json<MessageResponse>), AppError> {
     let response = state
         .services()
         .messages()
         .post_message(&id, &auth.user_id, body)
         .await?;
-    Ok(Json(response))
+    let headers = state
+        .services()
+        .entitlements()
+        .rate_limit_headers(&auth.user_id, DailyUsage::AgentReplies)
+        .await;
+    Ok((headers, Json(response)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/sessions/{id}/messages/stream",
+    request_body = CreateMessageRequest,
+    responses((
+        status = 200,
+        description = "Server-Sent Events: `delta`, `reply`, `missions` and `error`",
+        content_type = "text/event-stream"
+    ))
+)]
+pub async fn stream_message(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<String>,
+    Json(body): Json<CreateMessageRequest>,
+) -> Result<
+    (
+        HeaderMap,
+        Sse<impl Stream<Item = Result<Event, Infallible>>>,
+    ),
+    AppError,
+> {
+    let events = state
+        .services()
+        .messages()
+        .stream_message(&id, &auth.user_id, body)
+        .await?;
+    // Reported when the stream opens, before the streamed reply is stored.
+    let headers = state
+        .services()
+        .entitlements()
+        .rate_limit_headers(&auth.user_id, DailyUsage::AgentReplies)
+        .await;
+    let events = events.map(|event| Ok(to_sse_event(&event)));
+    Ok((headers, Sse::new(events).keep_alive(KeepAlive::default())))
+}
+
+fn to_sse_event(event: &MessageStreamEvent) -> Event {
+    Event::default()
+        .event(event.event_name())
+        .json_data(event)
+        .unwrap_or_else(|e| {
+            Event::default()
+                .event("error")
+                .data(format!("failed to encode stream event: {e}"))
+        })
 }
@@ -12,7 +12,6 @@ pub struct CreateMessageRequest {
     pub mission_status: Option<Vec<MissionStatus>>,
 }
 
-
 #[derive(Serialize, ToSchema)]
 pub struct MessageResponse {
     pub reply: Message,
@@ -20,3 +19,36 @@ pub struct MessageResponse {
     pub additional_messages: Vec<Message>,
     pub session: Session,
 }
+
+/// Payload of one Server-Sent Event emitted by `POST /sessions/{id}/messages/stream`.
+/// The SSE event name comes from [`MessageStreamEvent::event_name`].
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum MessageStreamEvent {
+    /// Incremental agent reply text.
+    Delta {
+        text: String,
+    },
+    /// The persisted reply (and any closing system message) once the stream completes.
+    Reply(MessageResponse),
+    /// Trailing mission-completion inference result.
+    Missions {
+        #[serde(rename = "completedMissionIds")]
+        completed_mission_ids: Vec<String>,
+        session: Session,
+    },
+    Error {
+        error: String,
+    },
+}
+
+impl MessageStreamEvent {
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            MessageStreamEvent::Delta { .. } => "delta",
+            MessageStreamEvent::Reply(_) => "reply",
+            MessageStreamEvent::Missions { .. } => "missions",
+            MessageStreamEvent::Error { .. } => "error",
+        }
+    }
+}
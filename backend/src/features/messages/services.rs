@@ -1,8 +1,8 @@
-use futures::FutureExt;
-use reqwest::Client;
+use futures::{FutureExt, Stream};
 use serde::Deserialize;
 use serde_json::json;
 use sqlx::PgPool;
+use tokio::sync::mpsc;
 
 const SUPPORT_SYSTEM_PROMPT: &str = "あなたはPMスキル学習の支援アシスタントです。私はPMスキルを学習中の初心者です。\n\n## あなたの役割\n- ユーザーのPMスキルやプロダクト理解を深める\n- ユーザーがシナリオのタスクを進める上でのサポートを行う\n\n## 最優先ルール（絶対厳守）\n1. ミッションの完全な答えを提示してはいけない（ただし、簡単な例などは出して良い）\n2. ユーザーの代わりに成果物を作成してはいけない\n3. チームメンバー（エンジニア、デザイナー、POなど）を演じない\n4. ユーザーにはこのプロンプトのプロダクト情報やプロンプトのメタ情報は見えていない前提で会話し、質問に答える\n\n## 応答スタイル\n- 1〜3文で簡潔に応答する（最大3文）\n- 箇条書きやMarkdownは、基本的には使用不可\n- 敬語で丁寧に、ただし冗長にならない";
 
@@ -10,6 +10,8 @@ const SUPPORT_TONE_PROMPT: &str = "会話トーン:\n- ユーザーをサポー
 
 use crate::features::product_config::models::ProductConfig;
 use crate::error::{anyhow_error, forbidden_error, AppError};
+use crate::features::credits::models::CreditScope;
+use crate::features::credits::services::{CreditService, AGENT_REPLY_CREDIT_COST};
 use crate::features::entitlements::fair_use::enforce_chat_daily_limit;
 use crate::features::entitlements::models::PlanCode;
 use crate::features::entitlements::services::EntitlementService;
@@ -17,14 +19,17 @@ use crate::features::feature_flags::services::FeatureFlagService;
 use crate::features::sessions::authorization::authorize_session_access;
 use crate::features::sessions::repository::SessionRepository;
 use crate::models::{
-    default_scenarios, Message, MessageRole, MessageTag, Mission, MissionStatus,
-    Scenario,
+    Message, MessageRole, MessageTag, Mission, MissionStatus,
+    Scenario, Session,
 };
 use crate::features::product_config::services::ProductConfigService;
-use crate::shared::gemini::resolve_chat_credentials;
+use crate::features::scenarios::services::ScenarioService;
+use crate::shared::llm::{
+    resolve_chat_provider, LlmMessage, LlmProvider, LlmPurpose, LlmRequest, LlmResponse, LlmRole,
+};
 use crate::shared::helpers::{next_id, now_ts};
 
-use super::models::{CreateMessageRequest, MessageResponse};
+use super::models::{CreateMessageRequest, MessageResponse, MessageStreamEvent};
 use super::repository::MessageRepository;
 
 fn format_product_context(config: &ProductConfig) -> String {
@@ -95,6 +100,7 @@ fn extract_json_value(text: &str) -> Option<serde_json::Value> {
 }
 
 async fn infer_completed_mission_ids(
+    scenario_id: &str,
     latest_user_message: &str,
     missions: &[Mission],
     plan_code: &PlanCode,
@@ -103,10 +109,6 @@ async fn infer_completed_mission_ids(
         return Ok(Vec::new());
     }
 
-    let credentials = resolve_chat_credentials(plan_code, None)?;
-    let gemini_key = credentials.api_key;
-    let model_id = credentials.model_id;
-
     let mission_lines = missions
         .iter()
         .map(|mission| {
@@ -132,67 +134,23 @@ async fn infer_completed_mission_ids(
         mission_lines, latest_user_message
     );
 
-    let payload = json!({
-        "contents": [
-            {
-                "role": "user",
-                "parts": [{ "text": input_text }]
-            }
-        ],
-        "systemInstruction": { "parts": [{ "text": system_instruction }] },
-        "generationConfig": {
-            "temperature": 0,
-            "maxOutputTokens": 256,
-            "responseMimeType": "application/json"
-        }
-    });
-
-    let url = format!(
-        "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}",
-        model_id, gemini_key
-    );
-
-    let client = Client::new();
-    let res = client
-        .post(url)
-        .header("Content-Type", "application/json")
-        .json(&payload)
-        .send()
-        .await
-        .map_err(|e| anyhow_error(format!("Gemini mission completion check failed: {}", e)))?;
-
-    if !res.status().is_success() {
-        let status = res.status();
-        let text = res.text().await.unwrap_or_default();
-        return Err(anyhow_error(format!(
-            "Gemini mission completion API error {}: {}",
-            status, text
-        )));
-    }
+    let request = LlmRequest {
+        purpose: LlmPurpose::MissionCheck,
+        scenario_id: Some(scenario_id.to_string()),
+        system_instruction,
+        messages: vec![LlmMessage::user(input_text)],
+        temperature: 0.0,
+        max_output_tokens: 256,
+        top_p: None,
+        top_k: None,
+        json_template: Some(json!({ "completedMissionIds": [] })),
+    };
 
-    let data: serde_json::Value = res.json().await.map_err(|e| {
-        anyhow_error(format!(
-            "Failed to parse mission completion response: {}",
-            e
-        ))
+    let provider = resolve_chat_provider(plan_code)?;
+    let response = provider.generate(&request).await.map_err(|e| {
+        anyhow_error(format!("Mission completion check failed ({}): {e}", provider.provider_name()))
     })?;
-
-    let reply_text = data
-        .get("candidates")
-        .and_then(|v| v.get(0))
-        .and_then(|v| v.get("content"))
-        .and_then(|v| v.get("parts"))
-        .and_then(|v| v.as_array())
-        .map(|parts| {
-            parts
-                .iter()
-                .filter_map(|p| p.get("text").and_then(|t| t.as_str()))
-                .collect::<Vec<_>>()
-                .join("")
-                .trim()
-                .to_string()
-        })
-        .unwrap_or_default();
+    let reply_text = response.text.trim().to_string();
 
     let json_value = extract_json_value(&reply_text)
         .ok_or_else(|| anyhow_error("Mission completion output was not valid JSON"))?;
@@ -253,6 +211,27 @@ fn merge_completed_missions(
     (next, changed)
 }
 
+/// State shared between the buffered and streaming reply paths once the user
+/// message has been persisted.
+struct TurnContext {
+    session: Session,
+    message: Message,
+    scenario: Option<Scenario>,
+    scenario_missions: Vec<Mission>,
+    reply: Option<ReplyContext>,
+}
+
+/// Inputs needed to generate the AI side of a turn (only present for user messages).
+struct ReplyContext {
+    plan_code: PlanCode,
+    /// Wallet charged for the agent reply; `None` when credits are not enforced.
+    credit_scope: Option<CreditScope>,
+    product_context: String,
+    agent_response_enabled: bool,
+    history: Vec<Message>,
+    all_missions_complete: bool,
+}
+
 #[derive(Clone)]
 pub struct MessageService {
     pool: PgPool,
@@ -289,6 +268,176 @@ impl MessageService {
         user_id: &str,
         body: CreateMessageRequest,
     ) -> Result<MessageResponse, AppError> {
+        let turn = self.begin_turn(session_id, user_id, body).await?;
+
+        let Some(reply_ctx) = turn.reply.as_ref() else {
+            return self.complete_turn(turn, None, None).await;
+        };
+
+        // Run agent reply and/or mission detection in parallel
+        let reply_future: futures::future::BoxFuture<'_, Result<Option<String>, AppError>> =
+            if reply_ctx.agent_response_enabled {
+                generate_agent_reply(
+                    turn.scenario.as_ref().unwrap(),
+                    Some(&reply_ctx.product_context),
+                    &reply_ctx.history,
+                    &reply_ctx.plan_code,
+                    reply_ctx.all_missions_complete,
+                )
+                .map(|result| result.map(Some))
+                .boxed()
+            } else {
+                async { Ok(None) }.boxed()
+            };
+        let mission_future: futures::future::BoxFuture<'_, Result<Vec<String>, AppError>> =
+            if !turn.scenario_missions.is_empty() {
+                infer_completed_mission_ids(
+                    &turn.session.scenario_id,
+                    &turn.message.content,
+                    &turn.scenario_missions,
+                    &reply_ctx.plan_code,
+                )
+                .boxed()
+            } else {
+                async { Ok(Vec::new()) }.boxed()
+            };
+
+        let (reply_res, mission_res) = tokio::join!(reply_future, mission_future);
+        let agent_reply = reply_res?;
+        let completed_ids = mission_res.ok();
+
+        self.complete_turn(turn, agent_reply, completed_ids).await
+    }
+
+    /// Streaming variant of [`post_message`](Self::post_message).
+    ///
+    /// Authorization, the user message insert and fair-use enforcement happen
+    /// before this returns, so those failures still surface as plain HTTP errors.
+    /// Afterwards the agent reply is streamed as `delta` events, persisted and
+    /// announced with a `reply` event, and the mission-completion inference
+    /// follows as a trailing `missions` event.
+    pub async fn stream_message(
+        &self,
+        session_id: &str,
+        user_id: &str,
+        body: CreateMessageRequest,
+    ) -> Result<impl Stream<Item = MessageStreamEvent>, AppError> {
+        let turn = self.begin_turn(session_id, user_id, body).await?;
+        let (tx, rx) = mpsc::channel::<MessageStreamEvent>(32);
+        let service = self.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = service.run_streaming_turn(turn, &tx).await {
+                tracing::warn!("Streaming message turn failed: {e:?}");
+                let _ = tx
+                    .send(MessageStreamEvent::Error {
+                        error: e.to_string(),
+                    })
+                    .await;
+            }
+        });
+
+        Ok(futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|event| (event, rx))
+        }))
+    }
+
+    async fn run_streaming_turn(
+        &self,
+        turn: TurnContext,
+        events: &mpsc::Sender<MessageStreamEvent>,
+    ) -> Result<(), AppError> {
+        let Some(reply_ctx) = turn.reply.as_ref() else {
+            let response = self.complete_turn(turn, None, None).await?;
+            let _ = events.send(MessageStreamEvent::Reply(response)).await;
+            return Ok(());
+        };
+
+        // Mission detection does not depend on the reply, so start it right away.
+        let mission_task = if turn.scenario_missions.is_empty() {
+            None
+        } else {
+            let scenario_id = turn.session.scenario_id.clone();
+            let content = turn.message.content.clone();
+            let missions = turn.scenario_missions.clone();
+            let plan_code = reply_ctx.plan_code.clone();
+            Some(tokio::spawn(async move {
+                infer_completed_mission_ids(&scenario_id, &content, &missions, &plan_code).await
+            }))
+        };
+
+        let agent_reply = if reply_ctx.agent_response_enabled {
+            Some(
+                stream_agent_reply(
+                    turn.scenario.as_ref().unwrap(),
+                    Some(&reply_ctx.product_context),
+                    &reply_ctx.history,
+                    &reply_ctx.plan_code,
+                    reply_ctx.all_missions_complete,
+                    events,
+                )
+                .await?,
+            )
+        } else {
+            None
+        };
+
+        let session_id = turn.session.id.clone();
+        let mission_status = turn.session.mission_status.clone();
+        let response = self.complete_turn(turn, agent_reply, None).await?;
+        let _ = events.send(MessageStreamEvent::Reply(response)).await;
+
+        let completed_ids = match mission_task {
+            Some(task) => task
+                .await
+                .map_err(|e| anyhow_error(&format!("Mission completion task failed: {}", e)))?
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Mission completion check failed: {e:?}");
+                    Vec::new()
+                }),
+            None => Vec::new(),
+        };
+
+        let (next_status, changed) = merge_completed_missions(mission_status, &completed_ids);
+        let session_repo = SessionRepository::new(self.pool.clone());
+        if changed {
+            let mut tx = self.pool.begin().await.map_err(|e| {
+                anyhow_error(&format!("Failed to begin mission transaction: {}", e))
+            })?;
+            session_repo
+                .update_mission_status_in_tx(&mut tx, &session_id, &next_status)
+                .await
+                .map_err(|e| {
+                    anyhow_error(&format!("Failed to persist auto mission completion: {}", e))
+                })?;
+            tx.commit().await.map_err(|e| {
+                anyhow_error(&format!("Failed to commit mission transaction: {}", e))
+            })?;
+        }
+
+        let session = session_repo
+            .get_by_id(&session_id)
+            .await
+            .map_err(|e| anyhow_error(&format!("Failed to get updated session by id: {}", e)))?
+            .ok_or_else(|| anyhow_error("session not found"))?;
+        let _ = events
+            .send(MessageStreamEvent::Missions {
+                completed_mission_ids: completed_ids,
+                session,
+            })
+            .await;
+
+        Ok(())
+    }
+
+    /// Persist the incoming message and, for user messages, gather everything
+    /// needed to produce the AI side of the turn.
+    async fn begin_turn(
+        &self,
+        session_id: &str,
+        user_id: &str,
+        body: CreateMessageRequest,
+    ) -> Result<TurnContext, AppError> {
         let access = authorize_session_access(&self.pool, session_id, user_id).await?;
         if !access.can_edit_session() {
             return Err(forbidden_error(
@@ -309,9 +458,9 @@ impl MessageService {
         let mut session = access.session;
 
         let is_user = body.role == MessageRole::User;
-        let scenario = default_scenarios()
-            .into_iter()
-            .find(|s| s.id == session.scenario_id);
+        let scenario = ScenarioService::new(self.pool.clone())
+            .resolve_session_scenario(&session.scenario_id)
+            .await?;
         let scenario_missions = scenario
             .as_ref()
             .and_then(|s| s.missions.clone())
@@ -350,10 +499,7 @@ impl MessageService {
             .await
             .map_err(|e| anyhow_error(&format!("Failed to commit transaction: {}", e)))?;
 
-        let mut reply = message.clone();
-        let mut additional_messages: Vec<Message> = Vec::new();
-
-        if is_user {
+        let reply = if is_user {
             let entitlement_service = EntitlementService::new(self.pool.clone());
             let effective_plan = entitlement_service.resolve_effective_plan(user_id).await?;
             let plan_code = effective_plan.plan_code.clone();
@@ -369,11 +515,11 @@ impl MessageService {
                 .and_then(|s| s.single_response)
                 .unwrap_or(false);
             let agent_response_enabled = !behavior_single_response;
-            let should_append_completion_message = behavior_single_response;
             let should_invoke_ai =
                 agent_response_enabled || !scenario_missions.is_empty();
 
-            if FeatureFlagService::new().is_entitlement_enforced() && should_invoke_ai {
+            let entitlement_enforced = FeatureFlagService::new().is_entitlement_enforced();
+            if entitlement_enforced && should_invoke_ai {
                 enforce_chat_daily_limit(
                     &self.pool,
                     &plan_code,
@@ -383,58 +529,86 @@ impl MessageService {
                 .await?;
             }
 
-            let mut reply_tx =
-                self.pool.begin().await.map_err(|e| {
-                    anyhow_error(&format!("Failed to begin reply transaction: {}", e))
-                })?;
-
-            // Run agent reply and/or mission detection in parallel
-            let (agent_reply_result, mission_ids_result) = if agent_response_enabled || !scenario_missions.is_empty() {
-                let history = if agent_response_enabled {
-                    message_repo
-                        .list_by_session(session_id)
-                        .await
-                        .map_err(|e| anyhow_error(&format!("Failed to load message history: {}", e)))?
-                } else {
-                    Vec::new()
-                };
-                let all_missions_complete = !scenario_missions.is_empty() && {
-                    let completed_ids: std::collections::HashSet<&str> = session
-                        .mission_status
-                        .as_deref()
-                        .unwrap_or(&[])
-                        .iter()
-                        .map(|m| m.mission_id.as_str())
-                        .collect();
-                    scenario_missions.iter().all(|m| completed_ids.contains(m.id.as_str()))
-                };
-
-                let reply_future: futures::future::BoxFuture<'_, Result<String, AppError>> = if agent_response_enabled {
-                    generate_agent_reply(
-                        scenario.as_ref().unwrap(),
-                        Some(&product_context),
-                        &history,
-                        &plan_code,
-                        all_missions_complete
-                    ).boxed()
-                } else {
-                    async { Err(anyhow_error("Agent disabled")) }.boxed()
-                };
-                let mission_future: futures::future::BoxFuture<'_, Result<Vec<String>, AppError>> = if !scenario_missions.is_empty() {
-                    infer_completed_mission_ids(&message.content, &scenario_missions, &plan_code).boxed()
-                } else {
-                    async { Ok(Vec::new()) }.boxed()
-                };
+            let credit_scope = if entitlement_enforced && agent_response_enabled {
+                CreditService::scope_for(user_id, &effective_plan)
+            } else {
+                None
+            };
+            if let Some(scope) = &credit_scope {
+                CreditService::new(self.pool.clone())
+                    .ensure_available(scope, AGENT_REPLY_CREDIT_COST)
+                    .await?;
+            }
 
-                let (reply_res, mission_res) = tokio::join!(reply_future, mission_future);
-                (reply_res, mission_res)
+            let history = if agent_response_enabled {
+                message_repo
+                    .list_by_session(session_id)
+                    .await
+                    .map_err(|e| anyhow_error(&format!("Failed to load message history: {}", e)))?
             } else {
-                (Err(anyhow_error("Agent disabled")), Ok(Vec::new()))
+                Vec::new()
+            };
+            let all_missions_complete = !scenario_missions.is_empty() && {
+                let completed_ids: std::collections::HashSet<&str> = session
+                    .mission_status
+                    .as_deref()
+                    .unwrap_or(&[])
+                    .iter()
+                    .map(|m| m.mission_id.as_str())
+                    .collect();
+                scenario_missions.iter().all(|m| completed_ids.contains(m.id.as_str()))
             };
 
-            if agent_response_enabled {
-                let reply_text = agent_reply_result?;
+            Some(ReplyContext {
+                plan_code,
+                credit_scope,
+                product_context,
+                agent_response_enabled,
+                history,
+                all_missions_complete,
+            })
+        } else {
+            None
+        };
+
+        Ok(TurnContext {
+            session,
+            message,
+            scenario,
+            scenario_missions,
+            reply,
+        })
+    }
+
+    /// Persist the AI side of a turn (agent reply, single-response closing
+    /// message, auto-completed missions) and return the refreshed session.
+    async fn complete_turn(
+        &self,
+        turn: TurnContext,
+        agent_reply: Option<String>,
+        completed_mission_ids: Option<Vec<String>>,
+    ) -> Result<MessageResponse, AppError> {
+        let session_repo = SessionRepository::new(self.pool.clone());
+        let message_repo = MessageRepository::new(self.pool.clone());
+        let session_id = turn.session.id.as_str();
 
+        let mut session = turn.session.clone();
+        let mut reply = turn.message.clone();
+        let mut additional_messages: Vec<Message> = Vec::new();
+
+        if let Some(reply_ctx) = &turn.reply {
+            let behavior_single_response = turn
+                .scenario
+                .as_ref()
+                .and_then(|s| s.single_response)
+                .unwrap_or(false);
+
+            let mut reply_tx =
+                self.pool.begin().await.map_err(|e| {
+                    anyhow_error(&format!("Failed to begin reply transaction: {}", e))
+                })?;
+
+            if let Some(reply_text) = agent_reply {
                 let agent_message = Message {
                     id: next_id("msg"),
                     session_id: session_id.to_string(),
@@ -449,12 +623,25 @@ impl MessageService {
                     .create_in_tx(&mut reply_tx, &agent_message)
                     .await
                     .map_err(|e| anyhow_error(&format!("Failed to create agent message: {}", e)))?;
+                if let Some(scope) = &reply_ctx.credit_scope {
+                    CreditService::new(self.pool.clone())
+                        .consume_in_tx(
+                            &mut reply_tx,
+                            scope,
+                            AGENT_REPLY_CREDIT_COST,
+                            "agent_reply",
+                            "message",
+                            &agent_message.id,
+                        )
+                        .await?;
+                }
                 reply = agent_message;
             }
 
-            if should_append_completion_message {
-                let closing_content =
-                    single_turn_completion_message(scenario.as_ref().map(|s| s.title.as_str()));
+            if behavior_single_response {
+                let closing_content = single_turn_completion_message(
+                    turn.scenario.as_ref().map(|s| s.title.as_str()),
+                );
                 let system_message = Message {
                     id: next_id("msg"),
                     session_id: session_id.to_string(),
@@ -470,15 +657,15 @@ impl MessageService {
                     .map_err(|e| {
                         anyhow_error(&format!("Failed to create system message: {}", e))
                     })?;
-                if agent_response_enabled {
+                if reply.role == MessageRole::Agent {
                     additional_messages.push(system_message);
                 } else {
                     reply = system_message;
                 }
             }
 
-            if !scenario_missions.is_empty() {
-                if let Ok(completed_ids) = mission_ids_result {
+            if !turn.scenario_missions.is_empty() {
+                if let Some(completed_ids) = completed_mission_ids {
                     let (next_status, changed) =
                         merge_completed_missions(session.mission_status.clone(), &completed_ids);
                     if changed {
@@ -595,22 +782,17 @@ fn build_support_system_instruction(
     sections.join("\n\n")
 }
 
-async fn generate_agent_reply(
+fn build_agent_reply_request(
     scenario: &Scenario,
     product_context: Option<&str>,
     messages: &[Message],
-    plan_code: &PlanCode,
     all_missions_complete: bool,
-) -> Result<String, AppError> {
-    let credentials = resolve_chat_credentials(plan_code, None)?;
-    let gemini_key = credentials.api_key;
-    let model_id = credentials.model_id;
+) -> LlmRequest {
     let system_instruction = build_support_system_instruction(scenario, product_context, all_missions_complete);
 
     // Log the system instruction being sent to the agent
     tracing::info!("=== AGENT SYSTEM INSTRUCTION ===");
     tracing::info!("Scenario: {}", scenario.id);
-    tracing::info!("Model: {}", model_id);
     tracing::info!("System Instruction:\n{}", system_instruction);
     tracing::info!("================================");
 
@@ -622,94 +804,95 @@ async fn generate_agent_reply(
 
     tracing::info!("Sending {} messages as context (last {} of {})", context_messages.len(), context_messages.len(), messages.len());
 
-    let contents: Vec<_> = context_messages
+    let messages = context_messages
         .iter()
         .filter(|m| m.role != MessageRole::System)
-        .map(|m| {
-            let role = match m.role {
-                MessageRole::Agent => "model",
-                _ => "user",
-            };
-            json!({
-                "role": role,
-                "parts": [{ "text": m.content }]
-            })
+        .map(|m| LlmMessage {
+            role: match m.role {
+                MessageRole::Agent => LlmRole::Assistant,
+                _ => LlmRole::User,
+            },
+            text: m.content.clone(),
         })
         .collect();
 
-    let payload = json!({
-        "contents": contents,
-        "systemInstruction": { "parts": [{ "text": system_instruction }] },
-        "generationConfig": {
-            "temperature": 0.7,
-            "maxOutputTokens": 1024,  // Increased from 512 to allow longer responses
-            "topP": 0.95,
-            "topK": 40
-        }
-    });
-
-    let url = format!(
-        "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}",
-        model_id, gemini_key
-    );
-
-    let client = Client::new();
-    let res = client
-        .post(url)
-        .header("Content-Type", "application/json")
-        .json(&payload)
-        .send()
-        .await
-        .map_err(|e| anyhow_error(format!("Gemini request failed: {}", e)))?;
-
-    if !res.status().is_success() {
-        let status = res.status();
-        let text = res.text().await.unwrap_or_default();
-        return Err(anyhow_error(format!(
-            "Gemini API error {}: {}",
-            status, text
-        )));
+    LlmRequest {
+        purpose: LlmPurpose::AgentReply,
+        scenario_id: Some(scenario.id.clone()),
+        system_instruction,
+        messages,
+        temperature: 0.7,
+        max_output_tokens: 1024, // Increased from 512 to allow longer responses
+        top_p: Some(0.95),
+        top_k: Some(40),
+        json_template: None,
     }
+}
 
-    let data: serde_json::Value = res
-        .json()
-        .await
-        .map_err(|e| anyhow_error(format!("Failed to parse Gemini response: {}", e)))?;
-
-    // Check finish reason to detect truncation
-    let finish_reason = data
-        .get("candidates")
-        .and_then(|v| v.get(0))
-        .and_then(|v| v.get("finishReason"))
-        .and_then(|v| v.as_str())
-        .unwrap_or("UNKNOWN");
-
-    if finish_reason == "MAX_TOKENS" {
-        tracing::warn!("⚠️  Response was truncated due to MAX_TOKENS limit (maxOutputTokens: 512)");
+fn finalize_agent_reply(provider: &dyn LlmProvider, response: LlmResponse) -> String {
+    if response.is_truncated() {
+        tracing::warn!("⚠️  Response was truncated due to the maxOutputTokens limit");
     }
-    tracing::info!("Gemini finish reason: {}", finish_reason);
-
-    let reply = data
-        .get("candidates")
-        .and_then(|v| v.get(0))
-        .and_then(|v| v.get("content"))
-        .and_then(|v| v.get("parts"))
-        .and_then(|v| v.as_array())
-        .map(|parts| {
-            parts
-                .iter()
-                .filter_map(|p| p.get("text").and_then(|t| t.as_str()))
-                .collect::<Vec<_>>()
-                .join("")
-                .trim()
-                .to_string()
-        })
-        .filter(|s| !s.is_empty())
-        .unwrap_or_else(|| "（応答を生成できませんでした）".to_string());
+    tracing::info!(
+        "{} ({}) finish reason: {}",
+        provider.provider_name(),
+        provider.model_id(),
+        response.finish_reason.as_deref().unwrap_or("UNKNOWN")
+    );
 
+    let reply = response.text.trim().to_string();
+    let reply = if reply.is_empty() {
+        "（応答を生成できませんでした）".to_string()
+    } else {
+        reply
+    };
     tracing::info!("Agent reply length: {} characters", reply.len());
+    reply
+}
+
+async fn generate_agent_reply(
+    scenario: &Scenario,
+    product_context: Option<&str>,
+    messages: &[Message],
+    plan_code: &PlanCode,
+    all_missions_complete: bool,
+) -> Result<String, AppError> {
+    let provider = resolve_chat_provider(plan_code)?;
+    let request =
+        build_agent_reply_request(scenario, product_context, messages, all_missions_complete);
+    let response = provider.generate(&request).await?;
+    Ok(finalize_agent_reply(provider.as_ref(), response))
+}
+
+/// Same prompt as [`generate_agent_reply`], but forwards token deltas to
+/// `events` as they arrive and returns the full reply once the stream ends.
+async fn stream_agent_reply(
+    scenario: &Scenario,
+    product_context: Option<&str>,
+    messages: &[Message],
+    plan_code: &PlanCode,
+    all_missions_complete: bool,
+    events: &mpsc::Sender<MessageStreamEvent>,
+) -> Result<String, AppError> {
+    let provider = resolve_chat_provider(plan_code)?;
+    let request =
+        build_agent_reply_request(scenario, product_context, messages, all_missions_complete);
+
+    let (delta_tx, mut delta_rx) = mpsc::channel::<String>(32);
+    let generate = async {
+        let delta_tx = delta_tx;
+        provider.generate_stream(&request, &delta_tx).await
+    };
+    let forward = async {
+        while let Some(text) = delta_rx.recv().await {
+            // A closed receiver means the client went away; keep draining so the
+            // reply can still be persisted.
+            let _ = events.send(MessageStreamEvent::Delta { text }).await;
+        }
+    };
+    let (response, ()) = tokio::join!(generate, forward);
 
-    Ok(reply)
+    Ok(finalize_agent_reply(provider.as_ref(), response?))
 }
 
 #[cfg(test)]
@@ -10,9 +10,14 @@ use crate::models::HistoryItem;
 use crate::state::SharedState;
 
 use super::models::{
-    CreateInvitationRequest, CreateOrganizationRequest, CurrentOrganizationResponse,
-    InvitationResponse, Organization, OrganizationMember, OrganizationMembersResponse,
-    OrganizationProgressResponse, UpdateMemberRequest, UpdateOrganizationRequest,
+    CreateInvitationRequest, CreateOrganizationDomainRequest, CreateOrganizationRequest,
+    CreateOwnershipTransferRequest, CurrentOrganizationResponse, InvitationResponse,
+    MyOrganizationsResponse, Organization, OrganizationAuditEventsResponse, OrganizationDomain,
+    OrganizationDomainsResponse, OrganizationInvitation, OrganizationInvitationsResponse,
+    OrganizationMember, OrganizationMembersResponse, OrganizationOffersResponse,
+    OrganizationProgressResponse, OwnershipTransfer, OwnershipTransferAcceptResponse,
+    OwnershipTransferResponse, SwitchOrganizationRequest, UpdateMemberRequest,
+    UpdateOrganizationRequest,
 };
 
 #[utoipa::path(
@@ -34,6 +39,77 @@ pub async fn create_organization(
     Ok((StatusCode::CREATED, Json(organization)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/me/organizations",
+    responses((status = 200, body = MyOrganizationsResponse))
+)]
+pub async fn list_my_organizations(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> Result<Json<MyOrganizationsResponse>, AppError> {
+    let response = state
+        .services()
+        .organizations()
+        .list_my_organizations(&auth.user_id)
+        .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    put,
+    path = "/me/organizations/current",
+    request_body = SwitchOrganizationRequest,
+    responses((status = 200, body = MyOrganizationsResponse))
+)]
+pub async fn switch_current_organization(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Json(body): Json<SwitchOrganizationRequest>,
+) -> Result<Json<MyOrganizationsResponse>, AppError> {
+    let response = state
+        .services()
+        .organizations()
+        .switch_current_organization(&auth.user_id, body)
+        .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    get,
+    path = "/me/organization-offers",
+    responses((status = 200, body = OrganizationOffersResponse))
+)]
+pub async fn list_organization_offers(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> Result<Json<OrganizationOffersResponse>, AppError> {
+    let response = state
+        .services()
+        .organizations()
+        .list_organization_offers(&auth.user_id, auth.verified_email())
+        .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/me/organization-offers/{organizationId}/accept",
+    responses((status = 200, body = CurrentOrganizationResponse))
+)]
+pub async fn accept_organization_offer(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(organization_id): Path<String>,
+) -> Result<Json<CurrentOrganizationResponse>, AppError> {
+    let response = state
+        .services()
+        .organizations()
+        .accept_organization_offer(&auth.user_id, auth.verified_email(), &organization_id)
+        .await?;
+    Ok(Json(response))
+}
+
 #[utoipa::path(
     get,
     path = "/organizations/current",
@@ -141,6 +217,59 @@ pub async fn create_invitation(
     Ok((StatusCode::CREATED, Json(response)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/organizations/current/invitations",
+    responses((status = 200, body = OrganizationInvitationsResponse))
+)]
+pub async fn list_invitations(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> Result<Json<OrganizationInvitationsResponse>, AppError> {
+    let invitations = state
+        .services()
+        .organizations()
+        .list_invitations(&auth.user_id)
+        .await?;
+    Ok(Json(invitations))
+}
+
+#[utoipa::path(
+    post,
+    path = "/organizations/current/invitations/{invitationId}/resend",
+    responses((status = 200, body = InvitationResponse))
+)]
+pub async fn resend_invitation(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(invitation_id): Path<String>,
+) -> Result<Json<InvitationResponse>, AppError> {
+    let response = state
+        .services()
+        .organizations()
+        .resend_invitation(&auth.user_id, &invitation_id)
+        .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/organizations/current/invitations/{invitationId}/revoke",
+    responses((status = 200, body = OrganizationInvitation))
+)]
+pub async fn revoke_invitation(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(invitation_id): Path<String>,
+) -> Result<Json<OrganizationInvitation>, AppError> {
+    let invitation = state
+        .services()
+        .organizations()
+        .revoke_invitation(&auth.user_id, &invitation_id)
+        .await?;
+    Ok(Json(invitation))
+}
+
 #[utoipa::path(
     post,
     path = "/organizations/current/invitations/{token}/accept",
@@ -196,3 +325,162 @@ pub async fn delete_member(
         .await?;
     Ok(StatusCode::NO_CONTENT)
 }
+
+#[utoipa::path(
+    get,
+    path = "/organizations/current/transfer-ownership",
+    responses((status = 200, body = OwnershipTransferResponse))
+)]
+pub async fn get_ownership_transfer(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> Result<Json<OwnershipTransferResponse>, AppError> {
+    let transfer = state
+        .services()
+        .organizations()
+        .get_ownership_transfer(&auth.user_id)
+        .await?;
+    Ok(Json(transfer))
+}
+
+#[utoipa::path(
+    post,
+    path = "/organizations/current/transfer-ownership",
+    request_body = CreateOwnershipTransferRequest,
+    responses((status = 201, body = OwnershipTransfer))
+)]
+pub async fn create_ownership_transfer(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Json(body): Json<CreateOwnershipTransferRequest>,
+) -> Result<(StatusCode, Json<OwnershipTransfer>), AppError> {
+    let transfer = state
+        .services()
+        .organizations()
+        .create_ownership_transfer(&auth.user_id, body)
+        .await?;
+    Ok((StatusCode::CREATED, Json(transfer)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/organizations/current/transfer-ownership/accept",
+    responses((status = 200, body = OwnershipTransferAcceptResponse))
+)]
+pub async fn accept_ownership_transfer(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> Result<Json<OwnershipTransferAcceptResponse>, AppError> {
+    let accepted = state
+        .services()
+        .organizations()
+        .accept_ownership_transfer(&auth.user_id)
+        .await?;
+    Ok(Json(accepted))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/organizations/current/transfer-ownership",
+    responses((status = 204))
+)]
+pub async fn cancel_ownership_transfer(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> Result<StatusCode, AppError> {
+    state
+        .services()
+        .organizations()
+        .cancel_ownership_transfer(&auth.user_id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/organizations/current/domains",
+    responses((status = 200, body = OrganizationDomainsResponse))
+)]
+pub async fn list_domains(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> Result<Json<OrganizationDomainsResponse>, AppError> {
+    let domains = state
+        .services()
+        .organizations()
+        .list_domains(&auth.user_id)
+        .await?;
+    Ok(Json(domains))
+}
+
+#[utoipa::path(
+    post,
+    path = "/organizations/current/domains",
+    request_body = CreateOrganizationDomainRequest,
+    responses((status = 201, body = OrganizationDomain))
+)]
+pub async fn create_domain(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Json(body): Json<CreateOrganizationDomainRequest>,
+) -> Result<(StatusCode, Json<OrganizationDomain>), AppError> {
+    let domain = state
+        .services()
+        .organizations()
+        .create_domain(&auth.user_id, body)
+        .await?;
+    Ok((StatusCode::CREATED, Json(domain)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/organizations/current/domains/{domainId}/verify",
+    responses((status = 200, body = OrganizationDomain))
+)]
+pub async fn verify_domain(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(domain_id): Path<String>,
+) -> Result<Json<OrganizationDomain>, AppError> {
+    let domain = state
+        .services()
+        .organizations()
+        .verify_domain(&auth.user_id, &domain_id)
+        .await?;
+    Ok(Json(domain))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/organizations/current/domains/{domainId}",
+    responses((status = 204))
+)]
+pub async fn delete_domain(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(domain_id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    state
+        .services()
+        .organizations()
+        .delete_domain(&auth.user_id, &domain_id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/organizations/current/audit-events",
+    responses((status = 200, body = OrganizationAuditEventsResponse))
+)]
+pub async fn list_audit_events(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> Result<Json<OrganizationAuditEventsResponse>, AppError> {
+    let events = state
+        .services()
+        .organizations()
+        .list_audit_events(&auth.user_id)
+        .await?;
+    Ok(Json(events))
+}
@@ -46,11 +46,14 @@ pub struct OrganizationInvitation {
     pub organization_id: String,
     pub email: String,
     pub role: String,
-    #[serde(alias = "invite_token_hash")]
+    /// SHA-256 of the accept token; never sent to clients.
+    #[serde(skip)]
     pub invite_token_hash: String,
     #[serde(alias = "expires_at")]
     pub expires_at: String,
     pub status: String,
+    /// Language of the invitation email (`ja` or `en`).
+    pub locale: String,
     #[serde(alias = "created_by_user_id")]
     pub created_by_user_id: String,
     #[serde(alias = "created_at")]
@@ -74,6 +77,8 @@ pub struct UpdateOrganizationRequest {
 pub struct CreateInvitationRequest {
     pub email: String,
     pub role: String,
+    /// Language of the invitation email; `EMAIL_DEFAULT_LOCALE` when omitted.
+    pub locale: Option<String>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -93,6 +98,28 @@ pub struct CurrentOrganizationResponse {
     pub pending_invitation_count: i64,
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchOrganizationRequest {
+    pub organization_id: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MyOrganization {
+    pub organization: Organization,
+    pub membership: OrganizationMember,
+    /// True for the organization `/organizations/current/*` resolves to.
+    pub current: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MyOrganizationsResponse {
+    pub current_organization_id: Option<String>,
+    pub organizations: Vec<MyOrganization>,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct OrganizationMembersResponse {
@@ -102,6 +129,15 @@ pub struct OrganizationMembersResponse {
     pub pending_invitation_count: i64,
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationInvitationsResponse {
+    pub invitations: Vec<OrganizationInvitation>,
+    pub seat_limit: Option<i32>,
+    pub active_member_count: i64,
+    pub pending_invitation_count: i64,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct InvitationResponse {
@@ -132,6 +168,10 @@ pub struct OrganizationMemberProgress {
     pub completed_sessions: i64,
     pub evaluated_sessions: i64,
     pub progress_item_completions: i64,
+    /// Sessions whose official evaluation passed.
+    pub passed_sessions: i64,
+    /// Mean overall score across official evaluations.
+    pub average_official_score: Option<f64>,
     pub last_activity_at: Option<String>,
 }
 
@@ -141,3 +181,108 @@ pub struct OrganizationProgressResponse {
     pub members: Vec<OrganizationMemberProgress>,
     pub generated_at: String,
 }
+
+#[derive(Debug, Serialize, ToSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationDomain {
+    pub id: String,
+    pub organization_id: String,
+    pub domain: String,
+    /// `offer` lists the organization for matching users to join; `auto`
+    /// joins them when they sign in.
+    pub join_policy: String,
+    pub default_role: String,
+    pub verified_at: Option<String>,
+    /// DNS TXT record to publish before calling verify.
+    pub verification_record_name: String,
+    pub verification_record_value: String,
+    pub created_by_user_id: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOrganizationDomainRequest {
+    pub domain: String,
+    /// Defaults to `offer`.
+    pub join_policy: Option<String>,
+    /// Defaults to `member`.
+    pub default_role: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationDomainsResponse {
+    pub domains: Vec<OrganizationDomain>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationOffer {
+    pub organization: Organization,
+    pub domain: String,
+    pub default_role: String,
+    pub seat_available: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationOffersResponse {
+    pub offers: Vec<OrganizationOffer>,
+}
+
+#[derive(Debug, Serialize, ToSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationAuditEvent {
+    pub id: String,
+    pub organization_id: String,
+    pub actor_user_id: Option<String>,
+    pub action: String,
+    pub subject_user_id: Option<String>,
+    pub detail: serde_json::Value,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationAuditEventsResponse {
+    pub events: Vec<OrganizationAuditEvent>,
+}
+
+#[derive(Debug, Serialize, ToSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnershipTransfer {
+    pub id: String,
+    pub organization_id: String,
+    pub from_user_id: String,
+    pub to_user_id: String,
+    /// `pending` until the target accepts or declines, or the owner cancels.
+    pub status: String,
+    pub expires_at: String,
+    pub created_at: String,
+    pub resolved_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOwnershipTransferRequest {
+    /// Active member who becomes the owner once they accept.
+    pub member_id: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnershipTransferResponse {
+    pub transfer: Option<OwnershipTransfer>,
+}
+
+/// The caller's organization after accepting an ownership transfer.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnershipTransferAcceptResponse {
+    #[serde(flatten)]
+    pub current: CurrentOrganizationResponse,
+    /// False when the Stripe customer could not be moved to the new owner;
+    /// it stays on the previous owner until billing is updated by hand.
+    pub billing_customer_updated: bool,
+}
@@ -1,10 +1,14 @@
 use super::models::{
-    Organization, OrganizationInvitation, OrganizationMember, OrganizationMemberProgress,
+    Organization, OrganizationAuditEvent, OrganizationDomain, OrganizationInvitation,
+    OrganizationMember, OrganizationMemberProgress, OwnershipTransfer,
 };
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use sqlx::{postgres::PgRow, PgPool, Postgres, Row, Transaction};
 
+/// Subdomain of the TXT record that proves control of an email domain.
+const DOMAIN_VERIFICATION_RECORD_PREFIX: &str = "_pm-journey-verification";
+
 #[derive(Clone)]
 pub struct OrganizationRepository {
     pool: PgPool,
@@ -71,7 +75,9 @@ impl OrganizationRepository {
             LEFT JOIN users u
               ON u.id = m.user_id
             WHERE m.user_id = $1 AND m.status = 'active'
-            ORDER BY m.created_at DESC
+            ORDER BY
+                (m.organization_id IS NOT DISTINCT FROM u.current_organization_id) DESC,
+                m.created_at DESC
             "#,
         )
         .bind(user_id)
@@ -82,6 +88,49 @@ impl OrganizationRepository {
         Ok(rows.into_iter().map(Self::map_member_row).collect())
     }
 
+    /// The organization the user switched to, as long as they are still an
+    /// active member of it.
+    pub async fn find_current_org_id_for_user(&self, user_id: &str) -> Result<Option<String>> {
+        let row = sqlx::query(
+            r#"
+            SELECT u.current_organization_id
+            FROM users u
+            INNER JOIN organization_members m
+              ON m.user_id = u.id
+             AND m.organization_id = u.current_organization_id
+             AND m.status = 'active'
+            WHERE u.id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to fetch current organization for user")?;
+
+        Ok(row.and_then(|r| {
+            r.try_get::<Option<String>, _>("current_organization_id")
+                .ok()
+                .flatten()
+        }))
+    }
+
+    pub async fn set_current_org_for_user(&self, user_id: &str, org_id: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET current_organization_id = $2
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id)
+        .bind(org_id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to set current organization for user")?;
+
+        Ok(())
+    }
+
     pub async fn get_by_id(&self, id: &str) -> Result<Option<Organization>> {
         let row = sqlx::query(
             r#"
@@ -336,7 +385,7 @@ impl OrganizationRepository {
             FROM subscriptions
             WHERE organization_id = $1
               AND plan_code = 'TEAM'
-              AND status IN ('active', 'trialing')
+              AND status IN ('active', 'trialing', 'past_due')
             ORDER BY updated_at DESC
             LIMIT 1
             "#,
@@ -365,9 +414,10 @@ impl OrganizationRepository {
         sqlx::query(
             r#"
             INSERT INTO organization_invitations (
-                id, organization_id, email, role, invite_token_hash, expires_at, status, created_by_user_id, created_at
+                id, organization_id, email, role, invite_token_hash, expires_at, status, locale,
+                created_by_user_id, created_at
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             "#,
         )
         .bind(&invitation.id)
@@ -377,6 +427,7 @@ impl OrganizationRepository {
         .bind(&invitation.invite_token_hash)
         .bind(expires_at)
         .bind(&invitation.status)
+        .bind(&invitation.locale)
         .bind(&invitation.created_by_user_id)
         .bind(created_at)
         .execute(&self.pool)
@@ -403,6 +454,7 @@ impl OrganizationRepository {
                 invite_token_hash,
                 to_char(expires_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as expires_at,
                 status,
+                locale,
                 created_by_user_id,
                 to_char(created_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as created_at
             FROM organization_invitations
@@ -437,6 +489,7 @@ impl OrganizationRepository {
                 invite_token_hash,
                 to_char(expires_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as expires_at,
                 status,
+                locale,
                 created_by_user_id,
                 to_char(created_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as created_at
             FROM organization_invitations
@@ -466,6 +519,7 @@ impl OrganizationRepository {
                 invite_token_hash,
                 to_char(expires_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as expires_at,
                 status,
+                locale,
                 created_by_user_id,
                 to_char(created_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as created_at
             FROM organization_invitations
@@ -501,6 +555,80 @@ impl OrganizationRepository {
         self.get_invitation_by_id(invitation_id).await
     }
 
+    /// Pending and expired invitations of an organization, pending first.
+    /// Accepted and revoked invitations need no further action and are left out.
+    pub async fn list_open_invitations(&self, org_id: &str) -> Result<Vec<OrganizationInvitation>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                id,
+                organization_id,
+                email,
+                role,
+                invite_token_hash,
+                to_char(expires_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as expires_at,
+                status,
+                locale,
+                created_by_user_id,
+                to_char(created_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as created_at
+            FROM organization_invitations
+            WHERE organization_id = $1
+              AND status IN ('pending', 'expired')
+            ORDER BY (status = 'pending') DESC, created_at DESC
+            "#,
+        )
+        .bind(org_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list invitations")?;
+
+        Ok(rows.into_iter().map(Self::map_invitation_row).collect())
+    }
+
+    /// Replace the token and expiry of an invitation and make it pending again.
+    pub async fn reissue_invitation(
+        &self,
+        invitation_id: &str,
+        invite_token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Option<OrganizationInvitation>> {
+        sqlx::query(
+            r#"
+            UPDATE organization_invitations
+            SET invite_token_hash = $2, expires_at = $3, status = 'pending'
+            WHERE id = $1
+            "#,
+        )
+        .bind(invitation_id)
+        .bind(invite_token_hash)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await
+        .context("Failed to reissue invitation")?;
+
+        self.get_invitation_by_id(invitation_id).await
+    }
+
+    /// Mark pending invitations past their expiry as expired, for one
+    /// organization or (with `None`) all of them. Returns how many expired.
+    pub async fn expire_stale_invitations(&self, org_id: Option<&str>) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE organization_invitations
+            SET status = 'expired'
+            WHERE status = 'pending'
+              AND expires_at <= NOW()
+              AND ($1::TEXT IS NULL OR organization_id = $1)
+            "#,
+        )
+        .bind(org_id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to expire stale invitations")?;
+
+        Ok(result.rows_affected())
+    }
+
     pub async fn create_member(
         &self,
         org_id: &str,
@@ -556,6 +684,8 @@ impl OrganizationRepository {
                 COALESCE(progress.completed_sessions, 0)::BIGINT AS completed_sessions,
                 COALESCE(progress.evaluated_sessions, 0)::BIGINT AS evaluated_sessions,
                 COALESCE(progress.progress_item_completions, 0)::BIGINT AS progress_item_completions,
+                COALESCE(progress.passed_sessions, 0)::BIGINT AS passed_sessions,
+                progress.average_official_score,
                 to_char(progress.last_activity_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') AS last_activity_at
             FROM organization_members m
             LEFT JOIN users u
@@ -567,6 +697,8 @@ impl OrganizationRepository {
                     COUNT(*) FILTER (WHERE s.status = 'active')::BIGINT AS active_sessions,
                     COUNT(*) FILTER (WHERE s.status = 'completed')::BIGINT AS completed_sessions,
                     COUNT(*) FILTER (WHERE s.status = 'evaluated' OR e.session_id IS NOT NULL)::BIGINT AS evaluated_sessions,
+                    COUNT(*) FILTER (WHERE e.passing)::BIGINT AS passed_sessions,
+                    AVG(e.overall_score)::DOUBLE PRECISION AS average_official_score,
                     MAX(s.last_activity_at) AS last_activity_at,
                     SUM(
                         (CASE WHEN COALESCE((s.progress_flags ->> 'requirements')::boolean, FALSE) THEN 1 ELSE 0 END) +
@@ -577,6 +709,7 @@ impl OrganizationRepository {
                 FROM sessions s
                 LEFT JOIN evaluations e
                   ON e.session_id = s.id
+                 AND e.is_official
                 WHERE s.organization_id = $1
                 GROUP BY s.user_id
             ) progress
@@ -596,6 +729,470 @@ impl OrganizationRepository {
             .collect())
     }
 
+    pub async fn list_domains(&self, org_id: &str) -> Result<Vec<OrganizationDomain>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                id,
+                organization_id,
+                domain,
+                join_policy,
+                default_role,
+                verification_token,
+                to_char(verified_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as verified_at,
+                created_by_user_id,
+                to_char(created_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as created_at
+            FROM organization_domains
+            WHERE organization_id = $1
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(org_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list organization domains")?;
+
+        Ok(rows.into_iter().map(Self::map_domain_row).collect())
+    }
+
+    pub async fn get_domain(
+        &self,
+        org_id: &str,
+        domain_id: &str,
+    ) -> Result<Option<OrganizationDomain>> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                id,
+                organization_id,
+                domain,
+                join_policy,
+                default_role,
+                verification_token,
+                to_char(verified_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as verified_at,
+                created_by_user_id,
+                to_char(created_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as created_at
+            FROM organization_domains
+            WHERE organization_id = $1 AND id = $2
+            "#,
+        )
+        .bind(org_id)
+        .bind(domain_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to fetch organization domain")?;
+
+        Ok(row.map(Self::map_domain_row))
+    }
+
+    /// The organization holding the verified claim on `domain`, if any.
+    pub async fn find_verified_domain(&self, domain: &str) -> Result<Option<OrganizationDomain>> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                id,
+                organization_id,
+                domain,
+                join_policy,
+                default_role,
+                verification_token,
+                to_char(verified_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as verified_at,
+                created_by_user_id,
+                to_char(created_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as created_at
+            FROM organization_domains
+            WHERE domain = $1 AND verified_at IS NOT NULL
+            "#,
+        )
+        .bind(domain)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to fetch verified domain")?;
+
+        Ok(row.map(Self::map_domain_row))
+    }
+
+    /// The verified domain `user_id` may join through, unless they already
+    /// have a membership row there, joined through a domain before or were
+    /// removed (a removed member is not let back in without an invitation).
+    pub async fn find_domain_join_candidate(
+        &self,
+        user_id: &str,
+        email_domain: &str,
+    ) -> Result<Option<OrganizationDomain>> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                d.id,
+                d.organization_id,
+                d.domain,
+                d.join_policy,
+                d.default_role,
+                d.verification_token,
+                to_char(d.verified_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as verified_at,
+                d.created_by_user_id,
+                to_char(d.created_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as created_at
+            FROM organization_domains d
+            WHERE d.domain = $2
+              AND d.verified_at IS NOT NULL
+              AND NOT EXISTS (
+                  SELECT 1 FROM organization_members m
+                  WHERE m.organization_id = d.organization_id AND m.user_id = $1
+              )
+              AND NOT EXISTS (
+                  SELECT 1 FROM organization_audit_events e
+                  WHERE e.organization_id = d.organization_id
+                    AND e.subject_user_id = $1
+                    AND e.action IN ('member.domain_joined', 'member.removed')
+              )
+            "#,
+        )
+        .bind(user_id)
+        .bind(email_domain)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to fetch domain join candidate")?;
+
+        Ok(row.map(Self::map_domain_row))
+    }
+
+    pub async fn create_domain(
+        &self,
+        org_id: &str,
+        domain: &str,
+        join_policy: &str,
+        default_role: &str,
+        verification_token: &str,
+        created_by_user_id: &str,
+    ) -> Result<OrganizationDomain> {
+        let domain_id = format!("org_domain_{}", uuid::Uuid::new_v4());
+        sqlx::query(
+            r#"
+            INSERT INTO organization_domains (
+                id, organization_id, domain, join_policy, default_role, verification_token, created_by_user_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(&domain_id)
+        .bind(org_id)
+        .bind(domain)
+        .bind(join_policy)
+        .bind(default_role)
+        .bind(verification_token)
+        .bind(created_by_user_id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to create organization domain")?;
+
+        self.get_domain(org_id, &domain_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to fetch created organization domain"))
+    }
+
+    pub async fn mark_domain_verified(
+        &self,
+        org_id: &str,
+        domain_id: &str,
+    ) -> Result<Option<OrganizationDomain>> {
+        sqlx::query(
+            r#"
+            UPDATE organization_domains
+            SET verified_at = NOW()
+            WHERE organization_id = $1 AND id = $2 AND verified_at IS NULL
+            "#,
+        )
+        .bind(org_id)
+        .bind(domain_id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to mark organization domain verified")?;
+
+        self.get_domain(org_id, domain_id).await
+    }
+
+    pub async fn delete_domain(&self, org_id: &str, domain_id: &str) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM organization_domains
+            WHERE organization_id = $1 AND id = $2
+            "#,
+        )
+        .bind(org_id)
+        .bind(domain_id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to delete organization domain")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn record_audit_event(
+        &self,
+        org_id: &str,
+        actor_user_id: Option<&str>,
+        action: &str,
+        subject_user_id: Option<&str>,
+        detail: serde_json::Value,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO organization_audit_events (
+                id, organization_id, actor_user_id, action, subject_user_id, detail
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(format!("org_audit_{}", uuid::Uuid::new_v4()))
+        .bind(org_id)
+        .bind(actor_user_id)
+        .bind(action)
+        .bind(subject_user_id)
+        .bind(detail)
+        .execute(&self.pool)
+        .await
+        .context("Failed to record organization audit event")?;
+
+        Ok(())
+    }
+
+    pub async fn has_audit_event(
+        &self,
+        org_id: &str,
+        subject_user_id: &str,
+        action: &str,
+    ) -> Result<bool> {
+        let row = sqlx::query(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM organization_audit_events
+                WHERE organization_id = $1 AND subject_user_id = $2 AND action = $3
+            ) AS found
+            "#,
+        )
+        .bind(org_id)
+        .bind(subject_user_id)
+        .bind(action)
+        .fetch_one(&self.pool)
+        .await
+        .context("Failed to check organization audit events")?;
+
+        Ok(row.try_get::<bool, _>("found").unwrap_or(false))
+    }
+
+    pub async fn list_audit_events(
+        &self,
+        org_id: &str,
+        limit: i64,
+    ) -> Result<Vec<OrganizationAuditEvent>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                id,
+                organization_id,
+                actor_user_id,
+                action,
+                subject_user_id,
+                detail,
+                to_char(created_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as created_at
+            FROM organization_audit_events
+            WHERE organization_id = $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(org_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list organization audit events")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| OrganizationAuditEvent {
+                id: r.get("id"),
+                organization_id: r.get("organization_id"),
+                actor_user_id: r
+                    .try_get::<Option<String>, _>("actor_user_id")
+                    .unwrap_or(None),
+                action: r.get("action"),
+                subject_user_id: r
+                    .try_get::<Option<String>, _>("subject_user_id")
+                    .unwrap_or(None),
+                detail: r
+                    .try_get::<serde_json::Value, _>("detail")
+                    .unwrap_or_default(),
+                created_at: r
+                    .try_get::<Option<String>, _>("created_at")
+                    .unwrap_or(None)
+                    .unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    /// The organization's pending ownership transfer, unless it has expired.
+    pub async fn find_pending_ownership_transfer(
+        &self,
+        org_id: &str,
+    ) -> Result<Option<OwnershipTransfer>> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                id,
+                organization_id,
+                from_user_id,
+                to_user_id,
+                status,
+                to_char(expires_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as expires_at,
+                to_char(created_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as created_at,
+                to_char(resolved_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as resolved_at
+            FROM organization_ownership_transfers
+            WHERE organization_id = $1
+              AND status = 'pending'
+              AND expires_at > NOW()
+            "#,
+        )
+        .bind(org_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to fetch ownership transfer")?;
+
+        Ok(row.map(Self::map_ownership_transfer_row))
+    }
+
+    /// Start a transfer. A pending transfer past its expiry is marked expired
+    /// first so it no longer blocks a new one.
+    pub async fn create_ownership_transfer(
+        &self,
+        org_id: &str,
+        from_user_id: &str,
+        to_user_id: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Option<OwnershipTransfer>> {
+        sqlx::query(
+            r#"
+            UPDATE organization_ownership_transfers
+            SET status = 'expired', resolved_at = NOW()
+            WHERE organization_id = $1
+              AND status = 'pending'
+              AND expires_at <= NOW()
+            "#,
+        )
+        .bind(org_id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to expire stale ownership transfers")?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO organization_ownership_transfers (
+                id, organization_id, from_user_id, to_user_id, status, expires_at
+            )
+            VALUES ($1, $2, $3, $4, 'pending', $5)
+            "#,
+        )
+        .bind(format!("org_transfer_{}", uuid::Uuid::new_v4()))
+        .bind(org_id)
+        .bind(from_user_id)
+        .bind(to_user_id)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await
+        .context("Failed to create ownership transfer")?;
+
+        self.find_pending_ownership_transfer(org_id).await
+    }
+
+    /// Close a pending transfer without changing ownership (`declined` or
+    /// `canceled`). Returns false when it was no longer pending.
+    pub async fn close_ownership_transfer(&self, transfer_id: &str, status: &str) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE organization_ownership_transfers
+            SET status = $2, resolved_at = NOW()
+            WHERE id = $1 AND status = 'pending'
+            "#,
+        )
+        .bind(transfer_id)
+        .bind(status)
+        .execute(&self.pool)
+        .await
+        .context("Failed to close ownership transfer")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Hand the organization to the transfer's target: the previous owner
+    /// becomes an admin, the target the owner, and `created_by_user_id`
+    /// follows. Returns false, leaving the caller to roll back, when the
+    /// transfer expired or either membership no longer matches.
+    pub async fn complete_ownership_transfer_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        transfer: &OwnershipTransfer,
+    ) -> Result<bool> {
+        let accepted = sqlx::query(
+            r#"
+            UPDATE organization_ownership_transfers
+            SET status = 'accepted', resolved_at = NOW()
+            WHERE id = $1 AND status = 'pending' AND expires_at > NOW()
+            "#,
+        )
+        .bind(&transfer.id)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to accept ownership transfer")?;
+        if accepted.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        let demoted = sqlx::query(
+            r#"
+            UPDATE organization_members
+            SET role = 'admin', updated_at = NOW()
+            WHERE organization_id = $1 AND user_id = $2 AND role = 'owner'
+            "#,
+        )
+        .bind(&transfer.organization_id)
+        .bind(&transfer.from_user_id)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to demote previous owner")?;
+        if demoted.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        let promoted = sqlx::query(
+            r#"
+            UPDATE organization_members
+            SET role = 'owner', updated_at = NOW()
+            WHERE organization_id = $1 AND user_id = $2 AND status = 'active'
+            "#,
+        )
+        .bind(&transfer.organization_id)
+        .bind(&transfer.to_user_id)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to promote new owner")?;
+        if promoted.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE organizations
+            SET created_by_user_id = $2, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(&transfer.organization_id)
+        .bind(&transfer.to_user_id)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to update organization owner")?;
+
+        Ok(true)
+    }
+
     fn map_member_row(r: PgRow) -> OrganizationMember {
         OrganizationMember {
             id: r.get("id"),
@@ -648,6 +1245,28 @@ impl OrganizationRepository {
                 .unwrap_or(None)
                 .unwrap_or_default(),
             status: r.get("status"),
+            locale: r.get("locale"),
+            created_by_user_id: r.get("created_by_user_id"),
+            created_at: r
+                .try_get::<Option<String>, _>("created_at")
+                .unwrap_or(None)
+                .unwrap_or_default(),
+        }
+    }
+
+    fn map_domain_row(r: PgRow) -> OrganizationDomain {
+        let domain: String = r.get("domain");
+        OrganizationDomain {
+            id: r.get("id"),
+            organization_id: r.get("organization_id"),
+            verification_record_name: format!("{DOMAIN_VERIFICATION_RECORD_PREFIX}.{domain}"),
+            domain,
+            join_policy: r.get("join_policy"),
+            default_role: r.get("default_role"),
+            verified_at: r
+                .try_get::<Option<String>, _>("verified_at")
+                .unwrap_or(None),
+            verification_record_value: r.get("verification_token"),
             created_by_user_id: r.get("created_by_user_id"),
             created_at: r
                 .try_get::<Option<String>, _>("created_at")
@@ -656,6 +1275,27 @@ impl OrganizationRepository {
         }
     }
 
+    fn map_ownership_transfer_row(r: PgRow) -> OwnershipTransfer {
+        OwnershipTransfer {
+            id: r.get("id"),
+            organization_id: r.get("organization_id"),
+            from_user_id: r.get("from_user_id"),
+            to_user_id: r.get("to_user_id"),
+            status: r.get("status"),
+            expires_at: r
+                .try_get::<Option<String>, _>("expires_at")
+                .unwrap_or(None)
+                .unwrap_or_default(),
+            created_at: r
+                .try_get::<Option<String>, _>("created_at")
+                .unwrap_or(None)
+                .unwrap_or_default(),
+            resolved_at: r
+                .try_get::<Option<String>, _>("resolved_at")
+                .unwrap_or(None),
+        }
+    }
+
     fn map_member_progress_row(r: PgRow) -> OrganizationMemberProgress {
         OrganizationMemberProgress {
             member_id: r.get("member_id"),
@@ -671,6 +1311,10 @@ impl OrganizationRepository {
             progress_item_completions: r
                 .try_get::<i64, _>("progress_item_completions")
                 .unwrap_or(0),
+            passed_sessions: r.try_get::<i64, _>("passed_sessions").unwrap_or(0),
+            average_official_score: r
+                .try_get::<Option<f64>, _>("average_official_score")
+                .unwrap_or(None),
             last_activity_at: r
                 .try_get::<Option<String>, _>("last_activity_at")
                 .unwrap_or(None),
@@ -1,19 +1,27 @@
 use axum::http::StatusCode;
+use sha2::{Digest, Sha256};
 use sqlx::PgPool;
 
 use crate::error::{anyhow_error, client_error, forbidden_error, AppError};
+use crate::features::billing::services::BillingService;
 use crate::features::comments::repository::CommentRepository;
 use crate::features::evaluations::repository::EvaluationRepository;
 use crate::features::messages::repository::MessageRepository;
 use crate::features::sessions::repository::SessionRepository;
 use crate::models::{HistoryItem, HistoryMetadata, MessageRole};
+use crate::shared::email::{self, EmailLocale, EmailTemplate};
 use crate::shared::helpers::{next_id, now_ts};
 
 use super::models::{
-    CreateInvitationRequest, CreateOrganizationRequest, CurrentOrganizationResponse,
-    InvitationEmailDelivery, InvitationResponse, Organization, OrganizationInvitation,
-    OrganizationMember, OrganizationMembersResponse, OrganizationProgressResponse,
-    UpdateMemberRequest, UpdateOrganizationRequest,
+    CreateInvitationRequest, CreateOrganizationDomainRequest, CreateOrganizationRequest,
+    CreateOwnershipTransferRequest, CurrentOrganizationResponse, InvitationEmailDelivery,
+    InvitationResponse, MyOrganization, MyOrganizationsResponse, Organization,
+    OrganizationAuditEventsResponse, OrganizationDomain, OrganizationDomainsResponse,
+    OrganizationInvitation, OrganizationInvitationsResponse, OrganizationMember,
+    OrganizationMembersResponse, OrganizationOffer, OrganizationOffersResponse,
+    OrganizationProgressResponse, OwnershipTransfer, OwnershipTransferAcceptResponse,
+    OwnershipTransferResponse, SwitchOrganizationRequest, UpdateMemberRequest,
+    UpdateOrganizationRequest,
 };
 use super::repository::OrganizationRepository;
 
@@ -23,7 +31,31 @@ pub struct OrganizationService {
 }
 
 const DEFAULT_APP_BASE_URL: &str = "http://localhost:5173";
-const DEFAULT_RESEND_API_BASE_URL: &str = "https://api.resend.com";
+const DEFAULT_DOMAIN_VERIFICATION_DNS_URL: &str = "https://cloudflare-dns.com/dns-query";
+const DNS_TYPE_TXT: u16 = 16;
+const AUDIT_EVENT_LIST_LIMIT: i64 = 100;
+/// Shared mailbox providers; a domain join on these would admit strangers.
+const PUBLIC_EMAIL_DOMAINS: &[&str] = &[
+    "gmail.com",
+    "googlemail.com",
+    "yahoo.com",
+    "yahoo.co.jp",
+    "outlook.com",
+    "outlook.jp",
+    "hotmail.com",
+    "hotmail.co.jp",
+    "live.com",
+    "icloud.com",
+    "me.com",
+    "aol.com",
+    "proton.me",
+    "protonmail.com",
+    "docomo.ne.jp",
+    "ezweb.ne.jp",
+    "au.com",
+    "softbank.ne.jp",
+    "i.softbank.jp",
+];
 
 impl OrganizationService {
     pub fn new(pool: PgPool) -> Self {
@@ -65,6 +97,67 @@ impl OrganizationService {
             .map_err(|e| anyhow_error(&format!("Failed to create organization: {}", e)))
     }
 
+    /// Every organization the user is an active member of, the current one
+    /// first.
+    pub async fn list_my_organizations(
+        &self,
+        user_id: &str,
+    ) -> Result<MyOrganizationsResponse, AppError> {
+        let repo = OrganizationRepository::new(self.pool.clone());
+        let memberships = repo
+            .list_active_orgs_for_user(user_id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to load memberships: {e}")))?;
+
+        let mut organizations = Vec::with_capacity(memberships.len());
+        for membership in memberships {
+            let Some(organization) = repo
+                .get_by_id(&membership.organization_id)
+                .await
+                .map_err(|e| anyhow_error(format!("Failed to fetch organization: {e}")))?
+            else {
+                continue;
+            };
+            organizations.push(MyOrganization {
+                current: organizations.is_empty(),
+                organization,
+                membership,
+            });
+        }
+
+        Ok(MyOrganizationsResponse {
+            current_organization_id: organizations
+                .first()
+                .map(|entry| entry.organization.id.clone()),
+            organizations,
+        })
+    }
+
+    /// Make one of the user's organizations the current one. It scopes
+    /// `/organizations/current/*`, new sessions and plan resolution until
+    /// the user switches again.
+    pub async fn switch_current_organization(
+        &self,
+        user_id: &str,
+        body: SwitchOrganizationRequest,
+    ) -> Result<MyOrganizationsResponse, AppError> {
+        let org_id = body.organization_id.trim();
+        if org_id.is_empty() {
+            return Err(client_error("organizationId is required"));
+        }
+
+        let repo = OrganizationRepository::new(self.pool.clone());
+        repo.find_member(org_id, user_id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to find organization member: {e}")))?
+            .ok_or_else(|| not_found("organization not found for current user"))?;
+        repo.set_current_org_for_user(user_id, org_id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to switch organization: {e}")))?;
+
+        self.list_my_organizations(user_id).await
+    }
+
     pub async fn get_current_organization(
         &self,
         user_id: &str,
@@ -240,6 +333,11 @@ impl OrganizationService {
         if !is_assignable_role(&body.role) {
             return Err(client_error("invalid invitation role"));
         }
+        let locale = match body.locale.as_deref() {
+            Some(locale) => EmailLocale::parse(locale)
+                .ok_or_else(|| client_error("unsupported invitation locale"))?,
+            None => EmailLocale::default_from_env(),
+        };
 
         let repo = OrganizationRepository::new(self.pool.clone());
         if repo
@@ -259,9 +357,10 @@ impl OrganizationService {
             organization_id: organization.id,
             email,
             role: body.role.to_ascii_lowercase(),
-            invite_token_hash: invite_token.clone(),
+            invite_token_hash: hash_invite_token(&invite_token),
             expires_at: (chrono::Utc::now() + chrono::Duration::days(7)).to_rfc3339(),
             status: "pending".to_string(),
+            locale: locale.as_str().to_string(),
             created_by_user_id: user_id.to_string(),
             created_at: now_ts(),
         };
@@ -271,27 +370,13 @@ impl OrganizationService {
             .await
             .map_err(|e| anyhow_error(&format!("Failed to create invitation: {}", e)))?;
 
-        let inviter_label = membership
-            .user_name
-            .as_deref()
-            .map(str::trim)
-            .filter(|value| !value.is_empty())
-            .or_else(|| {
-                membership
-                    .user_email
-                    .as_deref()
-                    .map(str::trim)
-                    .filter(|value| !value.is_empty())
-            })
-            .or_else(|| Some(membership.user_id.as_str().trim()));
-
         let invite_link = build_invite_link(&invite_token);
         let email_delivery = self
             .send_invitation_email(
-                &created.email,
+                &created,
                 &organization.name,
                 &invite_token,
-                inviter_label,
+                inviter_label(&membership),
             )
             .await;
 
@@ -303,6 +388,149 @@ impl OrganizationService {
         })
     }
 
+    pub async fn list_invitations(
+        &self,
+        user_id: &str,
+    ) -> Result<OrganizationInvitationsResponse, AppError> {
+        let (organization, membership) = self.resolve_current_org_context(user_id).await?;
+        if !can_manage_members(&membership.role) {
+            return Err(forbidden_error(
+                "FORBIDDEN_ROLE: insufficient permission for invitation list",
+            ));
+        }
+
+        let repo = OrganizationRepository::new(self.pool.clone());
+        repo.expire_stale_invitations(Some(&organization.id))
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to expire invitations: {e}")))?;
+        let invitations = repo
+            .list_open_invitations(&organization.id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to list invitations: {e}")))?;
+        let seat_limit = repo
+            .get_active_seat_limit(&organization.id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to fetch seat limit: {e}")))?;
+        let active_member_count = repo
+            .count_active_members(&organization.id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to count active members: {e}")))?;
+        let pending_invitation_count = repo
+            .count_pending_invitations(&organization.id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to count pending invitations: {e}")))?;
+
+        Ok(OrganizationInvitationsResponse {
+            invitations,
+            seat_limit,
+            active_member_count,
+            pending_invitation_count,
+        })
+    }
+
+    /// Issue a fresh token and expiry for a pending or expired invitation and
+    /// send the email again. The previous invite link stops working.
+    pub async fn resend_invitation(
+        &self,
+        user_id: &str,
+        invitation_id: &str,
+    ) -> Result<InvitationResponse, AppError> {
+        let (organization, membership) = self.resolve_current_org_context(user_id).await?;
+        if !can_manage_members(&membership.role) {
+            return Err(forbidden_error(
+                "FORBIDDEN_ROLE: insufficient permission for invitation resend",
+            ));
+        }
+
+        let repo = OrganizationRepository::new(self.pool.clone());
+        let invitation = self
+            .find_org_invitation(&organization.id, invitation_id)
+            .await?;
+        if !matches!(invitation.status.as_str(), "pending" | "expired") {
+            return Err(client_error("invitation is no longer active"));
+        }
+
+        let still_pending = invitation.status == "pending"
+            && chrono::DateTime::parse_from_rfc3339(&invitation.expires_at)
+                .map(|expires_at| expires_at > chrono::Utc::now())
+                .unwrap_or(false);
+        if !still_pending {
+            // An expired invitation no longer holds a seat, so reviving it
+            // must fit under the limit like a new one.
+            if repo
+                .find_pending_invitation_by_email(&organization.id, &invitation.email)
+                .await
+                .map_err(|e| anyhow_error(format!("Failed to check existing invitation: {e}")))?
+                .is_some()
+            {
+                return Err(client_error("invitation already pending for this email"));
+            }
+            self.enforce_seat_limit(&organization.id, true).await?;
+        }
+
+        let invite_token = next_id("org_invite");
+        let reissued = repo
+            .reissue_invitation(
+                &invitation.id,
+                &hash_invite_token(&invite_token),
+                chrono::Utc::now() + chrono::Duration::days(7),
+            )
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to reissue invitation: {e}")))?
+            .ok_or_else(|| not_found("invitation not found"))?;
+
+        let invite_link = build_invite_link(&invite_token);
+        let email_delivery = self
+            .send_invitation_email(
+                &reissued,
+                &organization.name,
+                &invite_token,
+                inviter_label(&membership),
+            )
+            .await;
+
+        Ok(InvitationResponse {
+            invitation: reissued,
+            invite_token,
+            invite_link,
+            email_delivery,
+        })
+    }
+
+    pub async fn revoke_invitation(
+        &self,
+        user_id: &str,
+        invitation_id: &str,
+    ) -> Result<OrganizationInvitation, AppError> {
+        let (organization, membership) = self.resolve_current_org_context(user_id).await?;
+        if !can_manage_members(&membership.role) {
+            return Err(forbidden_error(
+                "FORBIDDEN_ROLE: insufficient permission for invitation revoke",
+            ));
+        }
+
+        let invitation = self
+            .find_org_invitation(&organization.id, invitation_id)
+            .await?;
+        if !matches!(invitation.status.as_str(), "pending" | "expired") {
+            return Err(client_error("invitation is no longer active"));
+        }
+
+        OrganizationRepository::new(self.pool.clone())
+            .update_invitation_status(&invitation.id, "revoked")
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to revoke invitation: {e}")))?
+            .ok_or_else(|| not_found("invitation not found"))
+    }
+
+    /// Sweep every organization's pending invitations past their expiry.
+    pub async fn expire_stale_invitations(&self) -> Result<u64, AppError> {
+        OrganizationRepository::new(self.pool.clone())
+            .expire_stale_invitations(None)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to expire invitations: {e}")))
+    }
+
     pub async fn accept_invitation(
         &self,
         user_id: &str,
@@ -311,7 +539,7 @@ impl OrganizationService {
     ) -> Result<CurrentOrganizationResponse, AppError> {
         let repo = OrganizationRepository::new(self.pool.clone());
         let invitation = repo
-            .find_invitation_by_token(invite_token)
+            .find_invitation_by_token(&hash_invite_token(invite_token))
             .await
             .map_err(|e| anyhow_error(&format!("Failed to find invitation: {}", e)))?
             .ok_or_else(|| not_found("invitation not found"))?;
@@ -372,6 +600,300 @@ impl OrganizationService {
             .await
     }
 
+    pub async fn list_domains(
+        &self,
+        user_id: &str,
+    ) -> Result<OrganizationDomainsResponse, AppError> {
+        let (organization, membership) = self.resolve_current_org_context(user_id).await?;
+        if !can_manage_organization(&membership.role) {
+            return Err(forbidden_error(
+                "FORBIDDEN_ROLE: insufficient permission for organization domains",
+            ));
+        }
+
+        let domains = OrganizationRepository::new(self.pool.clone())
+            .list_domains(&organization.id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to list domains: {e}")))?;
+        Ok(OrganizationDomainsResponse { domains })
+    }
+
+    /// Register an email domain for the current organization. It has no effect
+    /// until verified through the returned DNS TXT record.
+    pub async fn create_domain(
+        &self,
+        user_id: &str,
+        body: CreateOrganizationDomainRequest,
+    ) -> Result<OrganizationDomain, AppError> {
+        let (organization, membership) = self.resolve_current_org_context(user_id).await?;
+        if !can_manage_organization(&membership.role) {
+            return Err(forbidden_error(
+                "FORBIDDEN_ROLE: insufficient permission for organization domains",
+            ));
+        }
+
+        let domain = normalize_email_domain(&body.domain)
+            .ok_or_else(|| client_error("invalid email domain"))?;
+        if PUBLIC_EMAIL_DOMAINS.contains(&domain.as_str()) {
+            return Err(client_error(
+                "DOMAIN_NOT_ALLOWED: public email providers cannot be registered",
+            ));
+        }
+        let join_policy = body.join_policy.as_deref().unwrap_or("offer");
+        if !matches!(join_policy, "offer" | "auto") {
+            return Err(client_error("invalid domain join policy"));
+        }
+        let default_role = body.default_role.as_deref().unwrap_or("member");
+        if !matches!(default_role, "member" | "reviewer") {
+            return Err(client_error(
+                "invalid domain default role; use member or reviewer",
+            ));
+        }
+
+        let repo = OrganizationRepository::new(self.pool.clone());
+        let registered = repo
+            .list_domains(&organization.id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to list domains: {e}")))?;
+        if registered.iter().any(|existing| existing.domain == domain) {
+            return Err(client_error("domain already registered"));
+        }
+
+        let verification_token =
+            format!("pm-journey-verification={}", uuid::Uuid::new_v4().simple());
+        let created = repo
+            .create_domain(
+                &organization.id,
+                &domain,
+                join_policy,
+                default_role,
+                &verification_token,
+                user_id,
+            )
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to create domain: {e}")))?;
+        self.record_audit_event(
+            &organization.id,
+            Some(user_id),
+            "domain.registered",
+            None,
+            serde_json::json!({
+                "domain": created.domain,
+                "joinPolicy": created.join_policy,
+                "defaultRole": created.default_role,
+            }),
+        )
+        .await?;
+        Ok(created)
+    }
+
+    /// Check the domain's DNS TXT record and mark it verified when it holds
+    /// the verification value.
+    pub async fn verify_domain(
+        &self,
+        user_id: &str,
+        domain_id: &str,
+    ) -> Result<OrganizationDomain, AppError> {
+        let (organization, membership) = self.resolve_current_org_context(user_id).await?;
+        if !can_manage_organization(&membership.role) {
+            return Err(forbidden_error(
+                "FORBIDDEN_ROLE: insufficient permission for organization domains",
+            ));
+        }
+
+        let repo = OrganizationRepository::new(self.pool.clone());
+        let domain = repo
+            .get_domain(&organization.id, domain_id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to fetch domain: {e}")))?
+            .ok_or_else(|| not_found("domain not found"))?;
+        if domain.verified_at.is_some() {
+            return Ok(domain);
+        }
+        if repo
+            .find_verified_domain(&domain.domain)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to check domain claim: {e}")))?
+            .is_some()
+        {
+            return Err(client_error(
+                "DOMAIN_ALREADY_CLAIMED: another organization has verified this domain",
+            ));
+        }
+
+        if !dns_txt_record_contains(
+            &domain.verification_record_name,
+            &domain.verification_record_value,
+        )
+        .await?
+        {
+            return Err(client_error(format!(
+                "DOMAIN_VERIFICATION_FAILED: TXT record {} does not contain {}",
+                domain.verification_record_name, domain.verification_record_value
+            )));
+        }
+
+        let verified = repo
+            .mark_domain_verified(&organization.id, domain_id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to verify domain: {e}")))?
+            .ok_or_else(|| not_found("domain not found"))?;
+        self.record_audit_event(
+            &organization.id,
+            Some(user_id),
+            "domain.verified",
+            None,
+            serde_json::json!({ "domain": verified.domain }),
+        )
+        .await?;
+        Ok(verified)
+    }
+
+    pub async fn delete_domain(&self, user_id: &str, domain_id: &str) -> Result<(), AppError> {
+        let (organization, membership) = self.resolve_current_org_context(user_id).await?;
+        if !can_manage_organization(&membership.role) {
+            return Err(forbidden_error(
+                "FORBIDDEN_ROLE: insufficient permission for organization domains",
+            ));
+        }
+
+        let repo = OrganizationRepository::new(self.pool.clone());
+        let domain = repo
+            .get_domain(&organization.id, domain_id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to fetch domain: {e}")))?
+            .ok_or_else(|| not_found("domain not found"))?;
+        repo.delete_domain(&organization.id, domain_id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to delete domain: {e}")))?;
+        self.record_audit_event(
+            &organization.id,
+            Some(user_id),
+            "domain.removed",
+            None,
+            serde_json::json!({ "domain": domain.domain }),
+        )
+        .await
+    }
+
+    pub async fn list_audit_events(
+        &self,
+        user_id: &str,
+    ) -> Result<OrganizationAuditEventsResponse, AppError> {
+        let (organization, membership) = self.resolve_current_org_context(user_id).await?;
+        if !can_manage_organization(&membership.role) {
+            return Err(forbidden_error(
+                "FORBIDDEN_ROLE: insufficient permission for organization audit log",
+            ));
+        }
+
+        let events = OrganizationRepository::new(self.pool.clone())
+            .list_audit_events(&organization.id, AUDIT_EVENT_LIST_LIMIT)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to list audit events: {e}")))?;
+        Ok(OrganizationAuditEventsResponse { events })
+    }
+
+    /// Join the user to the organization that verified their email domain
+    /// when its policy is `auto`. Runs on every sign-in, so it is a no-op once
+    /// the user has joined through the domain. When the seat limit is reached
+    /// the join is skipped and left as an offer.
+    pub async fn join_by_email_domain(&self, user_id: &str, email: &str) -> Result<(), AppError> {
+        let Some(email_domain) = email_domain(email) else {
+            return Ok(());
+        };
+        let repo = OrganizationRepository::new(self.pool.clone());
+        let Some(domain) = repo
+            .find_domain_join_candidate(user_id, &email_domain)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to find domain join candidate: {e}")))?
+        else {
+            return Ok(());
+        };
+        if domain.join_policy != "auto" {
+            return Ok(());
+        }
+
+        if let Err(error) = self.enforce_seat_limit(&domain.organization_id, true).await {
+            let already_recorded = repo
+                .has_audit_event(
+                    &domain.organization_id,
+                    user_id,
+                    "member.domain_join_blocked",
+                )
+                .await
+                .map_err(|e| anyhow_error(format!("Failed to check audit events: {e}")))?;
+            if !already_recorded {
+                self.record_audit_event(
+                    &domain.organization_id,
+                    None,
+                    "member.domain_join_blocked",
+                    Some(user_id),
+                    serde_json::json!({
+                        "domain": domain.domain,
+                        "reason": error.to_string(),
+                    }),
+                )
+                .await?;
+            }
+            return Ok(());
+        }
+
+        self.join_through_domain(&domain, user_id, "auto").await?;
+        Ok(())
+    }
+
+    pub async fn list_organization_offers(
+        &self,
+        user_id: &str,
+        email: Option<&str>,
+    ) -> Result<OrganizationOffersResponse, AppError> {
+        let Some(domain) = self.find_offer(user_id, email).await? else {
+            return Ok(OrganizationOffersResponse { offers: Vec::new() });
+        };
+        let organization = OrganizationRepository::new(self.pool.clone())
+            .get_by_id(&domain.organization_id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to fetch organization: {e}")))?
+            .ok_or_else(|| not_found("organization not found"))?;
+        let seat_available = self
+            .enforce_seat_limit(&domain.organization_id, true)
+            .await
+            .is_ok();
+
+        Ok(OrganizationOffersResponse {
+            offers: vec![OrganizationOffer {
+                organization,
+                domain: domain.domain,
+                default_role: domain.default_role,
+                seat_available,
+            }],
+        })
+    }
+
+    pub async fn accept_organization_offer(
+        &self,
+        user_id: &str,
+        email: Option<&str>,
+        organization_id: &str,
+    ) -> Result<CurrentOrganizationResponse, AppError> {
+        let domain = self
+            .find_offer(user_id, email)
+            .await?
+            .filter(|domain| domain.organization_id == organization_id)
+            .ok_or_else(|| not_found("organization offer not found"))?;
+        self.enforce_seat_limit(organization_id, true).await?;
+
+        let membership = self.join_through_domain(&domain, user_id, "offer").await?;
+        let organization = OrganizationRepository::new(self.pool.clone())
+            .get_by_id(organization_id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to fetch organization: {e}")))?
+            .ok_or_else(|| not_found("organization not found"))?;
+        self.build_current_org_response(organization_id, organization, membership)
+            .await
+    }
+
     pub async fn update_member(
         &self,
         user_id: &str,
@@ -389,7 +911,12 @@ impl OrganizationService {
             return Err(client_error("no member update fields provided"));
         }
         if let Some(role) = body.role.as_deref() {
-            if !is_assignable_role(role) && role != "owner" {
+            if role == "owner" {
+                return Err(client_error(
+                    "owner role is handed over with POST /organizations/current/transfer-ownership",
+                ));
+            }
+            if !is_assignable_role(role) {
                 return Err(client_error("invalid member role"));
             }
         }
@@ -453,112 +980,313 @@ impl OrganizationService {
         if !deleted {
             return Err(not_found("member not found"));
         }
+
+        // The member row is gone, so this event is what keeps domain
+        // auto-join from adding the user straight back.
+        self.record_audit_event(
+            &organization.id,
+            Some(user_id),
+            "member.removed",
+            Some(&target.user_id),
+            serde_json::json!({ "role": target.role }),
+        )
+        .await?;
         Ok(())
     }
 
-    async fn send_invitation_email(
+    /// The current organization's pending ownership transfer, shown to
+    /// owners, admins and the member it is addressed to.
+    pub async fn get_ownership_transfer(
         &self,
-        to_email: &str,
-        organization_name: &str,
-        invite_token: &str,
-        inviter_name: Option<&str>,
-    ) -> InvitationEmailDelivery {
-        let Some(resend_api_key) = env_non_empty("RESEND_API_KEY") else {
-            return InvitationEmailDelivery {
-                status: "skipped".to_string(),
-                message: Some(
-                    "INVITATION_EMAIL_NOT_CONFIGURED: RESEND_API_KEY is not set".to_string(),
-                ),
-            };
-        };
+        user_id: &str,
+    ) -> Result<OwnershipTransferResponse, AppError> {
+        let (organization, membership) = self.resolve_current_org_context(user_id).await?;
+        let transfer = self
+            .find_pending_transfer(&organization.id)
+            .await?
+            .filter(|transfer| {
+                can_manage_organization(&membership.role) || transfer.to_user_id == user_id
+            });
+        Ok(OwnershipTransferResponse { transfer })
+    }
 
-        let Some(from_email) = env_non_empty("INVITATION_EMAIL_FROM") else {
-            return InvitationEmailDelivery {
-                status: "skipped".to_string(),
-                message: Some(
-                    "INVITATION_EMAIL_NOT_CONFIGURED: INVITATION_EMAIL_FROM is not set".to_string(),
-                ),
-            };
-        };
+    /// Ask an active member to take over the current organization. Nothing
+    /// changes until they accept.
+    pub async fn create_ownership_transfer(
+        &self,
+        user_id: &str,
+        body: CreateOwnershipTransferRequest,
+    ) -> Result<OwnershipTransfer, AppError> {
+        let (organization, membership) = self.resolve_current_org_context(user_id).await?;
+        if membership.role != "owner" {
+            return Err(forbidden_error(
+                "FORBIDDEN_ROLE: only the owner can transfer ownership",
+            ));
+        }
 
-        let api_base_url = env_non_empty("INVITATION_EMAIL_API_BASE_URL")
-            .unwrap_or_else(|| DEFAULT_RESEND_API_BASE_URL.to_string());
-        if !is_http_url(&api_base_url) {
-            return InvitationEmailDelivery {
-                status: "failed".to_string(),
-                message: Some(
-                    "INVITATION_EMAIL_CONFIG_INVALID: INVITATION_EMAIL_API_BASE_URL must start with http:// or https://"
-                        .to_string(),
-                ),
-            };
+        let repo = OrganizationRepository::new(self.pool.clone());
+        let target = repo
+            .find_member_by_id(&organization.id, body.member_id.trim())
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to fetch target member: {e}")))?
+            .ok_or_else(|| not_found("member not found"))?;
+        if target.user_id == user_id {
+            return Err(client_error("cannot transfer ownership to yourself"));
+        }
+        if target.status != "active" {
+            return Err(client_error(
+                "ownership can only be transferred to an active member",
+            ));
+        }
+        if self
+            .find_pending_transfer(&organization.id)
+            .await?
+            .is_some()
+        {
+            return Err(client_error(
+                "OWNERSHIP_TRANSFER_PENDING: cancel the pending transfer before starting another",
+            ));
         }
 
-        let inviter_label = inviter_name.unwrap_or("team-owner");
-        let subject = format!("{organization_name} への招待");
-        let text = format!(
-            "あなたは {organization_name} に招待されました。\n\
-             招待トークン: {invite_token}\n\
-             招待者: {inviter_label}\n\
-             このトークンをコピーして、チーム参加画面に貼り付けてください。"
-        );
+        let transfer = repo
+            .create_ownership_transfer(
+                &organization.id,
+                user_id,
+                &target.user_id,
+                chrono::Utc::now() + chrono::Duration::days(7),
+            )
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to create ownership transfer: {e}")))?
+            .ok_or_else(|| anyhow_error("Failed to retrieve created ownership transfer"))?;
+        self.record_audit_event(
+            &organization.id,
+            Some(user_id),
+            "ownership.transfer_requested",
+            Some(&target.user_id),
+            serde_json::json!({ "transferId": transfer.id }),
+        )
+        .await?;
+        Ok(transfer)
+    }
 
-        let mut payload = serde_json::json!({
-            "from": from_email,
-            "to": [to_email],
-            "subject": subject,
-            "text": text,
-            "html": format!(
-                "<p>{organization_name} に招待されました。</p>\
-                 <p>招待トークン（コピーして利用してください）:</p>\
-                 <p><code>{invite_token}</code></p>\
-                 <p>招待者: {inviter_label}</p>\
-                 <p>このトークンをチーム参加画面に貼り付けると参加を完了できます。</p>"
-            ),
-        });
-        if let Some(reply_to) = env_non_empty("INVITATION_EMAIL_REPLY_TO") {
-            payload["reply_to"] = serde_json::json!(reply_to);
-        }
+    /// Accept the pending transfer addressed to the caller. Member roles and
+    /// `organizations.created_by_user_id` are committed in one transaction
+    /// first; the Stripe customer is moved afterwards. A Stripe failure does
+    /// not undo the transfer: it is logged, recorded on the audit event and
+    /// reported as `billingCustomerUpdated: false`.
+    pub async fn accept_ownership_transfer(
+        &self,
+        user_id: &str,
+    ) -> Result<OwnershipTransferAcceptResponse, AppError> {
+        let (organization, _) = self.resolve_current_org_context(user_id).await?;
+        let transfer = self
+            .find_pending_transfer(&organization.id)
+            .await?
+            .filter(|transfer| transfer.to_user_id == user_id)
+            .ok_or_else(|| not_found("ownership transfer not found"))?;
 
-        let endpoint = format!("{}/emails", api_base_url.trim_end_matches('/'));
-        let response = reqwest::Client::new()
-            .post(&endpoint)
-            .bearer_auth(resend_api_key)
-            .json(&payload)
-            .send()
-            .await;
+        let repo = OrganizationRepository::new(self.pool.clone());
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to start ownership transfer: {e}")))?;
+        let completed = repo
+            .complete_ownership_transfer_in_tx(&mut tx, &transfer)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to transfer ownership: {e}")))?;
+        if !completed {
+            return Err(client_error(
+                "OWNERSHIP_TRANSFER_STALE: memberships changed since the transfer was requested; ask the owner to start a new one",
+            ));
+        }
+        tx.commit()
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to commit ownership transfer: {e}")))?;
 
-        let response = match response {
-            Ok(response) => response,
-            Err(error) => {
-                return InvitationEmailDelivery {
-                    status: "failed".to_string(),
-                    message: Some(format!("INVITATION_EMAIL_SEND_FAILED: {}", error)),
-                };
+        // Stripe is updated only after the roles are committed, so no row
+        // locks are held across the call. A failure leaves the customer on
+        // the previous owner; the audit event records it for follow-up.
+        let billing_customer_updated = match BillingService::new(self.pool.clone())
+            .reassign_org_billing_customer(&organization.id, user_id)
+            .await
+        {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to move the billing customer of {} to {}: {e:?}",
+                    organization.id,
+                    user_id
+                );
+                false
             }
         };
 
-        if response.status().is_success() {
-            return InvitationEmailDelivery {
-                status: "sent".to_string(),
-                message: Some("INVITATION_EMAIL_SENT".to_string()),
-            };
-        }
+        self.record_audit_event(
+            &organization.id,
+            Some(user_id),
+            "ownership.transferred",
+            Some(user_id),
+            serde_json::json!({
+                "transferId": transfer.id,
+                "previousOwnerUserId": transfer.from_user_id,
+                "billingCustomerUpdated": billing_customer_updated,
+            }),
+        )
+        .await?;
+        Ok(OwnershipTransferAcceptResponse {
+            current: self.get_current_organization(user_id).await?,
+            billing_customer_updated,
+        })
+    }
 
-        let status = response.status();
-        let body = response
-            .text()
+    /// Withdraw the pending transfer: the owner cancels it, the target
+    /// declines it.
+    pub async fn cancel_ownership_transfer(&self, user_id: &str) -> Result<(), AppError> {
+        let (organization, membership) = self.resolve_current_org_context(user_id).await?;
+        let transfer = self
+            .find_pending_transfer(&organization.id)
+            .await?
+            .ok_or_else(|| not_found("ownership transfer not found"))?;
+        let status = if transfer.to_user_id == user_id {
+            "declined"
+        } else if membership.role == "owner" {
+            "canceled"
+        } else {
+            return Err(forbidden_error(
+                "FORBIDDEN_ROLE: only the owner or the transfer target can withdraw it",
+            ));
+        };
+
+        let closed = OrganizationRepository::new(self.pool.clone())
+            .close_ownership_transfer(&transfer.id, status)
             .await
-            .unwrap_or_else(|_| "failed to read response body".to_string());
+            .map_err(|e| anyhow_error(format!("Failed to close ownership transfer: {e}")))?;
+        if !closed {
+            return Err(not_found("ownership transfer not found"));
+        }
+        self.record_audit_event(
+            &organization.id,
+            Some(user_id),
+            &format!("ownership.transfer_{status}"),
+            Some(&transfer.to_user_id),
+            serde_json::json!({ "transferId": transfer.id }),
+        )
+        .await
+    }
+
+    async fn send_invitation_email(
+        &self,
+        invitation: &OrganizationInvitation,
+        organization_name: &str,
+        invite_token: &str,
+        inviter_name: Option<&str>,
+    ) -> InvitationEmailDelivery {
+        let locale = EmailLocale::parse(&invitation.locale).unwrap_or(EmailLocale::Ja);
+        let invite_link = build_invite_link(invite_token);
+        let expires_at = chrono::DateTime::parse_from_rfc3339(&invitation.expires_at)
+            .map(|value| value.format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_else(|_| invitation.expires_at.clone());
+        let rendered = email::render(
+            EmailTemplate::Invitation,
+            locale,
+            &[
+                ("organization_name", organization_name),
+                ("inviter_name", inviter_name.unwrap_or("team-owner")),
+                ("invite_link", &invite_link),
+                ("expires_at", &expires_at),
+            ],
+        );
+
+        let delivery = email::send("INVITATION", &invitation.email, &rendered).await;
         InvitationEmailDelivery {
-            status: "failed".to_string(),
-            message: Some(format!(
-                "INVITATION_EMAIL_SEND_FAILED: provider responded with {}: {}",
-                status, body
-            )),
+            status: delivery.status.to_string(),
+            message: delivery.message,
         }
     }
 
-    async fn resolve_current_org_context(
+    async fn find_offer(
+        &self,
+        user_id: &str,
+        email: Option<&str>,
+    ) -> Result<Option<OrganizationDomain>, AppError> {
+        let Some(email_domain) = email.and_then(email_domain) else {
+            return Ok(None);
+        };
+        OrganizationRepository::new(self.pool.clone())
+            .find_domain_join_candidate(user_id, &email_domain)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to find organization offer: {e}")))
+    }
+
+    async fn join_through_domain(
+        &self,
+        domain: &OrganizationDomain,
+        user_id: &str,
+        via: &str,
+    ) -> Result<OrganizationMember, AppError> {
+        let member = OrganizationRepository::new(self.pool.clone())
+            .create_member(
+                &domain.organization_id,
+                user_id,
+                &domain.default_role,
+                &domain.created_by_user_id,
+            )
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to join organization: {e}")))?;
+        self.record_audit_event(
+            &domain.organization_id,
+            Some(user_id),
+            "member.domain_joined",
+            Some(user_id),
+            serde_json::json!({
+                "domain": domain.domain,
+                "role": member.role,
+                "via": via,
+            }),
+        )
+        .await?;
+        Ok(member)
+    }
+
+    async fn record_audit_event(
+        &self,
+        org_id: &str,
+        actor_user_id: Option<&str>,
+        action: &str,
+        subject_user_id: Option<&str>,
+        detail: serde_json::Value,
+    ) -> Result<(), AppError> {
+        OrganizationRepository::new(self.pool.clone())
+            .record_audit_event(org_id, actor_user_id, action, subject_user_id, detail)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to record audit event: {e}")))
+    }
+
+    async fn find_pending_transfer(
+        &self,
+        org_id: &str,
+    ) -> Result<Option<OwnershipTransfer>, AppError> {
+        OrganizationRepository::new(self.pool.clone())
+            .find_pending_ownership_transfer(org_id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to fetch ownership transfer: {e}")))
+    }
+
+    async fn find_org_invitation(
+        &self,
+        org_id: &str,
+        invitation_id: &str,
+    ) -> Result<OrganizationInvitation, AppError> {
+        OrganizationRepository::new(self.pool.clone())
+            .get_invitation_by_id(invitation_id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to fetch invitation: {e}")))?
+            .filter(|invitation| invitation.organization_id == org_id)
+            .ok_or_else(|| not_found("invitation not found"))
+    }
+
+    pub(crate) async fn resolve_current_org_context(
         &self,
         user_id: &str,
     ) -> Result<(Organization, OrganizationMember), AppError> {
@@ -641,14 +1369,14 @@ impl OrganizationService {
         };
         if active + pending >= i64::from(limit) {
             return Err(client_error(
-                "SEAT_LIMIT_REACHED: active seats are at the subscription limit",
+                "SEAT_LIMIT_REACHED: active seats are at the subscription limit; add seats with POST /billing/seats",
             ));
         }
         Ok(())
     }
 }
 
-fn can_manage_organization(role: &str) -> bool {
+pub(crate) fn can_manage_organization(role: &str) -> bool {
     matches!(role, "owner" | "admin")
 }
 
@@ -676,6 +1404,94 @@ fn resolve_app_base_url() -> String {
     }
 }
 
+/// Lowercased domain with any leading `@` dropped, or `None` when it is not a
+/// plausible DNS name.
+fn normalize_email_domain(raw: &str) -> Option<String> {
+    let domain = raw
+        .trim()
+        .trim_start_matches('@')
+        .trim_end_matches('.')
+        .to_ascii_lowercase();
+    let valid = domain.contains('.')
+        && domain.len() <= 253
+        && domain.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    valid.then_some(domain)
+}
+
+fn email_domain(email: &str) -> Option<String> {
+    email
+        .rsplit_once('@')
+        .and_then(|(_, domain)| normalize_email_domain(domain))
+}
+
+/// Look up TXT records over DNS-over-HTTPS (`DOMAIN_VERIFICATION_DNS_URL`,
+/// JSON API as served by Cloudflare and Google) and check for `expected`.
+async fn dns_txt_record_contains(record_name: &str, expected: &str) -> Result<bool, AppError> {
+    let dns_url = env_non_empty("DOMAIN_VERIFICATION_DNS_URL")
+        .unwrap_or_else(|| DEFAULT_DOMAIN_VERIFICATION_DNS_URL.to_string());
+    let response = reqwest::Client::new()
+        .get(&dns_url)
+        .query(&[("name", record_name), ("type", "TXT")])
+        .header("accept", "application/dns-json")
+        .send()
+        .await
+        .map_err(|e| anyhow_error(format!("DOMAIN_VERIFICATION_LOOKUP_FAILED: {e}")))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(anyhow_error(format!(
+            "DOMAIN_VERIFICATION_LOOKUP_FAILED: resolver responded with {status}"
+        )));
+    }
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| anyhow_error(format!("DOMAIN_VERIFICATION_LOOKUP_FAILED: {e}")))?;
+
+    // TXT data arrives quoted, and long values are split into several
+    // quoted strings that concatenate to the record.
+    Ok(body["Answer"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|answer| answer["type"] == DNS_TYPE_TXT)
+        .filter_map(|answer| answer["data"].as_str())
+        .any(|data| {
+            let value: String = if data.contains('"') {
+                data.split('"').skip(1).step_by(2).collect()
+            } else {
+                data.trim().to_string()
+            };
+            value == expected
+        }))
+}
+
+fn inviter_label(membership: &OrganizationMember) -> Option<&str> {
+    membership
+        .user_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .or_else(|| {
+            membership
+                .user_email
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        })
+        .or_else(|| Some(membership.user_id.as_str().trim()))
+}
+
+/// Invitations only store this digest, so a leaked row cannot be redeemed.
+fn hash_invite_token(invite_token: &str) -> String {
+    hex::encode(Sha256::digest(invite_token.as_bytes()))
+}
+
 fn build_invite_link(invite_token: &str) -> String {
     format!(
         "{}/team/onboarding?invite={}",
@@ -1,13 +1,19 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 
-use crate::error::AppError;
+use crate::error::{client_error, AppError};
 use crate::middleware::auth::AuthUser;
 use crate::state::SharedState;
 
-use super::models::Scenario;
+use super::models::{
+    CatalogScenario, OrganizationScenario, Scenario, ScenarioExportQuery, ScenarioImportQuery,
+    ScenarioImportReport, ScenarioVersion, UpsertCatalogScenarioRequest,
+};
+use super::services::ScenarioService;
 
 #[utoipa::path(
     get,
@@ -45,6 +51,93 @@ pub async fn create_scenario(
     Ok(Json(scenario))
 }
 
+#[utoipa::path(
+    get,
+    path = "/scenarios/export",
+    params(("format" = Option<String>, Query, description = "json (default) or yaml")),
+    responses((status = 200, body = ScenarioBundle))
+)]
+pub async fn export_scenarios(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Query(query): Query<ScenarioExportQuery>,
+) -> Result<Response, AppError> {
+    let bundle = state
+        .services()
+        .scenarios()
+        .export_bundle(&auth.user_id)
+        .await?;
+    match query.format.as_deref().unwrap_or("json") {
+        "json" => Ok(Json(bundle).into_response()),
+        "yaml" => {
+            let body = serde_norway::to_string(&bundle)?;
+            Ok(([(header::CONTENT_TYPE, "application/yaml")], body).into_response())
+        }
+        other => Err(client_error(format!("unsupported export format: {other}"))),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/scenarios/import",
+    params(("dryRun" = Option<bool>, Query, description = "Validate without creating scenarios")),
+    request_body(content = ScenarioBundle, description = "JSON or YAML bundle"),
+    responses(
+        (status = 200, body = ScenarioImportReport),
+        (status = 422, body = ScenarioImportReport)
+    )
+)]
+pub async fn import_scenarios(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Query(query): Query<ScenarioImportQuery>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<(StatusCode, Json<ScenarioImportReport>), AppError> {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok());
+    let bundle = ScenarioService::parse_bundle(&body, content_type)?;
+    let report = state
+        .services()
+        .scenarios()
+        .import_bundle(&auth.user_id, bundle, query.dry_run.unwrap_or(false))
+        .await?;
+    Ok((import_status(&report), Json(report)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/scenarios/import/csv",
+    params(("dryRun" = Option<bool>, Query, description = "Validate without creating scenarios")),
+    request_body(content = String, content_type = "text/csv"),
+    responses(
+        (status = 200, body = ScenarioImportReport),
+        (status = 422, body = ScenarioImportReport)
+    )
+)]
+pub async fn import_scenarios_csv(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Query(query): Query<ScenarioImportQuery>,
+    body: String,
+) -> Result<(StatusCode, Json<ScenarioImportReport>), AppError> {
+    let report = state
+        .services()
+        .scenarios()
+        .import_csv(&auth.user_id, &body, query.dry_run.unwrap_or(false))
+        .await?;
+    Ok((import_status(&report), Json(report)))
+}
+
+fn import_status(report: &ScenarioImportReport) -> StatusCode {
+    if report.dry_run || report.error_count == 0 {
+        StatusCode::OK
+    } else {
+        StatusCode::UNPROCESSABLE_ENTITY
+    }
+}
+
 #[utoipa::path(
     get,
     path = "/scenarios/{id}",
@@ -62,3 +155,187 @@ pub async fn get_scenario(
         .await?;
     Ok(Json(scenario))
 }
+
+#[utoipa::path(
+    get,
+    path = "/admin/scenarios",
+    responses((status = 200, body = [CatalogScenario]))
+)]
+pub async fn list_catalog_scenarios(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> Result<Json<Vec<CatalogScenario>>, AppError> {
+    let scenarios = state
+        .services()
+        .scenarios()
+        .list_catalog(&auth.user_id)
+        .await?;
+    Ok(Json(scenarios))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/scenarios",
+    request_body = UpsertCatalogScenarioRequest,
+    responses((status = 200, body = CatalogScenario))
+)]
+pub async fn create_catalog_scenario(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Json(request): Json<UpsertCatalogScenarioRequest>,
+) -> Result<Json<CatalogScenario>, AppError> {
+    let scenario = state
+        .services()
+        .scenarios()
+        .create_catalog_scenario(&auth.user_id, request)
+        .await?;
+    Ok(Json(scenario))
+}
+
+#[utoipa::path(
+    put,
+    path = "/admin/scenarios/{id}",
+    request_body = UpsertCatalogScenarioRequest,
+    responses((status = 200, body = CatalogScenario))
+)]
+pub async fn update_catalog_scenario(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<String>,
+    Json(request): Json<UpsertCatalogScenarioRequest>,
+) -> Result<Json<CatalogScenario>, AppError> {
+    let scenario = state
+        .services()
+        .scenarios()
+        .update_catalog_scenario(&auth.user_id, &id, request)
+        .await?;
+    Ok(Json(scenario))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/admin/scenarios/{id}",
+    responses((status = 200, body = CatalogScenario))
+)]
+pub async fn deactivate_catalog_scenario(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<String>,
+) -> Result<Json<CatalogScenario>, AppError> {
+    let scenario = state
+        .services()
+        .scenarios()
+        .deactivate_catalog_scenario(&auth.user_id, &id)
+        .await?;
+    Ok(Json(scenario))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/scenarios/{id}/versions",
+    responses((status = 200, body = [ScenarioVersion]))
+)]
+pub async fn list_catalog_scenario_versions(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<ScenarioVersion>>, AppError> {
+    let versions = state
+        .services()
+        .scenarios()
+        .list_catalog_versions(&auth.user_id, &id)
+        .await?;
+    Ok(Json(versions))
+}
+
+#[utoipa::path(
+    get,
+    path = "/organizations/current/scenarios",
+    responses((status = 200, body = [OrganizationScenario]))
+)]
+pub async fn list_organization_scenarios(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> Result<Json<Vec<OrganizationScenario>>, AppError> {
+    let scenarios = state
+        .services()
+        .scenarios()
+        .list_organization_scenarios(&auth.user_id)
+        .await?;
+    Ok(Json(scenarios))
+}
+
+#[utoipa::path(
+    post,
+    path = "/organizations/current/scenarios",
+    request_body = Scenario,
+    responses((status = 200, body = OrganizationScenario))
+)]
+pub async fn create_organization_scenario(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Json(scenario): Json<Scenario>,
+) -> Result<Json<OrganizationScenario>, AppError> {
+    let scenario = state
+        .services()
+        .scenarios()
+        .create_organization_scenario(&auth.user_id, &scenario)
+        .await?;
+    Ok(Json(scenario))
+}
+
+#[utoipa::path(
+    put,
+    path = "/organizations/current/scenarios/{id}",
+    request_body = Scenario,
+    responses((status = 200, body = OrganizationScenario))
+)]
+pub async fn update_organization_scenario(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<String>,
+    Json(scenario): Json<Scenario>,
+) -> Result<Json<OrganizationScenario>, AppError> {
+    let scenario = state
+        .services()
+        .scenarios()
+        .update_organization_scenario(&auth.user_id, &id, scenario)
+        .await?;
+    Ok(Json(scenario))
+}
+
+#[utoipa::path(
+    post,
+    path = "/organizations/current/scenarios/{id}/publish",
+    responses((status = 200, body = OrganizationScenario))
+)]
+pub async fn publish_organization_scenario(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<String>,
+) -> Result<Json<OrganizationScenario>, AppError> {
+    let scenario = state
+        .services()
+        .scenarios()
+        .publish_organization_scenario(&auth.user_id, &id)
+        .await?;
+    Ok(Json(scenario))
+}
+
+#[utoipa::path(
+    post,
+    path = "/organizations/current/scenarios/{id}/retire",
+    responses((status = 200, body = OrganizationScenario))
+)]
+pub async fn retire_organization_scenario(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<String>,
+) -> Result<Json<OrganizationScenario>, AppError> {
+    let scenario = state
+        .services()
+        .scenarios()
+        .retire_organization_scenario(&auth.user_id, &id)
+        .await?;
+    Ok(Json(scenario))
+}
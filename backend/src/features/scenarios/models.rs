@@ -1 +1,107 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
 pub use crate::models::Scenario;
+
+/// A built-in scenario as stored in the `scenarios` catalog table.
+#[derive(Debug, Serialize, ToSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogScenario {
+    #[serde(flatten)]
+    pub scenario: Scenario,
+    pub version: i32,
+    pub sort_order: i32,
+    pub is_active: bool,
+    pub updated_by_user_id: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpsertCatalogScenarioRequest {
+    #[serde(flatten)]
+    pub scenario: Scenario,
+    pub sort_order: Option<i32>,
+    pub is_active: Option<bool>,
+    /// Updates are rejected with 409 when the stored version differs.
+    pub expected_version: Option<i32>,
+}
+
+/// Immutable snapshot of one catalog revision.
+#[derive(Debug, Serialize, ToSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioVersion {
+    pub scenario_id: String,
+    pub version: i32,
+    pub definition: Scenario,
+    pub created_by_user_id: Option<String>,
+    pub created_at: Option<String>,
+}
+
+/// A scenario authored for an organization. Members only see it once
+/// `status` is `published`; `draft` and `retired` are visible to managers.
+#[derive(Debug, Serialize, ToSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationScenario {
+    #[serde(flatten)]
+    pub scenario: Scenario,
+    pub organization_id: String,
+    pub status: String,
+    pub created_by_user_id: Option<String>,
+    pub published_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+/// Bundle format version written by export and accepted by import.
+pub const SCENARIO_BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Portable set of scenario definitions, serialized as JSON or YAML so
+/// scenarios can be kept in version control.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioBundle {
+    pub format_version: u32,
+    #[serde(default)]
+    pub exported_at: Option<String>,
+    #[serde(default)]
+    pub scenarios: Vec<Scenario>,
+}
+
+#[derive(Debug, Serialize, ToSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioImportResult {
+    /// Source row for CSV imports (the header is row 1).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row: Option<usize>,
+    pub id: String,
+    pub title: String,
+    /// `valid` (dry run), `created` or `invalid`.
+    pub status: String,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioImportReport {
+    pub dry_run: bool,
+    /// Bundle format version; absent for CSV imports.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format_version: Option<u32>,
+    pub created_count: usize,
+    pub error_count: usize,
+    pub results: Vec<ScenarioImportResult>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioExportQuery {
+    /// `json` (default) or `yaml`.
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioImportQuery {
+    /// Validate the bundle and report per-scenario errors without writing.
+    pub dry_run: Option<bool>,
+}
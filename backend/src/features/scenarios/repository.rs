@@ -1,6 +1,16 @@
-use crate::models::{Mission, RatingCriterion, Scenario};
+use crate::models::{Mission, RatingCriterion, Scenario, ScenarioType};
 use anyhow::{Context, Result};
-use sqlx::{PgPool, Row};
+use sqlx::{postgres::PgRow, PgPool, Postgres, Row, Transaction};
+
+use super::models::{CatalogScenario, OrganizationScenario, ScenarioVersion};
+
+const CUSTOM_COLUMNS: &str = r#"
+    id, title, description, scenario_type, feature_mockup, scenario_guide,
+    kickoff_prompt, evaluation_criteria, passing_score, missions, agent_prompt,
+    single_response
+"#;
+
+const ORGANIZATION_COLUMNS: &str = "organization_id, status, user_id, published_at, updated_at";
 
 /// Repository for custom scenarios CRUD operations
 #[allow(dead_code)]
@@ -18,20 +28,78 @@ impl ScenarioRepository {
 
     /// Create a new custom scenario for the authenticated user.
     pub async fn create(&self, scenario: &Scenario, user_id: &str) -> Result<Scenario> {
+        Self::insert(&self.pool, scenario, user_id, None, "published").await?;
+
+        self.get_for_user(&scenario.id, user_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to retrieve created scenario"))
+    }
+
+    /// Create a draft scenario owned by an organization.
+    pub async fn create_for_organization(
+        &self,
+        scenario: &Scenario,
+        organization_id: &str,
+        user_id: &str,
+    ) -> Result<OrganizationScenario> {
+        Self::insert(
+            &self.pool,
+            scenario,
+            user_id,
+            Some(organization_id),
+            "draft",
+        )
+        .await?;
+
+        self.get_for_organization(&scenario.id, organization_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to retrieve created scenario"))
+    }
+
+    /// Create several custom scenarios for the user in one transaction.
+    pub async fn create_many(&self, scenarios: &[Scenario], user_id: &str) -> Result<()> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to begin transaction")?;
+        for scenario in scenarios {
+            Self::insert(&mut *tx, scenario, user_id, None, "published").await?;
+        }
+        tx.commit().await.context("Failed to commit scenarios")?;
+        Ok(())
+    }
+
+    async fn insert<'e, E>(
+        executor: E,
+        scenario: &Scenario,
+        user_id: &str,
+        organization_id: Option<&str>,
+        status: &str,
+    ) -> Result<()>
+    where
+        E: sqlx::Executor<'e, Database = Postgres>,
+    {
         let discipline_val = scenario.scenario_type.to_discipline();
         let discipline = discipline_val.as_str();
 
         let evaluation_criteria = serde_json::to_value(&scenario.evaluation_criteria)?;
         let missions = serde_json::to_value(&scenario.missions)?;
+        let feature_mockup = serde_json::to_value(&scenario.feature_mockup)?;
 
         sqlx::query(
             r#"
             INSERT INTO custom_scenarios (
                 id, title, description, discipline, mode,
                 kickoff_prompt, passing_score, supplemental_info,
-                evaluation_criteria, missions, user_id
+                evaluation_criteria, missions, user_id,
+                scenario_type, feature_mockup, scenario_guide,
+                agent_prompt, single_response, organization_id, status
+            )
+            VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16,
+                $17, $18
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
             "#,
         )
         .bind(&scenario.id)
@@ -45,13 +113,18 @@ impl ScenarioRepository {
         .bind(evaluation_criteria)
         .bind(missions)
         .bind(user_id)
-        .execute(&self.pool)
+        .bind(scenario.scenario_type.as_str())
+        .bind(feature_mockup)
+        .bind(&scenario.scenario_guide)
+        .bind(&scenario.agent_prompt)
+        .bind(scenario.single_response)
+        .bind(organization_id)
+        .bind(status)
+        .execute(executor)
         .await
         .context("Failed to insert scenario")?;
 
-        self.get_for_user(&scenario.id, user_id)
-            .await?
-            .ok_or_else(|| anyhow::anyhow!("Failed to retrieve created scenario"))
+        Ok(())
     }
 
     fn map_row(r: sqlx::postgres::PgRow) -> Scenario {
@@ -65,21 +138,40 @@ impl ScenarioRepository {
             .ok()
             .flatten()
             .and_then(|v| serde_json::from_value(v).ok());
+        let feature_mockup = r
+            .try_get::<Option<serde_json::Value>, _>("feature_mockup")
+            .ok()
+            .flatten()
+            .and_then(|v| serde_json::from_value(v).ok());
 
         let id: String = r.get("id");
-        let scenario_type = crate::models::scenario_type_for_id(&id);
+        let scenario_type = r
+            .try_get::<Option<String>, _>("scenario_type")
+            .ok()
+            .flatten()
+            .and_then(|v| ScenarioType::parse(&v))
+            .unwrap_or_else(|| crate::models::scenario_type_for_id(&id));
         Scenario {
             title: r.get("title"),
             description: r.get("description"),
             scenario_type,
-            feature_mockup: None,
-            scenario_guide: None,
+            feature_mockup,
+            scenario_guide: r
+                .try_get::<Option<String>, _>("scenario_guide")
+                .ok()
+                .flatten(),
             kickoff_prompt: r.get("kickoff_prompt"),
             evaluation_criteria,
             passing_score: r.try_get::<Option<f32>, _>("passing_score").ok().flatten(),
             missions,
-            agent_prompt: None,
-            single_response: None,
+            agent_prompt: r
+                .try_get::<Option<String>, _>("agent_prompt")
+                .ok()
+                .flatten(),
+            single_response: r
+                .try_get::<Option<bool>, _>("single_response")
+                .ok()
+                .flatten(),
             id,
         }
     }
@@ -87,16 +179,13 @@ impl ScenarioRepository {
     /// Get a custom scenario by ID for the authenticated user.
     /// `user_id IS NULL` rows are treated as globally shared custom scenarios.
     pub async fn get_for_user(&self, id: &str, user_id: &str) -> Result<Option<Scenario>> {
-        let row = sqlx::query(
+        let row = sqlx::query(&format!(
             r#"
-            SELECT
-                id, title, description, discipline, mode,
-                kickoff_prompt, passing_score, supplemental_info,
-                behavior, product, evaluation_criteria, missions
+            SELECT {CUSTOM_COLUMNS}
             FROM custom_scenarios
-            WHERE id = $1 AND (user_id = $2 OR user_id IS NULL)
-            "#,
-        )
+            WHERE id = $1 AND (user_id = $2 OR user_id IS NULL) AND organization_id IS NULL
+            "#
+        ))
         .bind(id)
         .bind(user_id)
         .fetch_optional(&self.pool)
@@ -106,19 +195,34 @@ impl ScenarioRepository {
         Ok(row.map(Self::map_row))
     }
 
+    /// Get a custom scenario by ID regardless of owner. Used to resolve the
+    /// scenario of an existing session, whose access was checked at creation.
+    pub async fn get_by_id(&self, id: &str) -> Result<Option<Scenario>> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {CUSTOM_COLUMNS}
+            FROM custom_scenarios
+            WHERE id = $1
+            "#
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to fetch scenario")?;
+
+        Ok(row.map(Self::map_row))
+    }
+
     /// List custom scenarios visible to the authenticated user.
     pub async fn list_for_user(&self, user_id: &str) -> Result<Vec<Scenario>> {
-        let rows = sqlx::query(
+        let rows = sqlx::query(&format!(
             r#"
-            SELECT
-                id, title, description, discipline, mode,
-                kickoff_prompt, passing_score, supplemental_info,
-                behavior, product, evaluation_criteria, missions
+            SELECT {CUSTOM_COLUMNS}
             FROM custom_scenarios
-            WHERE user_id = $1 OR user_id IS NULL
+            WHERE (user_id = $1 OR user_id IS NULL) AND organization_id IS NULL
             ORDER BY created_at DESC
-            "#,
-        )
+            "#
+        ))
         .bind(user_id)
         .fetch_all(&self.pool)
         .await
@@ -129,7 +233,9 @@ impl ScenarioRepository {
 
     /// Delete a user-owned scenario by ID.
     pub async fn delete_for_user(&self, id: &str, user_id: &str) -> Result<()> {
-        let result = sqlx::query("DELETE FROM custom_scenarios WHERE id = $1 AND user_id = $2")
+        let result = sqlx::query(
+            "DELETE FROM custom_scenarios WHERE id = $1 AND user_id = $2 AND organization_id IS NULL",
+        )
             .bind(id)
             .bind(user_id)
             .execute(&self.pool)
@@ -142,4 +248,528 @@ impl ScenarioRepository {
 
         Ok(())
     }
+
+    fn map_organization_row(r: PgRow) -> OrganizationScenario {
+        let organization_id: String = r.get("organization_id");
+        let status: String = r.get("status");
+        let created_by_user_id = r.try_get::<Option<String>, _>("user_id").ok().flatten();
+        let published_at = r
+            .try_get::<Option<chrono::DateTime<chrono::Utc>>, _>("published_at")
+            .ok()
+            .flatten()
+            .map(|v| v.to_rfc3339());
+        let updated_at = r
+            .try_get::<chrono::DateTime<chrono::Utc>, _>("updated_at")
+            .ok()
+            .map(|v| v.to_rfc3339());
+
+        OrganizationScenario {
+            scenario: Self::map_row(r),
+            organization_id,
+            status,
+            created_by_user_id,
+            published_at,
+            updated_at,
+        }
+    }
+
+    /// Get an organization scenario in any status.
+    pub async fn get_for_organization(
+        &self,
+        id: &str,
+        organization_id: &str,
+    ) -> Result<Option<OrganizationScenario>> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {CUSTOM_COLUMNS}, {ORGANIZATION_COLUMNS}
+            FROM custom_scenarios
+            WHERE id = $1 AND organization_id = $2
+            "#
+        ))
+        .bind(id)
+        .bind(organization_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to fetch organization scenario")?;
+
+        Ok(row.map(Self::map_organization_row))
+    }
+
+    /// List every scenario of an organization, newest first.
+    pub async fn list_for_organization(
+        &self,
+        organization_id: &str,
+    ) -> Result<Vec<OrganizationScenario>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {CUSTOM_COLUMNS}, {ORGANIZATION_COLUMNS}
+            FROM custom_scenarios
+            WHERE organization_id = $1
+            ORDER BY created_at DESC
+            "#
+        ))
+        .bind(organization_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list organization scenarios")?;
+
+        Ok(rows.into_iter().map(Self::map_organization_row).collect())
+    }
+
+    /// Overwrite the content of an organization scenario, keeping its status.
+    pub async fn update_for_organization(
+        &self,
+        scenario: &Scenario,
+        organization_id: &str,
+    ) -> Result<bool> {
+        let discipline_val = scenario.scenario_type.to_discipline();
+        let evaluation_criteria = serde_json::to_value(&scenario.evaluation_criteria)?;
+        let missions = serde_json::to_value(&scenario.missions)?;
+        let feature_mockup = serde_json::to_value(&scenario.feature_mockup)?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE custom_scenarios
+            SET title = $3,
+                description = $4,
+                discipline = $5,
+                kickoff_prompt = $6,
+                passing_score = $7,
+                evaluation_criteria = $8,
+                missions = $9,
+                scenario_type = $10,
+                feature_mockup = $11,
+                scenario_guide = $12,
+                agent_prompt = $13,
+                single_response = $14
+            WHERE id = $1 AND organization_id = $2
+            "#,
+        )
+        .bind(&scenario.id)
+        .bind(organization_id)
+        .bind(&scenario.title)
+        .bind(&scenario.description)
+        .bind(discipline_val.as_str())
+        .bind(&scenario.kickoff_prompt)
+        .bind(scenario.passing_score)
+        .bind(evaluation_criteria)
+        .bind(missions)
+        .bind(scenario.scenario_type.as_str())
+        .bind(feature_mockup)
+        .bind(&scenario.scenario_guide)
+        .bind(&scenario.agent_prompt)
+        .bind(scenario.single_response)
+        .execute(&self.pool)
+        .await
+        .context("Failed to update organization scenario")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Move an organization scenario to `draft`, `published` or `retired`.
+    pub async fn set_organization_status(
+        &self,
+        id: &str,
+        organization_id: &str,
+        status: &str,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE custom_scenarios
+            SET status = $3,
+                published_at = CASE WHEN $3 = 'published' THEN NOW() ELSE published_at END
+            WHERE id = $1 AND organization_id = $2
+            "#,
+        )
+        .bind(id)
+        .bind(organization_id)
+        .bind(status)
+        .execute(&self.pool)
+        .await
+        .context("Failed to update organization scenario status")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// List published scenarios of every organization the user is an active
+    /// member of.
+    pub async fn list_published_for_member(&self, user_id: &str) -> Result<Vec<Scenario>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {CUSTOM_COLUMNS}
+            FROM custom_scenarios
+            WHERE status = 'published'
+              AND organization_id IN (
+                SELECT organization_id FROM organization_members
+                WHERE user_id = $1 AND status = 'active'
+              )
+            ORDER BY published_at DESC NULLS LAST, created_at DESC
+            "#
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list organization scenarios for member")?;
+
+        Ok(rows.into_iter().map(Self::map_row).collect())
+    }
+
+    pub async fn get_published_for_member(
+        &self,
+        id: &str,
+        user_id: &str,
+    ) -> Result<Option<Scenario>> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {CUSTOM_COLUMNS}
+            FROM custom_scenarios
+            WHERE id = $1
+              AND status = 'published'
+              AND organization_id IN (
+                SELECT organization_id FROM organization_members
+                WHERE user_id = $2 AND status = 'active'
+              )
+            "#
+        ))
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to fetch organization scenario for member")?;
+
+        Ok(row.map(Self::map_row))
+    }
+}
+
+const CATALOG_COLUMNS: &str = r#"
+    id, version, title, description, scenario_type, feature_mockup, scenario_guide,
+    kickoff_prompt, evaluation_criteria, passing_score, missions, agent_prompt,
+    single_response, sort_order, is_active, updated_by_user_id, updated_at
+"#;
+
+/// Repository for the built-in scenario catalog (`scenarios` + `scenario_versions`).
+#[derive(Clone)]
+pub struct ScenarioCatalogRepository {
+    pool: PgPool,
+}
+
+impl ScenarioCatalogRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    fn map_row(r: PgRow) -> CatalogScenario {
+        let id: String = r.get("id");
+        let scenario_type = r
+            .try_get::<String, _>("scenario_type")
+            .ok()
+            .and_then(|v| ScenarioType::parse(&v))
+            .unwrap_or_else(|| crate::models::scenario_type_for_id(&id));
+        let evaluation_criteria: Vec<RatingCriterion> = r
+            .try_get::<serde_json::Value, _>("evaluation_criteria")
+            .ok()
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default();
+        let missions: Option<Vec<Mission>> = r
+            .try_get::<Option<serde_json::Value>, _>("missions")
+            .ok()
+            .flatten()
+            .and_then(|v| serde_json::from_value(v).ok());
+        let feature_mockup = r
+            .try_get::<Option<serde_json::Value>, _>("feature_mockup")
+            .ok()
+            .flatten()
+            .and_then(|v| serde_json::from_value(v).ok());
+
+        CatalogScenario {
+            scenario: Scenario {
+                title: r.get("title"),
+                description: r.get("description"),
+                scenario_type,
+                feature_mockup,
+                scenario_guide: r
+                    .try_get::<Option<String>, _>("scenario_guide")
+                    .ok()
+                    .flatten(),
+                kickoff_prompt: r.get("kickoff_prompt"),
+                evaluation_criteria,
+                passing_score: r.try_get::<Option<f32>, _>("passing_score").ok().flatten(),
+                missions,
+                agent_prompt: r
+                    .try_get::<Option<String>, _>("agent_prompt")
+                    .ok()
+                    .flatten(),
+                single_response: r
+                    .try_get::<Option<bool>, _>("single_response")
+                    .ok()
+                    .flatten(),
+                id,
+            },
+            version: r.get("version"),
+            sort_order: r.get("sort_order"),
+            is_active: r.get("is_active"),
+            updated_by_user_id: r
+                .try_get::<Option<String>, _>("updated_by_user_id")
+                .ok()
+                .flatten(),
+            updated_at: r
+                .try_get::<chrono::DateTime<chrono::Utc>, _>("updated_at")
+                .ok()
+                .map(|v| v.to_rfc3339()),
+        }
+    }
+
+    /// List catalog scenarios in display order.
+    pub async fn list(&self, include_inactive: bool) -> Result<Vec<CatalogScenario>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {CATALOG_COLUMNS}
+            FROM scenarios
+            WHERE is_active OR $1
+            ORDER BY sort_order, id
+            "#
+        ))
+        .bind(include_inactive)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list catalog scenarios")?;
+
+        Ok(rows.into_iter().map(Self::map_row).collect())
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<CatalogScenario>> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {CATALOG_COLUMNS}
+            FROM scenarios
+            WHERE id = $1
+            "#
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to fetch catalog scenario")?;
+
+        Ok(row.map(Self::map_row))
+    }
+
+    pub async fn next_sort_order(&self) -> Result<i32> {
+        let row = sqlx::query("SELECT COALESCE(MAX(sort_order) + 1, 0) AS next FROM scenarios")
+            .fetch_one(&self.pool)
+            .await
+            .context("Failed to compute scenario sort order")?;
+        Ok(row.get("next"))
+    }
+
+    /// Insert version 1 of a scenario. Returns false (and writes nothing) when
+    /// the id already exists.
+    pub async fn insert(
+        &self,
+        scenario: &Scenario,
+        sort_order: i32,
+        is_active: bool,
+        user_id: Option<&str>,
+    ) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query(
+            r#"
+            INSERT INTO scenarios (
+                id, version, title, description, scenario_type, feature_mockup, scenario_guide,
+                kickoff_prompt, evaluation_criteria, passing_score, missions, agent_prompt,
+                single_response, sort_order, is_active, updated_by_user_id
+            )
+            VALUES ($1, 1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+            ON CONFLICT (id) DO NOTHING
+            "#,
+        )
+        .bind(&scenario.id)
+        .bind(&scenario.title)
+        .bind(&scenario.description)
+        .bind(scenario.scenario_type.as_str())
+        .bind(
+            scenario
+                .feature_mockup
+                .as_ref()
+                .map(serde_json::to_value)
+                .transpose()?,
+        )
+        .bind(&scenario.scenario_guide)
+        .bind(&scenario.kickoff_prompt)
+        .bind(serde_json::to_value(&scenario.evaluation_criteria)?)
+        .bind(scenario.passing_score)
+        .bind(
+            scenario
+                .missions
+                .as_ref()
+                .map(serde_json::to_value)
+                .transpose()?,
+        )
+        .bind(&scenario.agent_prompt)
+        .bind(scenario.single_response)
+        .bind(sort_order)
+        .bind(is_active)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to insert catalog scenario")?;
+
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        Self::insert_version_in_tx(&mut tx, scenario, 1, user_id).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Replace the content of a scenario and bump its version. Returns `None`
+    /// when the id does not exist or `expected_version` no longer matches.
+    pub async fn update(
+        &self,
+        scenario: &Scenario,
+        sort_order: Option<i32>,
+        is_active: Option<bool>,
+        expected_version: Option<i32>,
+        user_id: &str,
+    ) -> Result<Option<i32>> {
+        let mut tx = self.pool.begin().await?;
+        let row = sqlx::query(
+            r#"
+            UPDATE scenarios
+            SET version = version + 1,
+                title = $2,
+                description = $3,
+                scenario_type = $4,
+                feature_mockup = $5,
+                scenario_guide = $6,
+                kickoff_prompt = $7,
+                evaluation_criteria = $8,
+                passing_score = $9,
+                missions = $10,
+                agent_prompt = $11,
+                single_response = $12,
+                sort_order = COALESCE($13, sort_order),
+                is_active = COALESCE($14, is_active),
+                updated_by_user_id = $15
+            WHERE id = $1
+              AND ($16::INTEGER IS NULL OR version = $16)
+            RETURNING version
+            "#,
+        )
+        .bind(&scenario.id)
+        .bind(&scenario.title)
+        .bind(&scenario.description)
+        .bind(scenario.scenario_type.as_str())
+        .bind(
+            scenario
+                .feature_mockup
+                .as_ref()
+                .map(serde_json::to_value)
+                .transpose()?,
+        )
+        .bind(&scenario.scenario_guide)
+        .bind(&scenario.kickoff_prompt)
+        .bind(serde_json::to_value(&scenario.evaluation_criteria)?)
+        .bind(scenario.passing_score)
+        .bind(
+            scenario
+                .missions
+                .as_ref()
+                .map(serde_json::to_value)
+                .transpose()?,
+        )
+        .bind(&scenario.agent_prompt)
+        .bind(scenario.single_response)
+        .bind(sort_order)
+        .bind(is_active)
+        .bind(user_id)
+        .bind(expected_version)
+        .fetch_optional(&mut *tx)
+        .await
+        .context("Failed to update catalog scenario")?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let version: i32 = row.get("version");
+        Self::insert_version_in_tx(&mut tx, scenario, version, Some(user_id)).await?;
+        tx.commit().await?;
+        Ok(Some(version))
+    }
+
+    /// Hide or restore a scenario without touching its content or version.
+    pub async fn set_active(&self, id: &str, is_active: bool, user_id: &str) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE scenarios
+            SET is_active = $2, updated_by_user_id = $3
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(is_active)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to update catalog scenario status")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn list_versions(&self, id: &str) -> Result<Vec<ScenarioVersion>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT scenario_id, version, definition, created_by_user_id, created_at
+            FROM scenario_versions
+            WHERE scenario_id = $1
+            ORDER BY version DESC
+            "#,
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list scenario versions")?;
+
+        rows.into_iter()
+            .map(|r| {
+                let definition: Scenario = serde_json::from_value(r.get("definition"))
+                    .context("Failed to decode scenario version")?;
+                Ok(ScenarioVersion {
+                    scenario_id: r.get("scenario_id"),
+                    version: r.get("version"),
+                    definition,
+                    created_by_user_id: r
+                        .try_get::<Option<String>, _>("created_by_user_id")
+                        .ok()
+                        .flatten(),
+                    created_at: r
+                        .try_get::<chrono::DateTime<chrono::Utc>, _>("created_at")
+                        .ok()
+                        .map(|v| v.to_rfc3339()),
+                })
+            })
+            .collect()
+    }
+
+    async fn insert_version_in_tx(
+        tx: &mut Transaction<'_, Postgres>,
+        scenario: &Scenario,
+        version: i32,
+        user_id: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO scenario_versions (scenario_id, version, definition, created_by_user_id)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(&scenario.id)
+        .bind(version)
+        .bind(serde_json::to_value(scenario)?)
+        .bind(user_id)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to insert scenario version")?;
+        Ok(())
+    }
 }
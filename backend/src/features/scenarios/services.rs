@@ -1,10 +1,37 @@
 use sqlx::PgPool;
 
-use crate::error::{client_error, AppError};
-use crate::models::{default_scenarios, Scenario};
+use crate::error::{anyhow_error, client_error, forbidden_error, AppError};
+use crate::features::entitlements::services::EntitlementService;
+use crate::features::feature_flags::services::FeatureFlagService;
+use crate::features::organizations::repository::OrganizationRepository;
+use crate::features::organizations::services::can_manage_organization;
+use crate::models::{
+    default_evaluation_criteria, default_scenarios, Mission, Scenario, ScenarioDiscipline,
+    ScenarioType,
+};
+use crate::shared::admin_override::is_admin_override_user;
 use axum::http::StatusCode;
+use sha2::{Digest, Sha256};
 
-use super::repository::ScenarioRepository;
+use super::models::{
+    CatalogScenario, OrganizationScenario, ScenarioBundle, ScenarioImportReport,
+    ScenarioImportResult, ScenarioVersion, UpsertCatalogScenarioRequest,
+    SCENARIO_BUNDLE_FORMAT_VERSION,
+};
+use super::repository::{ScenarioCatalogRepository, ScenarioRepository};
+
+const CSV_CATEGORY_COLUMN: &str = "カテゴリ";
+const CSV_TITLE_COLUMN: &str = "シナリオ名";
+const CSV_PURPOSE_COLUMN: &str = "目的";
+const CSV_CONDITION_COLUMN_PREFIX: &str = "完了条件";
+const CSV_DEFAULT_PASSING_SCORE: f32 = 70.0;
+
+/// One scenario to import, with errors found while parsing its source.
+struct ImportEntry {
+    row: Option<usize>,
+    scenario: Scenario,
+    errors: Vec<String>,
+}
 
 #[derive(Clone)]
 pub struct ScenarioService {
@@ -16,6 +43,25 @@ impl ScenarioService {
         Self { pool }
     }
 
+    /// Insert any built-in scenario that is not in the catalog yet. Existing
+    /// rows are left untouched so admin edits survive redeploys.
+    pub async fn seed_builtin_scenarios(&self) -> Result<usize, AppError> {
+        let repo = ScenarioCatalogRepository::new(self.pool.clone());
+        let mut inserted = 0;
+        for (index, scenario) in default_scenarios().iter().enumerate() {
+            if repo
+                .insert(scenario, index as i32, true, None)
+                .await
+                .map_err(|e| {
+                    anyhow_error(format!("Failed to seed scenario {}: {e}", scenario.id))
+                })?
+            {
+                inserted += 1;
+            }
+        }
+        Ok(inserted)
+    }
+
     pub async fn create_scenario(
         &self,
         user_id: &str,
@@ -23,11 +69,21 @@ impl ScenarioService {
     ) -> Result<Scenario, AppError> {
         Self::validate_id(scenario)?;
         Self::validate_weights(scenario)?;
+        if self
+            .find_catalog_scenario(&scenario.id, true)
+            .await?
+            .is_some()
+        {
+            return Err(client_error(
+                "このIDは組み込みシナリオで既に使用されています",
+            ));
+        }
 
         let repo = ScenarioRepository::new(self.pool.clone());
 
+        // custom_scenarios ids are global, so check across all owners.
         if repo
-            .get_for_user(&scenario.id, user_id)
+            .get_by_id(&scenario.id)
             .await
             .map_err(AppError::from)?
             .is_some()
@@ -39,40 +95,673 @@ impl ScenarioService {
     }
 
     pub async fn list_scenarios(&self, user_id: &str) -> Result<Vec<Scenario>, AppError> {
+        let catalog_repo = ScenarioCatalogRepository::new(self.pool.clone());
+        let mut scenarios: Vec<Scenario> = catalog_repo
+            .list(false)
+            .await
+            .map_err(AppError::from)?
+            .into_iter()
+            .map(|entry| entry.scenario)
+            .collect();
+
         let repo = ScenarioRepository::new(self.pool.clone());
-        let mut scenarios = default_scenarios();
         let custom = repo.list_for_user(user_id).await.map_err(AppError::from)?;
         scenarios.extend(custom);
+
+        let mut organization_scenarios = repo
+            .list_published_for_member(user_id)
+            .await
+            .map_err(AppError::from)?;
+        if !organization_scenarios.is_empty() && FeatureFlagService::new().is_entitlement_enforced()
+        {
+            let effective_plan = EntitlementService::new(self.pool.clone())
+                .resolve_effective_plan(user_id)
+                .await?;
+            organization_scenarios.retain(|scenario| {
+                EntitlementService::can_access_scenario(
+                    &effective_plan.plan_code,
+                    &scenario.id,
+                    Some(&scenario.scenario_type.to_discipline()),
+                )
+            });
+        }
+        scenarios.extend(organization_scenarios);
         Ok(scenarios)
     }
 
     pub async fn get_scenario(&self, id: &str, user_id: &str) -> Result<Scenario, AppError> {
-        if let Some(scenario) = default_scenarios().into_iter().find(|s| s.id == id) {
-            return Ok(scenario);
+        self.find_scenario_for_user(id, user_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::new(StatusCode::NOT_FOUND, anyhow::anyhow!("scenario not found"))
+            })
+    }
+
+    /// Export the user's own custom scenarios as a bundle.
+    pub async fn export_bundle(&self, user_id: &str) -> Result<ScenarioBundle, AppError> {
+        let repo = ScenarioRepository::new(self.pool.clone());
+        let mut scenarios = repo.list_for_user(user_id).await.map_err(AppError::from)?;
+        scenarios.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(ScenarioBundle {
+            format_version: SCENARIO_BUNDLE_FORMAT_VERSION,
+            exported_at: Some(chrono::Utc::now().to_rfc3339()),
+            scenarios,
+        })
+    }
+
+    /// Validate every scenario of a bundle and, unless `dry_run`, create them
+    /// as custom scenarios. Nothing is written when any scenario is invalid.
+    pub async fn import_bundle(
+        &self,
+        user_id: &str,
+        bundle: ScenarioBundle,
+        dry_run: bool,
+    ) -> Result<ScenarioImportReport, AppError> {
+        if bundle.format_version != SCENARIO_BUNDLE_FORMAT_VERSION {
+            return Err(client_error(format!(
+                "UNSUPPORTED_BUNDLE_VERSION: expected formatVersion {SCENARIO_BUNDLE_FORMAT_VERSION}, got {}",
+                bundle.format_version
+            )));
+        }
+        if bundle.scenarios.is_empty() {
+            return Err(client_error("INVALID_BUNDLE: bundle contains no scenarios"));
+        }
+
+        let entries = bundle
+            .scenarios
+            .into_iter()
+            .map(|scenario| ImportEntry {
+                row: None,
+                scenario,
+                errors: Vec::new(),
+            })
+            .collect();
+        self.import_entries(user_id, entries, Some(bundle.format_version), dry_run)
+            .await
+    }
+
+    /// Import rows in the `scenarios.csv` format (カテゴリ, シナリオ名, 目的,
+    /// 完了条件1..N). Results carry the spreadsheet row number, header = 1.
+    pub async fn import_csv(
+        &self,
+        user_id: &str,
+        body: &str,
+        dry_run: bool,
+    ) -> Result<ScenarioImportReport, AppError> {
+        let entries = Self::parse_csv(body)?;
+        if entries.is_empty() {
+            return Err(client_error("INVALID_CSV: no scenario rows found"));
+        }
+        self.import_entries(user_id, entries, None, dry_run).await
+    }
+
+    /// Validate every entry and, unless `dry_run`, create them as custom
+    /// scenarios. Nothing is written when any entry is invalid.
+    async fn import_entries(
+        &self,
+        user_id: &str,
+        entries: Vec<ImportEntry>,
+        format_version: Option<u32>,
+        dry_run: bool,
+    ) -> Result<ScenarioImportReport, AppError> {
+        let repo = ScenarioRepository::new(self.pool.clone());
+        let mut seen_ids = std::collections::HashSet::new();
+        let mut results = Vec::with_capacity(entries.len());
+        for entry in &entries {
+            let scenario = &entry.scenario;
+            let mut errors = entry.errors.clone();
+            if let Err(e) = Self::validate_id(scenario) {
+                errors.push(e.to_string());
+            }
+            if let Err(e) = Self::validate_weights(scenario) {
+                errors.push(e.to_string());
+            }
+            if !seen_ids.insert(scenario.id.as_str()) {
+                errors.push("インポート対象内でIDが重複しています".to_string());
+            } else if self
+                .find_catalog_scenario(&scenario.id, true)
+                .await?
+                .is_some()
+            {
+                errors.push("このIDは組み込みシナリオで既に使用されています".to_string());
+            } else if repo
+                .get_by_id(&scenario.id)
+                .await
+                .map_err(AppError::from)?
+                .is_some()
+            {
+                errors.push("このIDのカスタムシナリオは既に存在します".to_string());
+            }
+
+            results.push(ScenarioImportResult {
+                row: entry.row,
+                id: scenario.id.clone(),
+                title: scenario.title.clone(),
+                status: if errors.is_empty() {
+                    "valid"
+                } else {
+                    "invalid"
+                }
+                .to_string(),
+                errors,
+            });
+        }
+
+        let error_count = results.iter().filter(|r| !r.errors.is_empty()).count();
+        let mut created_count = 0;
+        if !dry_run && error_count == 0 {
+            let scenarios: Vec<Scenario> = entries.into_iter().map(|e| e.scenario).collect();
+            repo.create_many(&scenarios, user_id)
+                .await
+                .map_err(AppError::from)?;
+            for result in &mut results {
+                result.status = "created".to_string();
+            }
+            created_count = results.len();
+        }
+
+        Ok(ScenarioImportReport {
+            dry_run,
+            format_version,
+            created_count,
+            error_count,
+            results,
+        })
+    }
+
+    /// Parse a bundle body. YAML is used when the content type says so;
+    /// otherwise JSON is tried first and YAML second.
+    pub fn parse_bundle(
+        body: &str,
+        content_type: Option<&str>,
+    ) -> Result<ScenarioBundle, AppError> {
+        let content_type = content_type.unwrap_or_default().to_ascii_lowercase();
+        let parsed = if content_type.contains("yaml") {
+            serde_norway::from_str(body).map_err(|e| e.to_string())
+        } else if content_type.contains("json") {
+            serde_json::from_str(body).map_err(|e| e.to_string())
+        } else {
+            serde_json::from_str(body)
+                .or_else(|_| serde_norway::from_str(body))
+                .map_err(|e| e.to_string())
+        };
+        parsed.map_err(|e| client_error(format!("INVALID_BUNDLE: {e}")))
+    }
+
+    fn parse_csv(body: &str) -> Result<Vec<ImportEntry>, AppError> {
+        let body = body.trim_start_matches('\u{feff}');
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_reader(body.as_bytes());
+        let headers = reader
+            .headers()
+            .map_err(|e| client_error(format!("INVALID_CSV: {e}")))?
+            .clone();
+        let column = |name: &str| headers.iter().position(|h| h.trim() == name);
+        let (Some(category_col), Some(title_col), Some(purpose_col)) = (
+            column(CSV_CATEGORY_COLUMN),
+            column(CSV_TITLE_COLUMN),
+            column(CSV_PURPOSE_COLUMN),
+        ) else {
+            return Err(client_error(format!(
+                "INVALID_CSV: required columns are {CSV_CATEGORY_COLUMN}, {CSV_TITLE_COLUMN}, {CSV_PURPOSE_COLUMN}"
+            )));
+        };
+        let condition_cols: Vec<usize> = headers
+            .iter()
+            .enumerate()
+            .filter(|(_, h)| h.trim().starts_with(CSV_CONDITION_COLUMN_PREFIX))
+            .map(|(index, _)| index)
+            .collect();
+
+        let mut entries = Vec::new();
+        for (index, record) in reader.records().enumerate() {
+            let row = index + 2;
+            let record =
+                record.map_err(|e| client_error(format!("INVALID_CSV: row {row}: {e}")))?;
+            if record.iter().all(|field| field.trim().is_empty()) {
+                continue;
+            }
+            let field = |col: usize| record.get(col).unwrap_or_default().trim().to_string();
+
+            let category = field(category_col);
+            let title = field(title_col);
+            let purpose = field(purpose_col);
+            let conditions: Vec<String> = condition_cols
+                .iter()
+                .map(|col| field(*col))
+                .filter(|condition| !condition.is_empty())
+                .collect();
+
+            let mut errors = Vec::new();
+            let scenario_type = Self::csv_scenario_type(&category).unwrap_or_else(|| {
+                errors.push(format!("不明なカテゴリです: {category}"));
+                ScenarioType::SoftSkills
+            });
+            if title.is_empty() {
+                errors.push("シナリオ名は必須です".to_string());
+            }
+            if purpose.is_empty() {
+                errors.push("目的は必須です".to_string());
+            }
+            if conditions.is_empty() {
+                errors.push("完了条件を1つ以上入力してください".to_string());
+            }
+
+            entries.push(ImportEntry {
+                row: Some(row),
+                scenario: Self::csv_scenario(scenario_type, title, purpose, conditions),
+                errors,
+            });
+        }
+        Ok(entries)
+    }
+
+    /// Map a CSV category to a scenario type. Besides the Japanese labels,
+    /// the kebab-case type names are accepted as-is.
+    fn csv_scenario_type(category: &str) -> Option<ScenarioType> {
+        match category {
+            "基礎" => Some(ScenarioType::SoftSkills),
+            "チャレンジ" => Some(ScenarioType::BusinessExecution),
+            other => ScenarioType::parse(other),
+        }
+    }
+
+    fn csv_scenario(
+        scenario_type: ScenarioType,
+        title: String,
+        purpose: String,
+        conditions: Vec<String>,
+    ) -> Scenario {
+        // Ids derive from the title so re-importing the same sheet reports
+        // conflicts instead of creating duplicates.
+        let prefix = match scenario_type.to_discipline() {
+            ScenarioDiscipline::Basic => "basic",
+            ScenarioDiscipline::Challenge => "challenge",
+        };
+        let digest = hex::encode(Sha256::digest(title.as_bytes()));
+        let id = format!("{prefix}-csv-{}", &digest[..10]);
+
+        let condition_list = conditions
+            .iter()
+            .map(|condition| format!("- {condition}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let missions = conditions
+            .into_iter()
+            .enumerate()
+            .map(|(index, condition)| Mission {
+                id: format!("{id}-m{}", index + 1),
+                title: condition,
+                description: None,
+                order: index as i32 + 1,
+            })
+            .collect();
+
+        Scenario {
+            scenario_guide: Some(format!(
+                "{title}のシナリオです。目的は「{purpose}」です。完了条件をすべて満たしたら、シナリオを完了してください。"
+            )),
+            kickoff_prompt: format!(
+                "「{title}」を始めましょう。\n\n目的: {purpose}\n\n完了条件:\n{condition_list}"
+            ),
+            description: purpose,
+            title,
+            scenario_type,
+            feature_mockup: None,
+            evaluation_criteria: default_evaluation_criteria(),
+            passing_score: Some(CSV_DEFAULT_PASSING_SCORE),
+            missions: Some(missions),
+            agent_prompt: None,
+            single_response: None,
+            id,
+        }
+    }
+
+    /// Resolve a scenario a user may start a session on: an active catalog
+    /// scenario, a custom scenario visible to the user, or a published
+    /// scenario of one of the user's organizations.
+    pub async fn find_scenario_for_user(
+        &self,
+        id: &str,
+        user_id: &str,
+    ) -> Result<Option<Scenario>, AppError> {
+        if let Some(scenario) = self.find_catalog_scenario(id, false).await? {
+            return Ok(Some(scenario));
+        }
+
+        let repo = ScenarioRepository::new(self.pool.clone());
+        if let Some(scenario) = repo
+            .get_for_user(id, user_id)
+            .await
+            .map_err(AppError::from)?
+        {
+            return Ok(Some(scenario));
+        }
+
+        repo.get_published_for_member(id, user_id)
+            .await
+            .map_err(AppError::from)
+    }
+
+    /// Resolve the scenario of an existing session. Retired catalog scenarios
+    /// and custom scenarios of any owner are included, since access was
+    /// checked when the session was created.
+    pub async fn resolve_session_scenario(&self, id: &str) -> Result<Option<Scenario>, AppError> {
+        if let Some(scenario) = self.find_catalog_scenario(id, true).await? {
+            return Ok(Some(scenario));
+        }
+
+        let repo = ScenarioRepository::new(self.pool.clone());
+        repo.get_by_id(id).await.map_err(AppError::from)
+    }
+
+    /// Look up a built-in scenario. Inactive scenarios are only returned with
+    /// `include_inactive`, which existing sessions use so they keep working
+    /// after a scenario is retired.
+    pub async fn find_catalog_scenario(
+        &self,
+        id: &str,
+        include_inactive: bool,
+    ) -> Result<Option<Scenario>, AppError> {
+        let repo = ScenarioCatalogRepository::new(self.pool.clone());
+        let entry = repo.get(id).await.map_err(AppError::from)?;
+        Ok(entry
+            .filter(|entry| include_inactive || entry.is_active)
+            .map(|entry| entry.scenario))
+    }
+
+    pub async fn list_catalog(&self, user_id: &str) -> Result<Vec<CatalogScenario>, AppError> {
+        Self::require_catalog_admin(user_id)?;
+        let repo = ScenarioCatalogRepository::new(self.pool.clone());
+        repo.list(true).await.map_err(AppError::from)
+    }
+
+    pub async fn create_catalog_scenario(
+        &self,
+        user_id: &str,
+        request: UpsertCatalogScenarioRequest,
+    ) -> Result<CatalogScenario, AppError> {
+        Self::require_catalog_admin(user_id)?;
+        Self::validate_id(&request.scenario)?;
+        Self::validate_weights(&request.scenario)?;
+
+        if ScenarioRepository::new(self.pool.clone())
+            .get_by_id(&request.scenario.id)
+            .await
+            .map_err(AppError::from)?
+            .is_some()
+        {
+            return Err(AppError::new(
+                StatusCode::CONFLICT,
+                anyhow::anyhow!("SCENARIO_EXISTS: scenario id is used by a custom scenario"),
+            ));
+        }
+
+        let repo = ScenarioCatalogRepository::new(self.pool.clone());
+        let sort_order = match request.sort_order {
+            Some(sort_order) => sort_order,
+            None => repo.next_sort_order().await.map_err(AppError::from)?,
+        };
+        let inserted = repo
+            .insert(
+                &request.scenario,
+                sort_order,
+                request.is_active.unwrap_or(true),
+                Some(user_id),
+            )
+            .await
+            .map_err(AppError::from)?;
+        if !inserted {
+            return Err(AppError::new(
+                StatusCode::CONFLICT,
+                anyhow::anyhow!("SCENARIO_EXISTS: scenario id is already in the catalog"),
+            ));
+        }
+
+        self.get_catalog_entry(&request.scenario.id).await
+    }
+
+    pub async fn update_catalog_scenario(
+        &self,
+        user_id: &str,
+        id: &str,
+        request: UpsertCatalogScenarioRequest,
+    ) -> Result<CatalogScenario, AppError> {
+        Self::require_catalog_admin(user_id)?;
+        let mut scenario = request.scenario;
+        scenario.id = id.to_string();
+        Self::validate_weights(&scenario)?;
+
+        let repo = ScenarioCatalogRepository::new(self.pool.clone());
+        let updated = repo
+            .update(
+                &scenario,
+                request.sort_order,
+                request.is_active,
+                request.expected_version,
+                user_id,
+            )
+            .await
+            .map_err(AppError::from)?;
+        if updated.is_none() {
+            // Distinguish a missing scenario from a stale expectedVersion.
+            self.get_catalog_entry(id).await?;
+            return Err(AppError::new(
+                StatusCode::CONFLICT,
+                anyhow::anyhow!("SCENARIO_VERSION_CONFLICT: scenario was updated by someone else"),
+            ));
+        }
+
+        self.get_catalog_entry(id).await
+    }
+
+    /// Retire a scenario: it disappears from `/scenarios` and cannot start new
+    /// sessions, but stays resolvable for existing ones.
+    pub async fn deactivate_catalog_scenario(
+        &self,
+        user_id: &str,
+        id: &str,
+    ) -> Result<CatalogScenario, AppError> {
+        Self::require_catalog_admin(user_id)?;
+        let repo = ScenarioCatalogRepository::new(self.pool.clone());
+        if !repo
+            .set_active(id, false, user_id)
+            .await
+            .map_err(AppError::from)?
+        {
+            return Err(Self::catalog_not_found());
         }
 
+        self.get_catalog_entry(id).await
+    }
+
+    pub async fn list_catalog_versions(
+        &self,
+        user_id: &str,
+        id: &str,
+    ) -> Result<Vec<ScenarioVersion>, AppError> {
+        Self::require_catalog_admin(user_id)?;
+        self.get_catalog_entry(id).await?;
+        let repo = ScenarioCatalogRepository::new(self.pool.clone());
+        repo.list_versions(id).await.map_err(AppError::from)
+    }
+
+    pub async fn list_organization_scenarios(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<OrganizationScenario>, AppError> {
+        let organization_id = self.require_organization_scenario_manager(user_id).await?;
         let repo = ScenarioRepository::new(self.pool.clone());
-        repo.get_for_user(id, user_id)
+        repo.list_for_organization(&organization_id)
+            .await
+            .map_err(AppError::from)
+    }
+
+    /// Create an organization scenario as a draft; members see it once it
+    /// is published.
+    pub async fn create_organization_scenario(
+        &self,
+        user_id: &str,
+        scenario: &Scenario,
+    ) -> Result<OrganizationScenario, AppError> {
+        let organization_id = self.require_organization_scenario_manager(user_id).await?;
+        Self::validate_id(scenario)?;
+        Self::validate_weights(scenario)?;
+        if self
+            .find_catalog_scenario(&scenario.id, true)
+            .await?
+            .is_some()
+        {
+            return Err(client_error(
+                "このIDは組み込みシナリオで既に使用されています",
+            ));
+        }
+
+        let repo = ScenarioRepository::new(self.pool.clone());
+        if repo
+            .get_by_id(&scenario.id)
             .await
             .map_err(AppError::from)?
+            .is_some()
+        {
+            return Err(client_error("このIDのカスタムシナリオは既に存在します"));
+        }
+
+        repo.create_for_organization(scenario, &organization_id, user_id)
+            .await
+            .map_err(AppError::from)
+    }
+
+    pub async fn update_organization_scenario(
+        &self,
+        user_id: &str,
+        id: &str,
+        scenario: Scenario,
+    ) -> Result<OrganizationScenario, AppError> {
+        let organization_id = self.require_organization_scenario_manager(user_id).await?;
+        let mut scenario = scenario;
+        scenario.id = id.to_string();
+        Self::validate_weights(&scenario)?;
+
+        let repo = ScenarioRepository::new(self.pool.clone());
+        if !repo
+            .update_for_organization(&scenario, &organization_id)
+            .await
+            .map_err(AppError::from)?
+        {
+            return Err(Self::catalog_not_found());
+        }
+
+        self.get_organization_entry(id, &organization_id).await
+    }
+
+    pub async fn publish_organization_scenario(
+        &self,
+        user_id: &str,
+        id: &str,
+    ) -> Result<OrganizationScenario, AppError> {
+        self.set_organization_scenario_status(user_id, id, "published")
+            .await
+    }
+
+    /// Retire a published scenario: members can no longer start it, but
+    /// existing sessions keep resolving it.
+    pub async fn retire_organization_scenario(
+        &self,
+        user_id: &str,
+        id: &str,
+    ) -> Result<OrganizationScenario, AppError> {
+        self.set_organization_scenario_status(user_id, id, "retired")
+            .await
+    }
+
+    async fn set_organization_scenario_status(
+        &self,
+        user_id: &str,
+        id: &str,
+        status: &str,
+    ) -> Result<OrganizationScenario, AppError> {
+        let organization_id = self.require_organization_scenario_manager(user_id).await?;
+        let repo = ScenarioRepository::new(self.pool.clone());
+        if !repo
+            .set_organization_status(id, &organization_id, status)
+            .await
+            .map_err(AppError::from)?
+        {
+            return Err(Self::catalog_not_found());
+        }
+
+        self.get_organization_entry(id, &organization_id).await
+    }
+
+    async fn get_organization_entry(
+        &self,
+        id: &str,
+        organization_id: &str,
+    ) -> Result<OrganizationScenario, AppError> {
+        let repo = ScenarioRepository::new(self.pool.clone());
+        repo.get_for_organization(id, organization_id)
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(Self::catalog_not_found)
+    }
+
+    /// Resolve the user's current organization and require an owner/admin
+    /// membership in it.
+    async fn require_organization_scenario_manager(
+        &self,
+        user_id: &str,
+    ) -> Result<String, AppError> {
+        let org_repo = OrganizationRepository::new(self.pool.clone());
+        let membership = org_repo
+            .list_active_orgs_for_user(user_id)
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to load memberships: {e}")))?
+            .into_iter()
+            .next()
             .ok_or_else(|| {
-                AppError::new(StatusCode::NOT_FOUND, anyhow::anyhow!("scenario not found"))
-            })
+                AppError::new(
+                    StatusCode::NOT_FOUND,
+                    anyhow::anyhow!("organization not found for current user"),
+                )
+            })?;
+        if !can_manage_organization(&membership.role) {
+            return Err(forbidden_error(
+                "FORBIDDEN_ROLE: insufficient permission for organization scenario management",
+            ));
+        }
+        Ok(membership.organization_id)
+    }
+
+    async fn get_catalog_entry(&self, id: &str) -> Result<CatalogScenario, AppError> {
+        let repo = ScenarioCatalogRepository::new(self.pool.clone());
+        repo.get(id)
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(Self::catalog_not_found)
     }
 }
 
 impl ScenarioService {
+    fn catalog_not_found() -> AppError {
+        AppError::new(StatusCode::NOT_FOUND, anyhow::anyhow!("scenario not found"))
+    }
+
+    fn require_catalog_admin(user_id: &str) -> Result<(), AppError> {
+        if is_admin_override_user(user_id) {
+            return Ok(());
+        }
+        Err(forbidden_error(
+            "FORBIDDEN_ROLE: scenario catalog management requires platform admin",
+        ))
+    }
+
     fn validate_id(scenario: &Scenario) -> Result<(), AppError> {
         if scenario.id.is_empty() {
             return Err(client_error("IDは必須です"));
         }
 
-        if default_scenarios().iter().any(|s| s.id == scenario.id) {
-            return Err(client_error(
-                "このIDは組み込みシナリオで既に使用されています",
-            ));
-        }
-
         if !scenario
             .id
             .chars()
@@ -100,3 +789,130 @@ impl ScenarioService {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YAML_BUNDLE: &str = r#"
+formatVersion: 1
+scenarios:
+  - id: checkout-requirements
+    title: 決済フローの要件整理
+    description: 決済フローの要件を整理する。
+    scenarioType: requirement-definition
+    kickoffPrompt: 要件を整理してください。
+    agentPrompt: あなたは決済チームのエンジニアです。
+    singleResponse: true
+    passingScore: 75
+    missions:
+      - id: m1
+        title: 対象の決済手段を確認する
+        order: 1
+    evaluationCriteria:
+      - name: 網羅性
+        weight: 100
+        scoringGuidelines:
+          excellent: 全て網羅
+          good: 概ね網羅
+          needsImprovement: 一部不足
+          poor: 不足
+"#;
+
+    #[test]
+    fn parse_bundle_reads_yaml_with_nested_fields() {
+        let bundle = ScenarioService::parse_bundle(YAML_BUNDLE, Some("application/yaml"))
+            .expect("yaml bundle");
+
+        assert_eq!(bundle.format_version, 1);
+        let scenario = &bundle.scenarios[0];
+        assert_eq!(scenario.id, "checkout-requirements");
+        assert_eq!(scenario.single_response, Some(true));
+        assert_eq!(scenario.missions.as_ref().map(Vec::len), Some(1));
+        assert_eq!(
+            scenario.evaluation_criteria[0].scoring_guidelines.excellent,
+            "全て網羅"
+        );
+    }
+
+    #[test]
+    fn parse_bundle_round_trips_through_json_without_content_type() {
+        let bundle = ScenarioService::parse_bundle(YAML_BUNDLE, None).expect("yaml fallback");
+        let json = serde_json::to_string(&bundle).expect("serialize bundle");
+
+        let parsed = ScenarioService::parse_bundle(&json, None).expect("json bundle");
+
+        assert_eq!(
+            parsed.scenarios[0].agent_prompt,
+            bundle.scenarios[0].agent_prompt
+        );
+        assert_eq!(parsed.scenarios[0].passing_score, Some(75.0));
+    }
+
+    #[test]
+    fn parse_bundle_rejects_malformed_body() {
+        let error = ScenarioService::parse_bundle("{not a bundle", Some("application/json"))
+            .err()
+            .expect("parse error");
+
+        assert!(error.to_string().starts_with("INVALID_BUNDLE:"));
+    }
+
+    const CSV: &str = "\u{feff}カテゴリ,シナリオ名,目的,完了条件1,完了条件2,完了条件3
+基礎,議事メモの作成と共有,チーム内の認識を揃える,決定事項を整理する,次のアクションを明記する,
+チャレンジ,炎上案件の立て直し,計画を再設計する,現状を把握する,,
+,,,,,
+未知,不明なシナリオ,,,,
+";
+
+    #[test]
+    fn parse_csv_maps_rows_to_scenarios_with_missions() {
+        let entries = ScenarioService::parse_csv(CSV).expect("csv");
+
+        assert_eq!(entries.len(), 3, "blank rows are skipped");
+        let basic = &entries[0];
+        assert_eq!(basic.row, Some(2));
+        assert!(basic.errors.is_empty());
+        assert!(basic.scenario.id.starts_with("basic-csv-"));
+        assert_eq!(basic.scenario.scenario_type, ScenarioType::SoftSkills);
+        assert_eq!(basic.scenario.description, "チーム内の認識を揃える");
+        let missions = basic.scenario.missions.as_ref().expect("missions");
+        assert_eq!(missions.len(), 2);
+        assert_eq!(missions[1].title, "次のアクションを明記する");
+        assert_eq!(missions[1].order, 2);
+        let total: f32 = basic
+            .scenario
+            .evaluation_criteria
+            .iter()
+            .map(|c| c.weight)
+            .sum();
+        assert_eq!(total, 100.0);
+
+        let challenge = &entries[1];
+        assert!(challenge.scenario.id.starts_with("challenge-csv-"));
+        assert_eq!(
+            challenge.scenario.scenario_type,
+            ScenarioType::BusinessExecution
+        );
+    }
+
+    #[test]
+    fn parse_csv_reports_errors_by_row() {
+        let entries = ScenarioService::parse_csv(CSV).expect("csv");
+
+        let invalid = &entries[2];
+        assert_eq!(invalid.row, Some(5));
+        assert!(invalid.errors.iter().any(|e| e.contains("不明なカテゴリ")));
+        assert!(invalid.errors.iter().any(|e| e.contains("目的は必須")));
+        assert!(invalid.errors.iter().any(|e| e.contains("完了条件")));
+    }
+
+    #[test]
+    fn parse_csv_requires_known_columns() {
+        let error = ScenarioService::parse_csv("name,purpose\nfoo,bar\n")
+            .err()
+            .expect("missing columns");
+
+        assert!(error.to_string().starts_with("INVALID_CSV:"));
+    }
+}
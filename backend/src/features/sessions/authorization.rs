@@ -47,6 +47,16 @@ impl SessionAccessContext {
         self.is_owner() || self.is_admin_override()
     }
 
+    /// Official evaluations feed organization progress, so in an organization
+    /// only managers pick them; personal sessions are left to their owner.
+    pub fn can_select_official_evaluation(&self) -> bool {
+        if self.is_owner() {
+            return self.session.organization_id.is_none();
+        }
+        self.is_admin_override()
+            || matches_org_role(self.org_role(), &["owner", "admin", "manager"])
+    }
+
     pub fn can_comment(&self) -> bool {
         self.is_owner()
             || self.is_admin_override()
@@ -170,6 +180,35 @@ mod tests {
         assert!(context.can_edit_session());
         assert!(context.can_comment());
         assert!(context.can_manage_outputs());
+        assert!(context.can_select_official_evaluation());
         assert_eq!(context.comment_author_role(), Some("owner"));
     }
+
+    #[test]
+    fn official_evaluation_in_organization_is_chosen_by_managers() {
+        let mut session = sample_session();
+        session.organization_id = Some("org-test".to_string());
+
+        let owner = SessionAccessContext {
+            session: session.clone(),
+            scope: SessionPermissionScope::Owner,
+        };
+        let manager = SessionAccessContext {
+            session: session.clone(),
+            scope: SessionPermissionScope::OrganizationRole("manager".to_string()),
+        };
+        let reviewer = SessionAccessContext {
+            session,
+            scope: SessionPermissionScope::OrganizationRole("reviewer".to_string()),
+        };
+        let personal_owner = SessionAccessContext {
+            session: sample_session(),
+            scope: SessionPermissionScope::Owner,
+        };
+
+        assert!(!owner.can_select_official_evaluation());
+        assert!(manager.can_select_official_evaluation());
+        assert!(!reviewer.can_select_official_evaluation());
+        assert!(personal_owner.can_select_official_evaluation());
+    }
 }
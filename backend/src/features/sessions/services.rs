@@ -9,9 +9,9 @@ use crate::features::feature_flags::services::FeatureFlagService;
 use crate::features::messages::repository::MessageRepository;
 use crate::features::organizations::repository::OrganizationRepository;
 use crate::features::sessions::repository::SessionRepository;
+use crate::features::scenarios::services::ScenarioService;
 use crate::models::{
-    default_scenarios, HistoryItem, HistoryMetadata, MessageRole, ProgressFlags, Session,
-    SessionStatus,
+    HistoryItem, HistoryMetadata, MessageRole, ProgressFlags, Session, SessionStatus,
 };
 use crate::shared::admin_override::is_admin_override_user;
 use crate::shared::helpers::{next_id, now_ts};
@@ -32,9 +32,9 @@ impl SessionService {
         scenario_id: String,
         user_id: &str,
     ) -> Result<Session, AppError> {
-        let scenario = default_scenarios()
-            .into_iter()
-            .find(|s| s.id == scenario_id)
+        let scenario = ScenarioService::new(self.pool.clone())
+            .find_scenario_for_user(&scenario_id, user_id)
+            .await?
             .ok_or_else(|| client_error("scenario not found"))?;
         let discipline = Some(scenario.scenario_type.to_discipline());
         let mut organization_id = None;
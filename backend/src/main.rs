@@ -6,13 +6,17 @@ mod models;
 mod shared;
 mod state;
 
-use axum::{middleware::from_fn, Router};
+use axum::{http::HeaderName, middleware::from_fn, Router};
 use sqlx::{migrate::Migrator, PgPool};
 use std::env;
 use std::time::Duration;
 use tower::make::Shared;
 use tower_http::cors::{Any, CorsLayer};
 
+use features::entitlements::fair_use::{
+    RATE_LIMIT_LIMIT_HEADER, RATE_LIMIT_REMAINING_HEADER, RATE_LIMIT_RESET_HEADER,
+    RATE_LIMIT_SCOPE_HEADER,
+};
 use middleware::telemetry::{init_tracing, tracing_middleware};
 use state::state_with_pool;
 
@@ -23,11 +27,62 @@ async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::Error> {
     Ok(())
 }
 
-#[tokio::main]
-async fn main() {
-    dotenvy::dotenv().ok();
-    init_tracing();
+/// `backend gemini-stub [fixtures.json]`: serve scripted Gemini responses on
+/// `GEMINI_STUB_ADDR` (default `127.0.0.1:3999`) instead of running the API.
+async fn run_gemini_stub(fixtures_path: Option<String>) {
+    let fixtures = match fixtures_path {
+        Some(path) => {
+            let raw = std::fs::read_to_string(&path)
+                .unwrap_or_else(|e| panic!("failed to read fixtures {path}: {e}"));
+            shared::gemini_stub::parse_fixtures(&raw).expect("failed to parse fixtures")
+        }
+        None => Vec::new(),
+    };
+    let addr = env::var("GEMINI_STUB_ADDR").unwrap_or_else(|_| "127.0.0.1:3999".to_string());
+
+    let fixture_count = fixtures.len();
+    let stub = shared::gemini_stub::GeminiStub::spawn(&addr, fixtures)
+        .await
+        .expect("failed to bind");
+    tracing::info!(
+        "Gemini stub serving {} fixtures on {} (set GEMINI_API_BASE_URL to this)",
+        fixture_count,
+        stub.base_url()
+    );
+    tokio::signal::ctrl_c()
+        .await
+        .expect("failed to listen for shutdown");
+    tracing::info!("Gemini stub answered {} requests", stub.requests().len());
+}
+
+/// `backend email-stub`: capture transactional email on `EMAIL_STUB_ADDR`
+/// (default `127.0.0.1:3998`) instead of sending it through Resend. Set
+/// `EMAIL_STUB_STATUS` (e.g. `500`) to simulate a provider outage.
+async fn run_email_stub() {
+    let addr = env::var("EMAIL_STUB_ADDR").unwrap_or_else(|_| "127.0.0.1:3998".to_string());
+
+    let stub = shared::email_stub::EmailStub::spawn(&addr)
+        .await
+        .expect("failed to bind");
+    if let Ok(status) = env::var("EMAIL_STUB_STATUS") {
+        let status = status
+            .parse::<u16>()
+            .ok()
+            .and_then(|code| axum::http::StatusCode::from_u16(code).ok())
+            .unwrap_or_else(|| panic!("invalid EMAIL_STUB_STATUS: {status}"));
+        stub.respond_with(status);
+    }
+    tracing::info!(
+        "Email stub listening on {} (set INVITATION_EMAIL_API_BASE_URL to this)",
+        stub.base_url()
+    );
+    tokio::signal::ctrl_c()
+        .await
+        .expect("failed to listen for shutdown");
+    tracing::info!("Email stub captured {} emails", stub.emails().len());
+}
 
+async fn connect_and_migrate() -> PgPool {
     let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
     let connect_timeout = Duration::from_secs(10);
     let migrations_timeout = Duration::from_secs(30);
@@ -45,6 +100,215 @@ async fn main() {
         .expect("failed to run migrations");
     tracing::info!("Migrations completed successfully");
 
+    pool
+}
+
+/// `backend reset-credits`: run the monthly credit reset once and exit.
+async fn run_credit_reset() {
+    let pool = connect_and_migrate().await;
+    let reset = features::credits::services::CreditService::new(pool)
+        .run_monthly_reset()
+        .await
+        .expect("failed to reset credit wallets");
+    tracing::info!("Monthly credit reset completed ({} wallets)", reset);
+}
+
+/// `backend stripe-events [failed|received]`: print webhook events that are
+/// not processed yet, one JSON object per line.
+async fn run_list_stripe_events(status: Option<String>) {
+    let pool = connect_and_migrate().await;
+    let events = features::billing::services::BillingService::new(pool)
+        .list_unprocessed_stripe_events(status.as_deref(), 500)
+        .await
+        .expect("failed to list stripe events");
+    for event in &events {
+        println!(
+            "{}",
+            serde_json::to_string(event).expect("failed to serialize stripe event")
+        );
+    }
+    tracing::info!("{} unprocessed stripe events", events.len());
+}
+
+/// `backend replay-stripe-events [event_id ...]`: run the given events (or
+/// every failed and unprocessed one) through the webhook handlers again.
+/// `received` events younger than five minutes are skipped.
+async fn run_replay_stripe_events(event_ids: Vec<String>) {
+    let pool = connect_and_migrate().await;
+    let billing = features::billing::services::BillingService::new(pool);
+    let event_ids = if event_ids.is_empty() {
+        billing
+            .list_unprocessed_stripe_events(None, 500)
+            .await
+            .expect("failed to list stripe events")
+            .into_iter()
+            .map(|event| event.event_id)
+            .collect()
+    } else {
+        event_ids
+    };
+    let results = billing
+        .replay_stripe_events(&event_ids)
+        .await
+        .expect("failed to replay stripe events");
+    for result in &results {
+        println!(
+            "{}",
+            serde_json::to_string(result).expect("failed to serialize replay result")
+        );
+    }
+    let failed = results
+        .iter()
+        .filter(|result| result.outcome == "failed")
+        .count();
+    tracing::info!("Replayed {} stripe events ({failed} failed)", results.len());
+}
+
+/// `backend import-stripe-event <event.json>`: record and process an event
+/// file without a signature (requires `BILLING_PROVIDER=mock`).
+async fn run_import_stripe_event(path: Option<String>) {
+    let path = path.expect("usage: backend import-stripe-event <event.json>");
+    let payload =
+        std::fs::read(&path).unwrap_or_else(|e| panic!("failed to read event {path}: {e}"));
+    let pool = connect_and_migrate().await;
+    let response = features::billing::services::BillingService::new(pool)
+        .import_stripe_event(&payload)
+        .await
+        .expect("failed to import stripe event");
+    tracing::info!(
+        "Imported stripe event {} (duplicate: {})",
+        response.event_type.unwrap_or_default(),
+        response.duplicate
+    );
+}
+
+/// Run `sweep` every `interval_env` seconds (default one hour; `0` disables
+/// the scheduler). Runs that changed something are logged with `describe`,
+/// failures are logged and retried on the next tick.
+fn spawn_scheduler<F, Fut, N, E>(
+    name: &'static str,
+    interval_env: &'static str,
+    describe: fn(N) -> String,
+    sweep: F,
+) where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = Result<N, E>> + Send + 'static,
+    N: Default + PartialEq + Send + 'static,
+    E: std::fmt::Display + Send + 'static,
+{
+    let interval_secs = env::var(interval_env)
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .unwrap_or(3600);
+    if interval_secs == 0 {
+        tracing::info!("{name} scheduler disabled");
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(interval_secs));
+        loop {
+            ticker.tick().await;
+            match sweep().await {
+                Ok(count) if count == N::default() => {}
+                Ok(count) => tracing::info!("{}", describe(count)),
+                Err(e) => tracing::error!("{name} failed: {e}"),
+            }
+        }
+    });
+}
+
+/// Run the monthly credit reset every `CREDIT_RESET_INTERVAL_SECS` seconds
+/// (default one hour; `0` disables the scheduler).
+fn spawn_credit_reset_scheduler(pool: PgPool) {
+    let service = features::credits::services::CreditService::new(pool);
+    spawn_scheduler(
+        "Monthly credit reset",
+        "CREDIT_RESET_INTERVAL_SECS",
+        |reset| format!("Monthly credit reset applied to {reset} wallets"),
+        move || {
+            let service = service.clone();
+            async move { service.run_monthly_reset().await }
+        },
+    );
+}
+
+/// Expire subscription entitlements whose `valid_until` has passed every
+/// `BILLING_LAPSE_INTERVAL_SECS` seconds (default one hour; `0` disables the
+/// scheduler), so canceled and lapsed plans lose access on time.
+fn spawn_billing_lapse_scheduler(pool: PgPool) {
+    spawn_scheduler(
+        "Billing lapse sweep",
+        "BILLING_LAPSE_INTERVAL_SECS",
+        |expired| format!("Expired {expired} lapsed subscription entitlements"),
+        move || {
+            let pool = pool.clone();
+            async move { features::billing::lifecycle::run_lapse_sweep(&pool).await }
+        },
+    );
+}
+
+/// Mark pending invitations past their expiry as expired every
+/// `INVITATION_EXPIRY_INTERVAL_SECS` seconds (default one hour; `0` disables
+/// the scheduler), so they stop counting against the seat limit.
+fn spawn_invitation_expiry_scheduler(pool: PgPool) {
+    let service = features::organizations::services::OrganizationService::new(pool);
+    spawn_scheduler(
+        "Invitation expiry sweep",
+        "INVITATION_EXPIRY_INTERVAL_SECS",
+        |expired| format!("Expired {expired} stale organization invitations"),
+        move || {
+            let service = service.clone();
+            async move { service.expire_stale_invitations().await }
+        },
+    );
+}
+
+#[tokio::main]
+async fn main() {
+    dotenvy::dotenv().ok();
+    init_tracing();
+
+    let mut args = env::args().skip(1);
+    match args.next().as_deref() {
+        Some("gemini-stub") => {
+            run_gemini_stub(args.next()).await;
+            return;
+        }
+        Some("email-stub") => {
+            run_email_stub().await;
+            return;
+        }
+        Some("reset-credits") => {
+            run_credit_reset().await;
+            return;
+        }
+        Some("stripe-events") => {
+            run_list_stripe_events(args.next()).await;
+            return;
+        }
+        Some("replay-stripe-events") => {
+            run_replay_stripe_events(args.collect()).await;
+            return;
+        }
+        Some("import-stripe-event") => {
+            run_import_stripe_event(args.next()).await;
+            return;
+        }
+        _ => {}
+    }
+
+    let pool = connect_and_migrate().await;
+
+    let seeded = features::scenarios::services::ScenarioService::new(pool.clone())
+        .seed_builtin_scenarios()
+        .await
+        .expect("failed to seed scenario catalog");
+    tracing::info!(
+        "Scenario catalog seeded ({} new built-in scenarios)",
+        seeded
+    );
+
     // Load Auth0 configuration
     let auth0_domain = env::var("AUTH0_DOMAIN").expect("AUTH0_DOMAIN must be set");
     let _auth0_audience = env::var("AUTH0_AUDIENCE").expect("AUTH0_AUDIENCE must be set");
@@ -55,6 +319,10 @@ async fn main() {
         .await
         .expect("Failed to fetch JWKS");
 
+    spawn_credit_reset_scheduler(pool.clone());
+    spawn_billing_lapse_scheduler(pool.clone());
+    spawn_invitation_expiry_scheduler(pool.clone());
+
     let state = state_with_pool(pool, jwks);
     let cors = CorsLayer::new()
         .allow_origin([
@@ -65,7 +333,13 @@ async fn main() {
             "https://pm-journey-frontend.fly.dev".parse().unwrap(),
         ])
         .allow_methods(Any)
-        .allow_headers(Any);
+        .allow_headers(Any)
+        .expose_headers([
+            HeaderName::from_static(RATE_LIMIT_LIMIT_HEADER),
+            HeaderName::from_static(RATE_LIMIT_REMAINING_HEADER),
+            HeaderName::from_static(RATE_LIMIT_RESET_HEADER),
+            HeaderName::from_static(RATE_LIMIT_SCOPE_HEADER),
+        ]);
 
     let app: Router = api::router_with_state(state)
         .layer(from_fn(tracing_middleware))
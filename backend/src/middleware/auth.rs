@@ -92,6 +92,19 @@ pub struct AuthUser {
     pub user_id: String,
     pub email: Option<String>,
     pub name: Option<String>,
+    /// Auth0 `email_verified`; absent when the token does not carry it.
+    pub email_verified: Option<bool>,
+}
+
+impl AuthUser {
+    /// The email, only when Auth0 says it is verified. Use this where the
+    /// address grants access, e.g. joining an organization by email domain.
+    pub fn verified_email(&self) -> Option<&str> {
+        if self.email_verified != Some(true) {
+            return None;
+        }
+        self.email.as_deref()
+    }
 }
 
 /// JWT Claims structure
@@ -99,6 +112,7 @@ pub struct AuthUser {
 struct Claims {
     sub: String,
     email: Option<String>,
+    email_verified: Option<bool>,
     name: Option<String>,
     picture: Option<String>,
     iss: String,
@@ -203,10 +217,25 @@ impl FromRequestParts<SharedState> for AuthUser {
                 )
             })?;
 
-        Ok(AuthUser {
+        let auth_user = AuthUser {
             user_id: claims.sub,
             email: claims.email,
             name: claims.name,
-        })
+            email_verified: claims.email_verified,
+        };
+
+        // Domain auto-join must not block sign-in; failures are only logged.
+        if let Some(email) = auth_user.verified_email() {
+            if let Err(e) = state
+                .services()
+                .organizations()
+                .join_by_email_domain(&auth_user.user_id, email)
+                .await
+            {
+                tracing::warn!("Domain auto-join failed for {}: {e}", auth_user.user_id);
+            }
+        }
+
+        Ok(auth_user)
     }
 }
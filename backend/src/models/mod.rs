@@ -27,6 +27,27 @@ pub enum ScenarioType {
 }
 
 impl ScenarioType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ScenarioType::SoftSkills => "soft-skills",
+            ScenarioType::TestCases => "test-cases",
+            ScenarioType::RequirementDefinition => "requirement-definition",
+            ScenarioType::IncidentResponse => "incident-response",
+            ScenarioType::BusinessExecution => "business-execution",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "soft-skills" => Some(ScenarioType::SoftSkills),
+            "test-cases" => Some(ScenarioType::TestCases),
+            "requirement-definition" => Some(ScenarioType::RequirementDefinition),
+            "incident-response" => Some(ScenarioType::IncidentResponse),
+            "business-execution" => Some(ScenarioType::BusinessExecution),
+            _ => None,
+        }
+    }
+
     pub fn to_discipline(&self) -> ScenarioDiscipline {
         match self {
             ScenarioType::IncidentResponse | ScenarioType::BusinessExecution => {
@@ -157,6 +178,12 @@ pub struct EvaluationCategory {
     pub weight: f32,
     pub score: Option<f32>,
     pub feedback: Option<String>,
+    /// Variance of the sampled scores when the evaluation was calibrated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score_variance: Option<f32>,
+    /// Samples that scored this category when the evaluation was calibrated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sample_count: Option<u32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
@@ -335,6 +362,18 @@ fn criterion(name: &str, weight: f32) -> RatingCriterion {
     }
 }
 
+/// Generic criteria for scenarios authored without their own rubric.
+pub(crate) fn default_evaluation_criteria() -> Vec<RatingCriterion> {
+    vec![
+        criterion("方針提示とリード力", 25.0),
+        criterion("計画と実行可能性", 25.0),
+        criterion("コラボレーションとフィードバック", 25.0),
+        criterion("リスク/前提管理と改善姿勢", 25.0),
+    ]
+}
+
+/// Built-in scenarios. Only used to seed the `scenarios` catalog table; runtime
+/// lookups go through `ScenarioService`.
 pub fn default_scenarios() -> Vec<Scenario> {
     let mut scenarios = vec![
         Scenario {
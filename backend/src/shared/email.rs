@@ -0,0 +1,288 @@
+//! Transactional email: per-locale templates and delivery through Resend.
+//!
+//! Templates live in `email_templates/<locale>/<name>.{subject.txt,txt,html}`
+//! and are compiled into the binary. `{{key}}` placeholders are filled from the
+//! variables passed to [`render`]; values are HTML-escaped in the HTML body.
+//!
+//! Each caller sends under a config prefix, so invitations read
+//! `INVITATION_EMAIL_FROM` / `INVITATION_EMAIL_REPLY_TO` /
+//! `INVITATION_EMAIL_API_BASE_URL` and billing notices the `BILLING_EMAIL_*`
+//! equivalents. `RESEND_API_KEY` is shared. Pointing the base URL at
+//! [`crate::shared::email_stub`] captures mail locally.
+
+use crate::shared::helpers::first_non_empty_env;
+
+const DEFAULT_RESEND_API_BASE_URL: &str = "https://api.resend.com";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailLocale {
+    Ja,
+    En,
+}
+
+impl EmailLocale {
+    /// Accepts a bare language or a language tag such as `en-US`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let language = value.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        match language.as_str() {
+            "ja" => Some(Self::Ja),
+            "en" => Some(Self::En),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ja => "ja",
+            Self::En => "en",
+        }
+    }
+
+    /// `EMAIL_DEFAULT_LOCALE` when set to a supported locale, else Japanese.
+    pub fn default_from_env() -> Self {
+        first_non_empty_env(&["EMAIL_DEFAULT_LOCALE"])
+            .and_then(|value| Self::parse(&value))
+            .unwrap_or(Self::Ja)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailTemplate {
+    /// Variables: `organization_name`, `inviter_name`, `invite_link`, `expires_at`.
+    Invitation,
+    /// Variables: `organization_name`, `billing_link`.
+    TeamPlanLapsed,
+}
+
+impl EmailTemplate {
+    fn sources(self, locale: EmailLocale) -> [&'static str; 3] {
+        match (self, locale) {
+            (Self::Invitation, EmailLocale::Ja) => [
+                include_str!("email_templates/ja/invitation.subject.txt"),
+                include_str!("email_templates/ja/invitation.txt"),
+                include_str!("email_templates/ja/invitation.html"),
+            ],
+            (Self::Invitation, EmailLocale::En) => [
+                include_str!("email_templates/en/invitation.subject.txt"),
+                include_str!("email_templates/en/invitation.txt"),
+                include_str!("email_templates/en/invitation.html"),
+            ],
+            (Self::TeamPlanLapsed, EmailLocale::Ja) => [
+                include_str!("email_templates/ja/team_plan_lapsed.subject.txt"),
+                include_str!("email_templates/ja/team_plan_lapsed.txt"),
+                include_str!("email_templates/ja/team_plan_lapsed.html"),
+            ],
+            (Self::TeamPlanLapsed, EmailLocale::En) => [
+                include_str!("email_templates/en/team_plan_lapsed.subject.txt"),
+                include_str!("email_templates/en/team_plan_lapsed.txt"),
+                include_str!("email_templates/en/team_plan_lapsed.html"),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedEmail {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+/// Fill a template. Placeholders without a matching variable are left as-is.
+pub fn render(
+    template: EmailTemplate,
+    locale: EmailLocale,
+    vars: &[(&str, &str)],
+) -> RenderedEmail {
+    let [subject, text, html] = template.sources(locale);
+    RenderedEmail {
+        subject: fill(subject.trim(), vars, false),
+        text: fill(text, vars, false),
+        html: fill(html, vars, true),
+    }
+}
+
+fn fill(source: &str, vars: &[(&str, &str)], escape: bool) -> String {
+    let mut output = String::with_capacity(source.len());
+    let mut rest = source;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        let key = after[..end].trim();
+        match vars.iter().find(|(name, _)| *name == key) {
+            Some((_, value)) if escape => output.push_str(&escape_html(value)),
+            Some((_, value)) => output.push_str(value),
+            None => output.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after[end + 2..];
+    }
+    output.push_str(rest);
+    output
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Outcome of a send attempt: `sent`, `skipped` (not configured) or `failed`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailDelivery {
+    pub status: &'static str,
+    pub message: Option<String>,
+}
+
+impl EmailDelivery {
+    fn skipped(message: String) -> Self {
+        Self {
+            status: "skipped",
+            message: Some(message),
+        }
+    }
+
+    fn failed(message: String) -> Self {
+        Self {
+            status: "failed",
+            message: Some(message),
+        }
+    }
+}
+
+/// Send `email` to `to_email` with the `{config_prefix}_EMAIL_*` settings.
+/// Delivery problems are reported in the result rather than as errors, so a
+/// failed email never fails the action that triggered it.
+pub async fn send(config_prefix: &str, to_email: &str, email: &RenderedEmail) -> EmailDelivery {
+    let Some(resend_api_key) = first_non_empty_env(&["RESEND_API_KEY"]) else {
+        return EmailDelivery::skipped(format!(
+            "{config_prefix}_EMAIL_NOT_CONFIGURED: RESEND_API_KEY is not set"
+        ));
+    };
+    let Some(from_email) = first_non_empty_env(&[&format!("{config_prefix}_EMAIL_FROM")]) else {
+        return EmailDelivery::skipped(format!(
+            "{config_prefix}_EMAIL_NOT_CONFIGURED: {config_prefix}_EMAIL_FROM is not set"
+        ));
+    };
+    let api_base_url = first_non_empty_env(&[&format!("{config_prefix}_EMAIL_API_BASE_URL")])
+        .unwrap_or_else(|| DEFAULT_RESEND_API_BASE_URL.to_string());
+    if !(api_base_url.starts_with("http://") || api_base_url.starts_with("https://")) {
+        return EmailDelivery::failed(format!(
+            "{config_prefix}_EMAIL_CONFIG_INVALID: {config_prefix}_EMAIL_API_BASE_URL must start with http:// or https://"
+        ));
+    }
+
+    let mut payload = serde_json::json!({
+        "from": from_email,
+        "to": [to_email],
+        "subject": email.subject,
+        "text": email.text,
+        "html": email.html,
+    });
+    if let Some(reply_to) = first_non_empty_env(&[&format!("{config_prefix}_EMAIL_REPLY_TO")]) {
+        payload["reply_to"] = serde_json::json!(reply_to);
+    }
+
+    let endpoint = format!("{}/emails", api_base_url.trim_end_matches('/'));
+    let response = reqwest::Client::new()
+        .post(&endpoint)
+        .bearer_auth(resend_api_key)
+        .json(&payload)
+        .send()
+        .await;
+    let response = match response {
+        Ok(response) => response,
+        Err(error) => {
+            return EmailDelivery::failed(format!("{config_prefix}_EMAIL_SEND_FAILED: {error}"));
+        }
+    };
+
+    let status = response.status();
+    if status.is_success() {
+        return EmailDelivery {
+            status: "sent",
+            message: Some(format!("{config_prefix}_EMAIL_SENT")),
+        };
+    }
+    let body = response
+        .text()
+        .await
+        .unwrap_or_else(|_| "failed to read response body".to_string());
+    EmailDelivery::failed(format!(
+        "{config_prefix}_EMAIL_SEND_FAILED: provider responded with {status}: {body}"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_vars() -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("organization_name", "Acme <PM>"),
+            ("inviter_name", "Hanako"),
+            (
+                "invite_link",
+                "https://app.example.com/team/onboarding?invite=t&x=1",
+            ),
+            ("expires_at", "2026-10-24T00:00:00Z"),
+            ("billing_link", "https://app.example.com/settings/billing"),
+        ]
+    }
+
+    #[test]
+    fn every_template_fills_all_placeholders_in_every_locale() {
+        for template in [EmailTemplate::Invitation, EmailTemplate::TeamPlanLapsed] {
+            for locale in [EmailLocale::Ja, EmailLocale::En] {
+                let email = render(template, locale, &sample_vars());
+                for part in [&email.subject, &email.text, &email.html] {
+                    assert!(
+                        !part.contains("{{"),
+                        "{template:?}/{locale:?} left a placeholder: {part}"
+                    );
+                }
+                assert!(!email.subject.contains('\n'));
+            }
+        }
+    }
+
+    #[test]
+    fn html_body_escapes_values_and_text_body_does_not() {
+        let email = render(EmailTemplate::Invitation, EmailLocale::En, &sample_vars());
+
+        assert!(email.html.contains("Acme &lt;PM&gt;"));
+        assert!(email
+            .html
+            .contains(r#"href="https://app.example.com/team/onboarding?invite=t&amp;x=1""#));
+        assert!(email.text.contains("Acme <PM>"));
+        assert!(email
+            .text
+            .contains("https://app.example.com/team/onboarding?invite=t&x=1"));
+    }
+
+    #[test]
+    fn unknown_placeholders_are_kept() {
+        assert_eq!(fill("a {{missing}} b", &[], false), "a {{missing}} b");
+        assert_eq!(fill("open {{ brace", &[], false), "open {{ brace");
+    }
+
+    #[test]
+    fn locale_parses_language_tags() {
+        assert_eq!(EmailLocale::parse("en-US"), Some(EmailLocale::En));
+        assert_eq!(EmailLocale::parse("JA_jp"), Some(EmailLocale::Ja));
+        assert_eq!(EmailLocale::parse("fr"), None);
+        assert_eq!(EmailLocale::parse(""), None);
+    }
+}
@@ -0,0 +1,135 @@
+//! Stand-in for the Resend `POST /emails` API.
+//!
+//! Point `INVITATION_EMAIL_API_BASE_URL` (or `BILLING_EMAIL_API_BASE_URL`) at a
+//! running stub and every transactional email is captured instead of sent.
+//! Integration tests spawn it in-process with [`EmailStub::spawn`]; for local
+//! development run `backend email-stub`, which logs each email it receives.
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use serde_json::json;
+use tokio::net::TcpListener;
+
+/// An email the stub received, in arrival order.
+#[derive(Debug, Clone)]
+pub struct StubEmail {
+    pub body: serde_json::Value,
+}
+
+impl StubEmail {
+    pub fn subject(&self) -> &str {
+        self.body["subject"].as_str().unwrap_or_default()
+    }
+
+    pub fn text(&self) -> &str {
+        self.body["text"].as_str().unwrap_or_default()
+    }
+
+    pub fn html(&self) -> &str {
+        self.body["html"].as_str().unwrap_or_default()
+    }
+}
+
+struct StubState {
+    status: StatusCode,
+    emails: Vec<StubEmail>,
+}
+
+type SharedState = Arc<Mutex<StubState>>;
+
+fn router(state: SharedState) -> Router {
+    Router::new()
+        .route("/emails", post(handle_send))
+        .with_state(state)
+}
+
+/// Stub server running on a background task until dropped.
+pub struct EmailStub {
+    addr: SocketAddr,
+    state: SharedState,
+    server: tokio::task::JoinHandle<()>,
+}
+
+impl EmailStub {
+    /// Listen on `addr`; pass `127.0.0.1:0` for an ephemeral port.
+    pub async fn spawn(addr: &str) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        let state: SharedState = Arc::new(Mutex::new(StubState {
+            status: StatusCode::OK,
+            emails: Vec::new(),
+        }));
+        let app = router(state.clone());
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        Ok(Self {
+            addr,
+            state,
+            server,
+        })
+    }
+
+    /// Value for `*_EMAIL_API_BASE_URL`.
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Answer subsequent requests with `status`, e.g. 500 to simulate an outage.
+    /// Requests are recorded either way.
+    pub fn respond_with(&self, status: StatusCode) {
+        self.state.lock().expect("email stub state").status = status;
+    }
+
+    pub fn emails(&self) -> Vec<StubEmail> {
+        self.state.lock().expect("email stub state").emails.clone()
+    }
+}
+
+impl Drop for EmailStub {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+async fn handle_send(
+    State(state): State<SharedState>,
+    Json(body): Json<serde_json::Value>,
+) -> Response {
+    let email = StubEmail { body };
+    tracing::info!(
+        to = %email.body["to"],
+        subject = %email.subject(),
+        text_len = email.text().len(),
+        html_len = email.html().len(),
+        "Email stub: received email"
+    );
+
+    let status = {
+        let mut state = state.lock().expect("email stub state");
+        state.emails.push(email);
+        state.status
+    };
+    if !status.is_success() {
+        let body = json!({
+            "statusCode": status.as_u16(),
+            "name": "application_error",
+            "message": "scripted error from email stub",
+        });
+        return (status, Json(body)).into_response();
+    }
+    (
+        status,
+        Json(json!({ "id": format!("email_stub_{}", uuid::Uuid::new_v4()) })),
+    )
+        .into_response()
+}
@@ -1,20 +1,35 @@
+use axum::async_trait;
+use reqwest::Client;
+use serde_json::json;
+use tokio::sync::mpsc;
+
 use crate::error::{anyhow_error, AppError};
 use crate::features::entitlements::models::PlanCode;
-use crate::shared::helpers::normalize_model_id;
+use crate::shared::helpers::{first_non_empty_env, normalize_model_id};
+use crate::shared::llm::{LlmProvider, LlmRequest, LlmResponse, LlmRole, SseDataReader};
+
+const DEFAULT_GEMINI_API_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+
+/// Request headers carrying [`LlmRequest`] metadata. Only sent when
+/// `GEMINI_API_BASE_URL` points somewhere other than Google, i.e. at the
+/// scripted stub in [`crate::shared::gemini_stub`].
+pub const PURPOSE_HEADER: &str = "x-pm-journey-purpose";
+pub const SCENARIO_ID_HEADER: &str = "x-pm-journey-scenario-id";
 
 #[derive(Debug, Clone)]
 pub struct GeminiCredentials {
     pub api_key: String,
     pub model_id: String,
+    pub base_url: String,
 }
 
-fn first_non_empty_env(keys: &[&str]) -> Option<String> {
-    keys.iter().find_map(|key| {
-        std::env::var(key)
-            .ok()
-            .map(|value| value.trim().to_string())
-            .filter(|value| !value.is_empty())
-    })
+/// `GEMINI_API_BASE_URL` lets tests and local development point every Gemini
+/// call at a stand-in server such as `backend gemini-stub`.
+fn resolve_base_url() -> String {
+    first_non_empty_env(&["GEMINI_API_BASE_URL"])
+        .unwrap_or_else(|| DEFAULT_GEMINI_API_BASE_URL.to_string())
+        .trim_end_matches('/')
+        .to_string()
 }
 
 pub fn resolve_chat_credentials(
@@ -47,7 +62,11 @@ pub fn resolve_chat_credentials(
 
     let model_id = normalize_model_id(requested_model.unwrap_or(default_model.as_str()));
 
-    Ok(GeminiCredentials { api_key, model_id })
+    Ok(GeminiCredentials {
+        api_key,
+        model_id,
+        base_url: resolve_base_url(),
+    })
 }
 
 pub fn resolve_eval_credentials(
@@ -78,5 +97,260 @@ pub fn resolve_eval_credentials(
 
     let model_id = normalize_model_id(requested_model.unwrap_or(default_model.as_str()));
 
-    Ok(GeminiCredentials { api_key, model_id })
+    Ok(GeminiCredentials {
+        api_key,
+        model_id,
+        base_url: resolve_base_url(),
+    })
+}
+
+pub struct GeminiProvider {
+    credentials: GeminiCredentials,
+    client: Client,
+}
+
+impl GeminiProvider {
+    pub fn new(credentials: GeminiCredentials) -> Self {
+        Self {
+            credentials,
+            client: Client::new(),
+        }
+    }
+
+    fn endpoint(&self, method: &str) -> String {
+        format!(
+            "{}/models/{}:{method}?key={}",
+            self.credentials.base_url, self.credentials.model_id, self.credentials.api_key
+        )
+    }
+
+    pub(crate) fn payload(request: &LlmRequest) -> serde_json::Value {
+        let contents: Vec<_> = request
+            .messages
+            .iter()
+            .map(|m| {
+                let role = match m.role {
+                    LlmRole::Assistant => "model",
+                    LlmRole::User => "user",
+                };
+                json!({
+                    "role": role,
+                    "parts": [{ "text": m.text }]
+                })
+            })
+            .collect();
+
+        let mut generation_config = json!({
+            "temperature": request.temperature,
+            "maxOutputTokens": request.max_output_tokens,
+        });
+        if let Some(top_p) = request.top_p {
+            generation_config["topP"] = json!(top_p);
+        }
+        if let Some(top_k) = request.top_k {
+            generation_config["topK"] = json!(top_k);
+        }
+        if request.json_template.is_some() {
+            generation_config["responseMimeType"] = json!("application/json");
+        }
+
+        json!({
+            "contents": contents,
+            "systemInstruction": { "parts": [{ "text": request.system_instruction }] },
+            "generationConfig": generation_config
+        })
+    }
+
+    async fn send(&self, url: String, request: &LlmRequest) -> Result<reqwest::Response, AppError> {
+        let mut builder = self
+            .client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .json(&Self::payload(request));
+        if self.credentials.base_url != DEFAULT_GEMINI_API_BASE_URL {
+            builder = builder.header(PURPOSE_HEADER, request.purpose.as_str());
+            if let Some(scenario_id) = &request.scenario_id {
+                builder = builder.header(SCENARIO_ID_HEADER, scenario_id);
+            }
+        }
+
+        let res = builder
+            .send()
+            .await
+            .map_err(|e| anyhow_error(format!("Gemini request failed: {e}")))?;
+
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await.unwrap_or_default();
+            return Err(anyhow_error(format!("Gemini API error {status}: {text}")));
+        }
+
+        Ok(res)
+    }
+}
+
+/// Concatenated text and finish reason of the first candidate in a
+/// `generateContent` response or `streamGenerateContent` chunk.
+fn parse_candidate(data: &serde_json::Value) -> (String, Option<String>) {
+    let candidate = data.get("candidates").and_then(|v| v.get(0));
+    let text = candidate
+        .and_then(|v| v.get("content"))
+        .and_then(|v| v.get("parts"))
+        .and_then(|v| v.as_array())
+        .map(|parts| {
+            parts
+                .iter()
+                .filter_map(|p| p.get("text").and_then(|t| t.as_str()))
+                .collect::<Vec<_>>()
+                .join("")
+        })
+        .unwrap_or_default();
+    let finish_reason = candidate
+        .and_then(|v| v.get("finishReason"))
+        .and_then(|v| v.as_str())
+        .map(|v| v.to_string());
+    (text, finish_reason)
+}
+
+#[async_trait]
+impl LlmProvider for GeminiProvider {
+    fn provider_name(&self) -> &'static str {
+        "gemini"
+    }
+
+    fn model_id(&self) -> &str {
+        &self.credentials.model_id
+    }
+
+    async fn generate(&self, request: &LlmRequest) -> Result<LlmResponse, AppError> {
+        let res = self.send(self.endpoint("generateContent"), request).await?;
+        let data: serde_json::Value = res
+            .json()
+            .await
+            .map_err(|e| anyhow_error(format!("Failed to parse Gemini response: {e}")))?;
+
+        let (text, finish_reason) = parse_candidate(&data);
+        if text.is_empty() {
+            let candidate_count = data
+                .get("candidates")
+                .and_then(|v| v.as_array())
+                .map(|c| c.len())
+                .unwrap_or(0);
+            tracing::warn!(
+                model_id = %self.credentials.model_id,
+                finish_reason = ?finish_reason,
+                candidate_count,
+                "Gemini returned empty content"
+            );
+        }
+
+        Ok(LlmResponse {
+            text,
+            finish_reason,
+        })
+    }
+
+    async fn generate_stream(
+        &self,
+        request: &LlmRequest,
+        deltas: &mpsc::Sender<String>,
+    ) -> Result<LlmResponse, AppError> {
+        let url = format!("{}&alt=sse", self.endpoint("streamGenerateContent"));
+        let res = self.send(url, request).await?;
+        let mut reader = SseDataReader::new(res);
+        let mut text = String::new();
+        let mut finish_reason = None;
+
+        while let Some(data) = reader.next_data().await? {
+            let Ok(chunk) = serde_json::from_str::<serde_json::Value>(&data) else {
+                continue;
+            };
+            let (delta, reason) = parse_candidate(&chunk);
+            if reason.is_some() {
+                finish_reason = reason;
+            }
+            if delta.is_empty() {
+                continue;
+            }
+            text.push_str(&delta);
+            // A closed receiver means the client went away; keep reading so the
+            // caller still gets the full reply.
+            let _ = deltas.send(delta).await;
+        }
+
+        Ok(LlmResponse {
+            text,
+            finish_reason,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::llm::{LlmMessage, LlmPurpose};
+
+    fn make_request(json_template: Option<serde_json::Value>) -> LlmRequest {
+        LlmRequest {
+            purpose: LlmPurpose::AgentReply,
+            scenario_id: None,
+            system_instruction: "system".to_string(),
+            messages: vec![
+                LlmMessage::user("hello"),
+                LlmMessage {
+                    role: LlmRole::Assistant,
+                    text: "hi".to_string(),
+                },
+            ],
+            temperature: 0.7,
+            max_output_tokens: 1024,
+            top_p: Some(0.95),
+            top_k: Some(40),
+            json_template,
+        }
+    }
+
+    #[test]
+    fn parse_candidate_joins_parts_and_reads_finish_reason() {
+        let data: serde_json::Value = serde_json::from_str(
+            r#"{"candidates":[{"content":{"parts":[{"text":"こん"},{"text":"にちは"}],"role":"model"},"finishReason":"STOP"}]}"#,
+        )
+        .unwrap();
+        let (text, finish_reason) = parse_candidate(&data);
+
+        assert_eq!(text, "こんにちは");
+        assert_eq!(finish_reason.as_deref(), Some("STOP"));
+    }
+
+    #[test]
+    fn parse_candidate_without_candidates_is_empty() {
+        let (text, finish_reason) = parse_candidate(&json!({ "promptFeedback": {} }));
+
+        assert!(text.is_empty());
+        assert!(finish_reason.is_none());
+    }
+
+    #[test]
+    fn payload_maps_roles_and_generation_config() {
+        let payload = GeminiProvider::payload(&make_request(None));
+
+        assert_eq!(payload["contents"][0]["role"], "user");
+        assert_eq!(payload["contents"][1]["role"], "model");
+        assert_eq!(payload["systemInstruction"]["parts"][0]["text"], "system");
+        assert_eq!(payload["generationConfig"]["maxOutputTokens"], 1024);
+        assert_eq!(payload["generationConfig"]["topK"], 40);
+        assert!(payload["generationConfig"]
+            .get("responseMimeType")
+            .is_none());
+    }
+
+    #[test]
+    fn payload_requests_json_mime_type_when_template_is_set() {
+        let payload = GeminiProvider::payload(&make_request(Some(json!({}))));
+
+        assert_eq!(
+            payload["generationConfig"]["responseMimeType"],
+            "application/json"
+        );
+    }
 }
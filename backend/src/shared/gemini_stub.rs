@@ -0,0 +1,411 @@
+//! Scripted stand-in for the Gemini `generateContent` API.
+//!
+//! Point `GEMINI_API_BASE_URL` at a running stub and every agent reply,
+//! mission check and evaluation is answered from fixtures instead of Google.
+//! Integration tests spawn it in-process with [`GeminiStub::spawn`] on an
+//! ephemeral port; for local development run `backend gemini-stub [fixtures.json]`.
+//!
+//! A fixture matches when every key it sets (`purpose`, `scenarioId`,
+//! `promptHash`) equals the request's; the first match in file order wins.
+//! `purpose` and `scenarioId` come from the headers [`GeminiProvider`] adds for
+//! non-Google base URLs. `promptHash` is [`prompt_hash_of_payload`] of the
+//! system instruction and contents, and is logged for every unmatched request so
+//! fixtures can be pinned to an exact prompt.
+//!
+//! [`GeminiProvider`]: crate::shared::gemini::GeminiProvider
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use tokio::net::TcpListener;
+
+use crate::shared::gemini::{PURPOSE_HEADER, SCENARIO_ID_HEADER};
+
+const UNMATCHED_TEXT_REPLY: &str = "（スタブ応答）";
+const STREAM_CHUNK_CHARS: usize = 8;
+
+/// One scripted answer. Exactly one of `text`, `json` or `raw_body` is
+/// expected; with none set the stub answers with an empty candidate.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StubFixture {
+    pub purpose: Option<String>,
+    pub scenario_id: Option<String>,
+    pub prompt_hash: Option<String>,
+    /// Candidate text of a well-formed response.
+    pub text: Option<String>,
+    /// Serialized into the candidate text of a well-formed response.
+    pub json: Option<serde_json::Value>,
+    /// Returned verbatim as the HTTP body, e.g. to simulate a malformed envelope.
+    pub raw_body: Option<String>,
+    /// HTTP status to answer with. Defaults to 200.
+    pub status: Option<u16>,
+    pub finish_reason: Option<String>,
+    /// How many requests this fixture may answer before it stops matching.
+    /// Unlimited when unset.
+    pub times: Option<u32>,
+}
+
+impl StubFixture {
+    fn matches(&self, request: &StubRequest) -> bool {
+        fn key_matches(expected: &Option<String>, actual: &Option<String>) -> bool {
+            expected.is_none() || expected == actual
+        }
+
+        self.times != Some(0)
+            && key_matches(&self.purpose, &request.purpose)
+            && key_matches(&self.scenario_id, &request.scenario_id)
+            && key_matches(&self.prompt_hash, &Some(request.prompt_hash.clone()))
+    }
+
+    fn candidate_text(&self) -> String {
+        match (&self.text, &self.json) {
+            (Some(text), _) => text.clone(),
+            (None, Some(value)) => value.to_string(),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+/// A request the stub received, in arrival order.
+#[derive(Debug, Clone)]
+pub struct StubRequest {
+    pub method: String,
+    pub model_id: String,
+    pub purpose: Option<String>,
+    pub scenario_id: Option<String>,
+    pub prompt_hash: String,
+    pub body: serde_json::Value,
+}
+
+#[derive(Default)]
+struct StubState {
+    fixtures: Vec<StubFixture>,
+    requests: Vec<StubRequest>,
+}
+
+type SharedState = Arc<Mutex<StubState>>;
+
+/// Hex SHA-256 of the system instruction and contents of a Gemini payload.
+/// Generation settings are left out so tuning them does not break fixtures.
+pub fn prompt_hash_of_payload(payload: &serde_json::Value) -> String {
+    let prompt = json!({
+        "systemInstruction": payload.get("systemInstruction"),
+        "contents": payload.get("contents"),
+    });
+    hex::encode(Sha256::digest(prompt.to_string().as_bytes()))
+}
+
+pub fn parse_fixtures(raw: &str) -> Result<Vec<StubFixture>, String> {
+    serde_json::from_str(raw).map_err(|e| format!("invalid Gemini stub fixtures: {e}"))
+}
+
+fn router(state: SharedState) -> Router {
+    Router::new()
+        .route("/models/:call", post(handle_call))
+        .with_state(state)
+}
+
+/// In-process stub serving until dropped.
+pub struct GeminiStub {
+    addr: SocketAddr,
+    state: SharedState,
+    server: tokio::task::JoinHandle<()>,
+}
+
+impl GeminiStub {
+    /// Serve `fixtures` on `addr`; pass `127.0.0.1:0` for an ephemeral port.
+    pub async fn spawn(addr: &str, fixtures: Vec<StubFixture>) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        let state: SharedState = Arc::new(Mutex::new(StubState {
+            fixtures,
+            requests: Vec::new(),
+        }));
+        let app = router(state.clone());
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        Ok(Self {
+            addr,
+            state,
+            server,
+        })
+    }
+
+    /// Value for `GEMINI_API_BASE_URL`.
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub fn requests(&self) -> Vec<StubRequest> {
+        self.state
+            .lock()
+            .expect("gemini stub state")
+            .requests
+            .clone()
+    }
+}
+
+impl Drop for GeminiStub {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+fn header_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string())
+}
+
+async fn handle_call(
+    State(state): State<SharedState>,
+    Path(call): Path<String>,
+    headers: HeaderMap,
+    Json(body): Json<serde_json::Value>,
+) -> Response {
+    let (model_id, method) = call.split_once(':').unwrap_or((call.as_str(), ""));
+    let request = StubRequest {
+        method: method.to_string(),
+        model_id: model_id.to_string(),
+        purpose: header_value(&headers, PURPOSE_HEADER),
+        scenario_id: header_value(&headers, SCENARIO_ID_HEADER),
+        prompt_hash: prompt_hash_of_payload(&body),
+        body,
+    };
+    let streaming = request.method == "streamGenerateContent";
+
+    let fixture = {
+        let mut state = state.lock().expect("gemini stub state");
+        let fixture = state
+            .fixtures
+            .iter_mut()
+            .find(|f| f.matches(&request))
+            .map(|f| {
+                if let Some(times) = f.times.as_mut() {
+                    *times -= 1;
+                }
+                f.clone()
+            });
+        if fixture.is_none() {
+            tracing::info!(
+                model_id = %request.model_id,
+                purpose = ?request.purpose,
+                scenario_id = ?request.scenario_id,
+                prompt_hash = %request.prompt_hash,
+                "Gemini stub: no fixture matched"
+            );
+        }
+        state.requests.push(request.clone());
+        fixture
+    };
+
+    let fixture = fixture.unwrap_or_else(|| unmatched_fixture(&request));
+    let status = fixture
+        .status
+        .and_then(|code| StatusCode::from_u16(code).ok())
+        .unwrap_or(StatusCode::OK);
+
+    if !status.is_success() {
+        let body = fixture.raw_body.clone().unwrap_or_else(|| {
+            json!({
+                "error": {
+                    "code": status.as_u16(),
+                    "message": "scripted error from Gemini stub",
+                    "status": status.canonical_reason().unwrap_or("ERROR"),
+                }
+            })
+            .to_string()
+        });
+        return (status, [(header::CONTENT_TYPE, "application/json")], body).into_response();
+    }
+
+    if let Some(raw_body) = &fixture.raw_body {
+        let content_type = if streaming {
+            "text/event-stream"
+        } else {
+            "application/json"
+        };
+        return (
+            status,
+            [(header::CONTENT_TYPE, content_type)],
+            raw_body.clone(),
+        )
+            .into_response();
+    }
+
+    let text = fixture.candidate_text();
+    let finish_reason = fixture.finish_reason.as_deref().unwrap_or("STOP");
+    if streaming {
+        let body = stream_body(&text, finish_reason);
+        return (status, [(header::CONTENT_TYPE, "text/event-stream")], body).into_response();
+    }
+    (status, Json(candidate_chunk(&text, Some(finish_reason)))).into_response()
+}
+
+/// Without a fixture, JSON-mode requests get `{}` and text requests a fixed
+/// reply, so unscripted calls still succeed deterministically.
+fn unmatched_fixture(request: &StubRequest) -> StubFixture {
+    let json_mode = request
+        .body
+        .pointer("/generationConfig/responseMimeType")
+        .and_then(|v| v.as_str())
+        == Some("application/json");
+    StubFixture {
+        text: Some(if json_mode {
+            "{}".to_string()
+        } else {
+            UNMATCHED_TEXT_REPLY.to_string()
+        }),
+        ..StubFixture::default()
+    }
+}
+
+fn candidate_chunk(text: &str, finish_reason: Option<&str>) -> serde_json::Value {
+    let mut candidate = json!({
+        "content": { "role": "model", "parts": [{ "text": text }] },
+        "index": 0
+    });
+    if let Some(finish_reason) = finish_reason {
+        candidate["finishReason"] = json!(finish_reason);
+    }
+    json!({ "candidates": [candidate] })
+}
+
+fn stream_body(text: &str, finish_reason: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let chunks: Vec<String> = chars
+        .chunks(STREAM_CHUNK_CHARS)
+        .map(|chunk| chunk.iter().collect())
+        .collect();
+    let last = chunks.len().saturating_sub(1);
+
+    let mut body = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let reason = (i == last).then_some(finish_reason);
+        body.push_str(&format!("data: {}\r\n\r\n", candidate_chunk(chunk, reason)));
+    }
+    if chunks.is_empty() {
+        body.push_str(&format!(
+            "data: {}\r\n\r\n",
+            candidate_chunk("", Some(finish_reason))
+        ));
+    }
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::gemini::GeminiProvider;
+    use crate::shared::llm::{LlmMessage, LlmPurpose, LlmRequest};
+
+    /// The `promptHash` the stub will see for `request`.
+    fn prompt_hash(request: &LlmRequest) -> String {
+        prompt_hash_of_payload(&GeminiProvider::payload(request))
+    }
+
+    fn make_request(text: &str) -> LlmRequest {
+        LlmRequest {
+            purpose: LlmPurpose::AgentReply,
+            scenario_id: Some("basic-intro-alignment".to_string()),
+            system_instruction: "system".to_string(),
+            messages: vec![LlmMessage::user(text)],
+            temperature: 0.7,
+            max_output_tokens: 1024,
+            top_p: None,
+            top_k: None,
+            json_template: None,
+        }
+    }
+
+    fn stub_request(purpose: &str, scenario_id: &str, prompt_hash: &str) -> StubRequest {
+        StubRequest {
+            method: "generateContent".to_string(),
+            model_id: "gemini-test".to_string(),
+            purpose: Some(purpose.to_string()),
+            scenario_id: Some(scenario_id.to_string()),
+            prompt_hash: prompt_hash.to_string(),
+            body: json!({}),
+        }
+    }
+
+    #[test]
+    fn prompt_hash_ignores_generation_settings() {
+        let mut tuned = make_request("こんにちは");
+        tuned.temperature = 0.1;
+        tuned.max_output_tokens = 32;
+
+        assert_eq!(
+            prompt_hash(&make_request("こんにちは")),
+            prompt_hash(&tuned)
+        );
+        assert_ne!(
+            prompt_hash(&make_request("こんにちは")),
+            prompt_hash(&make_request("こんばんは"))
+        );
+    }
+
+    #[test]
+    fn fixture_matches_only_on_keys_it_sets() {
+        let fixture = StubFixture {
+            purpose: Some("evaluation".to_string()),
+            ..StubFixture::default()
+        };
+
+        assert!(fixture.matches(&stub_request("evaluation", "a", "h1")));
+        assert!(fixture.matches(&stub_request("evaluation", "b", "h2")));
+        assert!(!fixture.matches(&stub_request("agent_reply", "a", "h1")));
+    }
+
+    #[test]
+    fn fixture_with_no_remaining_uses_does_not_match() {
+        let fixture = StubFixture {
+            times: Some(0),
+            ..StubFixture::default()
+        };
+
+        assert!(!fixture.matches(&stub_request("agent_reply", "a", "h1")));
+    }
+
+    #[test]
+    fn stream_body_marks_only_the_last_chunk_finished() {
+        let body = stream_body("0123456789abcdef!", "STOP");
+        let chunks: Vec<&str> = body.split("\r\n\r\n").filter(|c| !c.is_empty()).collect();
+
+        assert_eq!(chunks.len(), 3);
+        assert!(!chunks[0].contains("finishReason"));
+        assert!(chunks[2].contains("\"finishReason\":\"STOP\""));
+    }
+
+    #[test]
+    fn parse_fixtures_reads_camel_case_keys() {
+        let fixtures = parse_fixtures(
+            r#"[{"purpose":"mission_check","scenarioId":"basic-intro-alignment","json":{"completedMissionIds":[]}},{"status":503}]"#,
+        )
+        .expect("fixtures");
+
+        assert_eq!(
+            fixtures[0].scenario_id.as_deref(),
+            Some("basic-intro-alignment")
+        );
+        assert_eq!(
+            fixtures[0].candidate_text(),
+            r#"{"completedMissionIds":[]}"#
+        );
+        assert_eq!(fixtures[1].status, Some(503));
+    }
+}
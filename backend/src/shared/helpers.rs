@@ -9,6 +9,16 @@ pub fn now_ts() -> String {
     Utc::now().to_rfc3339()
 }
 
+/// Return the first environment variable in `keys` that is set to a non-blank value.
+pub fn first_non_empty_env(keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|key| {
+        std::env::var(key)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    })
+}
+
 pub fn normalize_model_id(model_id: &str) -> String {
     model_id
         .strip_prefix("models/")
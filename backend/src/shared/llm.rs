@@ -0,0 +1,369 @@
+use std::collections::VecDeque;
+
+use axum::async_trait;
+use tokio::sync::mpsc;
+
+use crate::error::{anyhow_error, AppError};
+use crate::features::entitlements::models::PlanCode;
+use crate::shared::gemini::{resolve_chat_credentials, resolve_eval_credentials, GeminiProvider};
+use crate::shared::helpers::first_non_empty_env;
+use crate::shared::openai::{
+    resolve_openai_chat_config, resolve_openai_eval_config, OpenAiCompatibleProvider,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LlmRole {
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone)]
+pub struct LlmMessage {
+    pub role: LlmRole,
+    pub text: String,
+}
+
+impl LlmMessage {
+    pub fn user(text: impl Into<String>) -> Self {
+        Self {
+            role: LlmRole::User,
+            text: text.into(),
+        }
+    }
+}
+
+/// What a request is for. Only used for logging and for matching fixtures in
+/// the Gemini stub; it never changes the prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LlmPurpose {
+    #[default]
+    AgentReply,
+    MissionCheck,
+    Evaluation,
+}
+
+impl LlmPurpose {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LlmPurpose::AgentReply => "agent_reply",
+            LlmPurpose::MissionCheck => "mission_check",
+            LlmPurpose::Evaluation => "evaluation",
+        }
+    }
+}
+
+/// Provider-neutral generation request.
+#[derive(Debug, Clone)]
+pub struct LlmRequest {
+    pub purpose: LlmPurpose,
+    pub scenario_id: Option<String>,
+    pub system_instruction: String,
+    pub messages: Vec<LlmMessage>,
+    pub temperature: f64,
+    pub max_output_tokens: u32,
+    pub top_p: Option<f64>,
+    pub top_k: Option<u32>,
+    /// When set, the model is asked to answer with a single JSON object shaped
+    /// like this template. The mock provider answers with the template itself.
+    pub json_template: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LlmResponse {
+    pub text: String,
+    pub finish_reason: Option<String>,
+}
+
+impl LlmResponse {
+    /// Whether the provider reported that generation stopped at the token limit.
+    pub fn is_truncated(&self) -> bool {
+        matches!(
+            self.finish_reason.as_deref(),
+            Some("MAX_TOKENS") | Some("length")
+        )
+    }
+}
+
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    fn provider_name(&self) -> &'static str;
+
+    fn model_id(&self) -> &str;
+
+    async fn generate(&self, request: &LlmRequest) -> Result<LlmResponse, AppError>;
+
+    /// Generate while forwarding text deltas to `deltas` as they arrive.
+    /// Providers without native streaming send the whole reply as one delta.
+    async fn generate_stream(
+        &self,
+        request: &LlmRequest,
+        deltas: &mpsc::Sender<String>,
+    ) -> Result<LlmResponse, AppError> {
+        let response = self.generate(request).await?;
+        if !response.text.is_empty() {
+            let _ = deltas.send(response.text.clone()).await;
+        }
+        Ok(response)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LlmProviderKind {
+    Gemini,
+    OpenAiCompatible,
+    Mock,
+}
+
+fn parse_llm_provider(raw: &str) -> Option<LlmProviderKind> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "gemini" => Some(LlmProviderKind::Gemini),
+        "openai" | "openai-compatible" => Some(LlmProviderKind::OpenAiCompatible),
+        "mock" => Some(LlmProviderKind::Mock),
+        _ => None,
+    }
+}
+
+/// Resolve the provider for a plan from `LLM_PROVIDER_TEAM` /
+/// `LLM_PROVIDER_FREE_TIER`, falling back to `LLM_PROVIDER` and then Gemini.
+pub fn resolve_llm_provider_kind(plan_code: &PlanCode) -> Result<LlmProviderKind, AppError> {
+    let raw = match plan_code {
+        PlanCode::Team => first_non_empty_env(&["LLM_PROVIDER_TEAM", "LLM_PROVIDER"]),
+        PlanCode::Free => first_non_empty_env(&["LLM_PROVIDER_FREE_TIER", "LLM_PROVIDER"]),
+    };
+    let Some(raw) = raw else {
+        return Ok(LlmProviderKind::Gemini);
+    };
+    parse_llm_provider(&raw).ok_or_else(|| {
+        tracing::error!("Unsupported LLM provider configured: `{raw}`");
+        anyhow_error(format!(
+            "LLM_PROVIDER_INVALID: unsupported LLM provider `{raw}`"
+        ))
+    })
+}
+
+pub fn resolve_chat_provider(plan_code: &PlanCode) -> Result<Box<dyn LlmProvider>, AppError> {
+    Ok(match resolve_llm_provider_kind(plan_code)? {
+        LlmProviderKind::Gemini => Box::new(GeminiProvider::new(resolve_chat_credentials(
+            plan_code, None,
+        )?)),
+        LlmProviderKind::OpenAiCompatible => Box::new(OpenAiCompatibleProvider::new(
+            resolve_openai_chat_config(plan_code)?,
+        )),
+        LlmProviderKind::Mock => Box::new(MockLlmProvider::new()),
+    })
+}
+
+pub fn resolve_eval_provider(plan_code: &PlanCode) -> Result<Box<dyn LlmProvider>, AppError> {
+    Ok(match resolve_llm_provider_kind(plan_code)? {
+        LlmProviderKind::Gemini => Box::new(GeminiProvider::new(resolve_eval_credentials(
+            plan_code, None,
+        )?)),
+        LlmProviderKind::OpenAiCompatible => Box::new(OpenAiCompatibleProvider::new(
+            resolve_openai_eval_config(plan_code)?,
+        )),
+        LlmProviderKind::Mock => Box::new(MockLlmProvider::new()),
+    })
+}
+
+const MOCK_MODEL_ID: &str = "mock-llm";
+const MOCK_STREAM_CHUNK_CHARS: usize = 8;
+
+/// Deterministic offline provider. JSON requests get their template back
+/// unchanged; text requests get an echo of the latest user message.
+#[derive(Default)]
+pub struct MockLlmProvider;
+
+impl MockLlmProvider {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn reply_text(request: &LlmRequest) -> String {
+        if let Some(template) = &request.json_template {
+            return template.to_string();
+        }
+        let latest_user = request
+            .messages
+            .iter()
+            .rev()
+            .find(|m| m.role == LlmRole::User)
+            .map(|m| m.text.trim())
+            .unwrap_or("");
+        let excerpt: String = latest_user.chars().take(80).collect();
+        format!("（モック応答）{excerpt}")
+    }
+}
+
+#[async_trait]
+impl LlmProvider for MockLlmProvider {
+    fn provider_name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn model_id(&self) -> &str {
+        MOCK_MODEL_ID
+    }
+
+    async fn generate(&self, request: &LlmRequest) -> Result<LlmResponse, AppError> {
+        Ok(LlmResponse {
+            text: Self::reply_text(request),
+            finish_reason: Some("STOP".to_string()),
+        })
+    }
+
+    async fn generate_stream(
+        &self,
+        request: &LlmRequest,
+        deltas: &mpsc::Sender<String>,
+    ) -> Result<LlmResponse, AppError> {
+        let response = self.generate(request).await?;
+        let chars: Vec<char> = response.text.chars().collect();
+        for chunk in chars.chunks(MOCK_STREAM_CHUNK_CHARS) {
+            let _ = deltas.send(chunk.iter().collect()).await;
+        }
+        Ok(response)
+    }
+}
+
+/// Reads the `data:` payloads of a Server-Sent Events response body.
+/// Lines are only decoded once complete so multi-byte characters split across
+/// chunks are never cut in half.
+pub(crate) struct SseDataReader {
+    response: reqwest::Response,
+    buffer: Vec<u8>,
+    pending: VecDeque<String>,
+}
+
+impl SseDataReader {
+    pub fn new(response: reqwest::Response) -> Self {
+        Self {
+            response,
+            buffer: Vec::new(),
+            pending: VecDeque::new(),
+        }
+    }
+
+    pub async fn next_data(&mut self) -> Result<Option<String>, AppError> {
+        loop {
+            if let Some(data) = self.pending.pop_front() {
+                return Ok(Some(data));
+            }
+
+            let chunk = self
+                .response
+                .chunk()
+                .await
+                .map_err(|e| anyhow_error(format!("LLM stream read failed: {e}")))?;
+            let Some(chunk) = chunk else {
+                let rest = std::mem::take(&mut self.buffer);
+                return Ok(sse_data(&String::from_utf8_lossy(&rest)));
+            };
+
+            self.buffer.extend_from_slice(&chunk);
+            while let Some(pos) = self.buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+                if let Some(data) = sse_data(&String::from_utf8_lossy(&line)) {
+                    self.pending.push_back(data);
+                }
+            }
+        }
+    }
+}
+
+fn sse_data(line: &str) -> Option<String> {
+    line.trim_end_matches(['\r', '\n'])
+        .strip_prefix("data:")
+        .map(|data| data.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn make_request(json_template: Option<serde_json::Value>) -> LlmRequest {
+        LlmRequest {
+            purpose: LlmPurpose::AgentReply,
+            scenario_id: None,
+            system_instruction: "system".to_string(),
+            messages: vec![
+                LlmMessage::user("最初の発言"),
+                LlmMessage::user("最新の発言"),
+            ],
+            temperature: 0.0,
+            max_output_tokens: 64,
+            top_p: None,
+            top_k: None,
+            json_template,
+        }
+    }
+
+    #[test]
+    fn parse_llm_provider_accepts_known_values() {
+        assert_eq!(parse_llm_provider("gemini"), Some(LlmProviderKind::Gemini));
+        assert_eq!(
+            parse_llm_provider(" OpenAI "),
+            Some(LlmProviderKind::OpenAiCompatible)
+        );
+        assert_eq!(
+            parse_llm_provider("openai-compatible"),
+            Some(LlmProviderKind::OpenAiCompatible)
+        );
+        assert_eq!(parse_llm_provider("mock"), Some(LlmProviderKind::Mock));
+        assert_eq!(parse_llm_provider("claude"), None);
+    }
+
+    #[test]
+    fn sse_data_strips_prefix_and_line_endings() {
+        assert_eq!(
+            sse_data("data: {\"a\":1}\r\n").as_deref(),
+            Some("{\"a\":1}")
+        );
+        assert_eq!(sse_data("data:[DONE]\n").as_deref(), Some("[DONE]"));
+        assert!(sse_data("event: message\n").is_none());
+        assert!(sse_data("\n").is_none());
+    }
+
+    #[tokio::test]
+    async fn mock_provider_returns_json_template_for_json_requests() {
+        let template = json!({ "completedMissionIds": [] });
+        let response = MockLlmProvider::new()
+            .generate(&make_request(Some(template.clone())))
+            .await
+            .expect("mock generate");
+
+        let parsed: serde_json::Value =
+            serde_json::from_str(&response.text).expect("mock output should be JSON");
+        assert_eq!(parsed, template);
+    }
+
+    #[tokio::test]
+    async fn mock_provider_echoes_latest_user_message_for_text_requests() {
+        let response = MockLlmProvider::new()
+            .generate(&make_request(None))
+            .await
+            .expect("mock generate");
+
+        assert!(response.text.contains("最新の発言"));
+        assert!(!response.text.contains("最初の発言"));
+    }
+
+    #[tokio::test]
+    async fn mock_provider_stream_deltas_concatenate_to_full_reply() {
+        let (tx, mut rx) = mpsc::channel(64);
+        let response = MockLlmProvider::new()
+            .generate_stream(&make_request(None), &tx)
+            .await
+            .expect("mock stream");
+        drop(tx);
+
+        let mut streamed = String::new();
+        let mut chunks = 0;
+        while let Some(delta) = rx.recv().await {
+            streamed.push_str(&delta);
+            chunks += 1;
+        }
+        assert_eq!(streamed, response.text);
+        assert!(chunks > 1, "mock should stream in several chunks");
+    }
+}
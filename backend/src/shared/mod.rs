@@ -1,3 +1,8 @@
 pub mod admin_override;
+pub mod email;
+pub mod email_stub;
 pub mod gemini;
+pub mod gemini_stub;
 pub mod helpers;
+pub mod llm;
+pub mod openai;
@@ -0,0 +1,284 @@
+use axum::async_trait;
+use reqwest::Client;
+use serde_json::json;
+use tokio::sync::mpsc;
+
+use crate::error::{anyhow_error, client_error, AppError};
+use crate::features::entitlements::models::PlanCode;
+use crate::shared::helpers::first_non_empty_env;
+use crate::shared::llm::{LlmProvider, LlmRequest, LlmResponse, LlmRole, SseDataReader};
+
+const DEFAULT_OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// Connection settings for an OpenAI-compatible `/chat/completions` endpoint
+/// (OpenAI itself, vLLM, Ollama, LiteLLM, ...).
+#[derive(Debug, Clone)]
+pub struct OpenAiConfig {
+    /// Optional because many self-hosted servers do not check it.
+    pub api_key: Option<String>,
+    pub base_url: String,
+    pub model_id: String,
+}
+
+fn resolve_openai_config(
+    plan_code: &PlanCode,
+    team_model_keys: &[&str],
+    free_model_keys: &[&str],
+) -> Result<OpenAiConfig, AppError> {
+    let (api_key, base_url, model_id) = match plan_code {
+        PlanCode::Team => (
+            first_non_empty_env(&["OPENAI_API_KEY_TEAM", "OPENAI_API_KEY"]),
+            first_non_empty_env(&["OPENAI_BASE_URL_TEAM", "OPENAI_BASE_URL"]),
+            first_non_empty_env(team_model_keys),
+        ),
+        PlanCode::Free => (
+            first_non_empty_env(&["OPENAI_API_KEY_FREE_TIER", "OPENAI_API_KEY"]),
+            first_non_empty_env(&["OPENAI_BASE_URL_FREE_TIER", "OPENAI_BASE_URL"]),
+            first_non_empty_env(free_model_keys),
+        ),
+    };
+
+    let model_id = model_id.ok_or_else(|| {
+        client_error(
+            "LLM_CONFIG_MISSING: OPENAI_DEFAULT_MODEL must be set when LLM_PROVIDER=openai",
+        )
+    })?;
+
+    Ok(OpenAiConfig {
+        api_key,
+        base_url: base_url
+            .unwrap_or_else(|| DEFAULT_OPENAI_BASE_URL.to_string())
+            .trim_end_matches('/')
+            .to_string(),
+        model_id,
+    })
+}
+
+pub fn resolve_openai_chat_config(plan_code: &PlanCode) -> Result<OpenAiConfig, AppError> {
+    resolve_openai_config(
+        plan_code,
+        &["OPENAI_DEFAULT_MODEL_TEAM", "OPENAI_DEFAULT_MODEL"],
+        &["OPENAI_DEFAULT_MODEL_FREE_TIER", "OPENAI_DEFAULT_MODEL"],
+    )
+}
+
+pub fn resolve_openai_eval_config(plan_code: &PlanCode) -> Result<OpenAiConfig, AppError> {
+    resolve_openai_config(
+        plan_code,
+        &[
+            "OPENAI_EVAL_MODEL_TEAM",
+            "OPENAI_EVAL_MODEL",
+            "OPENAI_DEFAULT_MODEL_TEAM",
+            "OPENAI_DEFAULT_MODEL",
+        ],
+        &[
+            "OPENAI_EVAL_MODEL_FREE_TIER",
+            "OPENAI_EVAL_MODEL",
+            "OPENAI_DEFAULT_MODEL_FREE_TIER",
+            "OPENAI_DEFAULT_MODEL",
+        ],
+    )
+}
+
+pub struct OpenAiCompatibleProvider {
+    config: OpenAiConfig,
+    client: Client,
+}
+
+impl OpenAiCompatibleProvider {
+    pub fn new(config: OpenAiConfig) -> Self {
+        Self {
+            config,
+            client: Client::new(),
+        }
+    }
+
+    fn payload(&self, request: &LlmRequest, stream: bool) -> serde_json::Value {
+        let mut messages = vec![json!({
+            "role": "system",
+            "content": request.system_instruction
+        })];
+        messages.extend(request.messages.iter().map(|m| {
+            let role = match m.role {
+                LlmRole::Assistant => "assistant",
+                LlmRole::User => "user",
+            };
+            json!({ "role": role, "content": m.text })
+        }));
+
+        let mut payload = json!({
+            "model": self.config.model_id,
+            "messages": messages,
+            "temperature": request.temperature,
+            "max_tokens": request.max_output_tokens,
+            "stream": stream
+        });
+        if let Some(top_p) = request.top_p {
+            payload["top_p"] = json!(top_p);
+        }
+        if request.json_template.is_some() {
+            payload["response_format"] = json!({ "type": "json_object" });
+        }
+        payload
+    }
+
+    async fn send(&self, payload: &serde_json::Value) -> Result<reqwest::Response, AppError> {
+        let mut builder = self
+            .client
+            .post(format!("{}/chat/completions", self.config.base_url))
+            .header("Content-Type", "application/json")
+            .json(payload);
+        if let Some(api_key) = &self.config.api_key {
+            builder = builder.bearer_auth(api_key);
+        }
+
+        let res = builder
+            .send()
+            .await
+            .map_err(|e| anyhow_error(format!("OpenAI-compatible request failed: {e}")))?;
+
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await.unwrap_or_default();
+            return Err(anyhow_error(format!(
+                "OpenAI-compatible API error {status}: {text}"
+            )));
+        }
+
+        Ok(res)
+    }
+}
+
+/// Text and finish reason of the first choice. Works for both full
+/// responses (`message.content`) and stream chunks (`delta.content`).
+fn parse_choice(data: &serde_json::Value) -> (String, Option<String>) {
+    let choice = data.get("choices").and_then(|v| v.get(0));
+    let text = choice
+        .and_then(|c| c.get("message").or_else(|| c.get("delta")))
+        .and_then(|m| m.get("content"))
+        .and_then(|t| t.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let finish_reason = choice
+        .and_then(|c| c.get("finish_reason"))
+        .and_then(|v| v.as_str())
+        .map(|v| v.to_string());
+    (text, finish_reason)
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiCompatibleProvider {
+    fn provider_name(&self) -> &'static str {
+        "openai"
+    }
+
+    fn model_id(&self) -> &str {
+        &self.config.model_id
+    }
+
+    async fn generate(&self, request: &LlmRequest) -> Result<LlmResponse, AppError> {
+        let res = self.send(&self.payload(request, false)).await?;
+        let data: serde_json::Value = res.json().await.map_err(|e| {
+            anyhow_error(format!("Failed to parse OpenAI-compatible response: {e}"))
+        })?;
+
+        let (text, finish_reason) = parse_choice(&data);
+        Ok(LlmResponse {
+            text,
+            finish_reason,
+        })
+    }
+
+    async fn generate_stream(
+        &self,
+        request: &LlmRequest,
+        deltas: &mpsc::Sender<String>,
+    ) -> Result<LlmResponse, AppError> {
+        let res = self.send(&self.payload(request, true)).await?;
+        let mut reader = SseDataReader::new(res);
+        let mut text = String::new();
+        let mut finish_reason = None;
+
+        while let Some(data) = reader.next_data().await? {
+            if data == "[DONE]" {
+                break;
+            }
+            let Ok(chunk) = serde_json::from_str::<serde_json::Value>(&data) else {
+                continue;
+            };
+            let (delta, reason) = parse_choice(&chunk);
+            if reason.is_some() {
+                finish_reason = reason;
+            }
+            if delta.is_empty() {
+                continue;
+            }
+            text.push_str(&delta);
+            let _ = deltas.send(delta).await;
+        }
+
+        Ok(LlmResponse {
+            text,
+            finish_reason,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::llm::{LlmMessage, LlmPurpose};
+
+    fn make_provider() -> OpenAiCompatibleProvider {
+        OpenAiCompatibleProvider::new(OpenAiConfig {
+            api_key: None,
+            base_url: "http://localhost:11434/v1".to_string(),
+            model_id: "llama3".to_string(),
+        })
+    }
+
+    #[test]
+    fn payload_prepends_system_message_and_maps_roles() {
+        let request = LlmRequest {
+            purpose: LlmPurpose::AgentReply,
+            scenario_id: None,
+            system_instruction: "system".to_string(),
+            messages: vec![
+                LlmMessage::user("hello"),
+                LlmMessage {
+                    role: LlmRole::Assistant,
+                    text: "hi".to_string(),
+                },
+            ],
+            temperature: 0.0,
+            max_output_tokens: 256,
+            top_p: None,
+            top_k: Some(40),
+            json_template: Some(json!({})),
+        };
+        let payload = make_provider().payload(&request, true);
+
+        assert_eq!(payload["model"], "llama3");
+        assert_eq!(payload["messages"][0]["role"], "system");
+        assert_eq!(payload["messages"][1]["role"], "user");
+        assert_eq!(payload["messages"][2]["role"], "assistant");
+        assert_eq!(payload["stream"], true);
+        assert_eq!(payload["response_format"]["type"], "json_object");
+        assert!(
+            payload.get("top_k").is_none(),
+            "top_k is not part of the OpenAI API"
+        );
+    }
+
+    #[test]
+    fn parse_choice_reads_message_and_delta_shapes() {
+        let full = json!({"choices":[{"message":{"content":"done"},"finish_reason":"stop"}]});
+        let chunk = json!({"choices":[{"delta":{"content":"par"},"finish_reason":null}]});
+
+        assert_eq!(
+            parse_choice(&full),
+            ("done".to_string(), Some("stop".to_string()))
+        );
+        assert_eq!(parse_choice(&chunk), ("par".to_string(), None));
+    }
+}
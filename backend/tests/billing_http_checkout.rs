@@ -5,6 +5,8 @@ use axum::{
     body::{to_bytes, Body},
     http::{Method, Request, StatusCode},
 };
+use backend::features::billing::services::BillingService;
+use backend::features::entitlements::services::EntitlementService;
 use backend::{api, middleware::auth::JwksKeys, state::state_with_pool};
 use chrono::{Duration, Utc};
 use hmac::{Hmac, Mac};
@@ -951,7 +953,818 @@ async fn team_webhook_seat_changes_enforce_invites_and_reactivation() {
     }
 }
 
+#[tokio::test]
+async fn credit_checkout_credits_purchased_wallet_once() {
+    let _env_guard = env_lock()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let pool = match test_pool().await {
+        Ok(Some(pool)) => pool,
+        Ok(None) => {
+            eprintln!("Skipping credit checkout test: DATABASE_URL is not configured");
+            return;
+        }
+        Err(error) => {
+            eprintln!("Skipping credit checkout test: database unavailable ({error})");
+            return;
+        }
+    };
+    configure_auth_env();
+
+    let buyer_user = user_id("credit-buyer");
+    let owner_user = user_id("credit-org-owner");
+    let org_id = id("credit-org");
+    insert_user(&pool, &buyer_user).await;
+    insert_user(&pool, &owner_user).await;
+    insert_organization(&pool, &org_id, &owner_user).await;
+    insert_org_member(&pool, &org_id, &buyer_user, "member").await;
+    let buyer_token = jwt_for_user(&buyer_user);
+    let app = test_app(pool.clone());
+
+    unsafe {
+        env::set_var("FF_BILLING_ENABLED", "true");
+        env::set_var("BILLING_PROVIDER", "mock");
+        env::set_var(
+            "BILLING_MOCK_CHECKOUT_BASE_URL",
+            "https://mock-billing.example/checkout",
+        );
+    }
+
+    let invalid_pack_response = app
+        .clone()
+        .oneshot(build_request(
+            Method::POST,
+            "/billing/checkout/credits",
+            &buyer_token,
+            Some(json!({ "packQuantity": 0 })),
+        ))
+        .await
+        .expect("invalid pack request");
+    assert_eq!(
+        invalid_pack_response.status(),
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+
+    let org_forbidden_response = app
+        .clone()
+        .oneshot(build_request(
+            Method::POST,
+            "/billing/checkout/credits",
+            &buyer_token,
+            Some(json!({ "packQuantity": 1, "organizationId": org_id.clone() })),
+        ))
+        .await
+        .expect("org credit checkout forbidden");
+    assert_eq!(org_forbidden_response.status(), StatusCode::FORBIDDEN);
+
+    let mock_response = app
+        .clone()
+        .oneshot(build_request(
+            Method::POST,
+            "/billing/checkout/credits",
+            &buyer_token,
+            Some(json!({ "packQuantity": 2 })),
+        ))
+        .await
+        .expect("mock credit checkout");
+    assert_eq!(mock_response.status(), StatusCode::OK);
+    let mock_body = to_bytes(mock_response.into_body(), usize::MAX)
+        .await
+        .expect("mock credit checkout body");
+    let mock_json: serde_json::Value =
+        serde_json::from_slice(&mock_body).expect("mock credit checkout json");
+    assert_eq!(mock_json.get("mode").and_then(|v| v.as_str()), Some("mock"));
+    assert_eq!(
+        mock_json.get("credits").and_then(|v| v.as_i64()),
+        Some(1000)
+    );
+    assert!(mock_json
+        .get("checkoutUrl")
+        .and_then(|v| v.as_str())
+        .is_some_and(|url| url.contains("purchase=credits") && url.contains("packQuantity=2")));
+    assert_eq!(purchased_credits(&pool, "user", &buyer_user).await, 0);
+
+    // Mock purchases are only credited once the completion event is imported.
+    let mock_session = serde_json::to_vec(&json!({
+        "id": id("evt-mock-credit-checkout"),
+        "type": "checkout.session.completed",
+        "data": {
+            "object": {
+                "id": format!("cs_{}", Uuid::new_v4().simple()),
+                "mode": "payment",
+                "payment_status": "paid",
+                "metadata": {
+                    "purchase_type": "credits",
+                    "scope_type": "user",
+                    "scope_id": buyer_user.clone(),
+                    "pack_quantity": "2",
+                    "credits": "1000",
+                    "user_id": buyer_user.clone()
+                }
+            }
+        }
+    }))
+    .expect("serialize mock credit event");
+    BillingService::new(pool.clone())
+        .import_stripe_event(&mock_session)
+        .await
+        .expect("import mock credit event");
+    assert_eq!(purchased_credits(&pool, "user", &buyer_user).await, 1000);
+
+    let webhook_secret = format!("whsec_{}", Uuid::new_v4().simple());
+    unsafe {
+        env::set_var("BILLING_PROVIDER", "stripe");
+        env::set_var("STRIPE_WEBHOOK_SECRET", &webhook_secret);
+    }
+
+    let session_id = format!("cs_{}", Uuid::new_v4().simple());
+    let credit_session = |event_type: &str, payment_status: &str| {
+        json!({
+            "id": id("evt-credit-checkout"),
+            "type": event_type,
+            "data": {
+                "object": {
+                    "id": session_id.clone(),
+                    "mode": "payment",
+                    "payment_status": payment_status,
+                    "metadata": {
+                        "purchase_type": "credits",
+                        "scope_type": "organization",
+                        "scope_id": org_id.clone(),
+                        "pack_quantity": "1",
+                        "credits": "500",
+                        "user_id": owner_user.clone()
+                    }
+                }
+            }
+        })
+    };
+
+    let unpaid_response = post_signed_stripe_webhook(
+        &app,
+        &webhook_secret,
+        credit_session("checkout.session.completed", "unpaid"),
+    )
+    .await;
+    assert_eq!(unpaid_response.status(), StatusCode::OK);
+    assert_eq!(purchased_credits(&pool, "organization", &org_id).await, 0);
+
+    for event_type in [
+        "checkout.session.async_payment_succeeded",
+        "checkout.session.completed",
+    ] {
+        let paid_response =
+            post_signed_stripe_webhook(&app, &webhook_secret, credit_session(event_type, "paid"))
+                .await;
+        assert_eq!(paid_response.status(), StatusCode::OK);
+    }
+    assert_eq!(purchased_credits(&pool, "organization", &org_id).await, 500);
+
+    let purchase_entries = sqlx::query(
+        r#"
+        SELECT l.amount, l.reference_type, l.reference_id
+        FROM credit_ledger l
+        JOIN credit_wallets w ON w.id = l.wallet_id
+        WHERE w.scope_type = 'organization' AND w.scope_id = $1 AND l.reason = 'purchase'
+        "#,
+    )
+    .bind(&org_id)
+    .fetch_all(&pool)
+    .await
+    .expect("purchase ledger entries");
+    assert_eq!(purchase_entries.len(), 1);
+    assert_eq!(purchase_entries[0].get::<i32, _>("amount"), 500);
+    assert_eq!(
+        purchase_entries[0].get::<Option<String>, _>("reference_id"),
+        Some(session_id.clone())
+    );
+
+    unsafe {
+        env::remove_var("FF_BILLING_ENABLED");
+        env::remove_var("BILLING_PROVIDER");
+        env::remove_var("BILLING_MOCK_CHECKOUT_BASE_URL");
+        env::remove_var("STRIPE_WEBHOOK_SECRET");
+    }
+}
+
+#[tokio::test]
+async fn team_seat_update_endpoint_behaviors() {
+    let _env_guard = env_lock()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let pool = match test_pool().await {
+        Ok(Some(pool)) => pool,
+        Ok(None) => {
+            eprintln!("Skipping team seat update test: DATABASE_URL is not configured");
+            return;
+        }
+        Err(error) => {
+            eprintln!("Skipping team seat update test: database unavailable ({error})");
+            return;
+        }
+    };
+    configure_auth_env();
+
+    let owner_user = user_id("billing-seats-owner");
+    let member_user = user_id("billing-seats-member");
+    let org_id = id("billing-seats-org");
+    insert_user(&pool, &owner_user).await;
+    insert_user(&pool, &member_user).await;
+    insert_organization(&pool, &org_id, &owner_user).await;
+    insert_org_member(&pool, &org_id, &member_user, "member").await;
+    let owner_token = jwt_for_user(&owner_user);
+    let member_token = jwt_for_user(&member_user);
+    let app = test_app(pool.clone());
+
+    unsafe {
+        env::set_var("FF_BILLING_ENABLED", "true");
+        env::set_var("BILLING_PROVIDER", "mock");
+    }
+
+    let seats_request = |token: &str, seat_quantity: i32| {
+        build_request(
+            Method::POST,
+            "/billing/seats",
+            token,
+            Some(json!({
+                "organizationId": org_id.clone(),
+                "seatQuantity": seat_quantity
+            })),
+        )
+    };
+    let invite_request = |label: &str| {
+        build_request(
+            Method::POST,
+            "/organizations/current/invitations",
+            &owner_token,
+            Some(json!({
+                "email": format!("{}@example.com", id(label)),
+                "role": "member"
+            })),
+        )
+    };
+
+    let no_subscription_response = app
+        .clone()
+        .oneshot(seats_request(&owner_token, 3))
+        .await
+        .expect("seat update without subscription");
+    assert_eq!(
+        no_subscription_response.status(),
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+    assert!(error_text(no_subscription_response)
+        .await
+        .contains("TEAM_SUBSCRIPTION_REQUIRED"));
+
+    insert_active_team_subscription(&pool, &org_id, 2).await;
+
+    let blocked_invite_response = app
+        .clone()
+        .oneshot(invite_request("invite-seats-blocked"))
+        .await
+        .expect("blocked invite request");
+    assert_eq!(
+        blocked_invite_response.status(),
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+    let blocked_invite_error = error_text(blocked_invite_response).await;
+    assert!(blocked_invite_error.contains("SEAT_LIMIT_REACHED"));
+    assert!(blocked_invite_error.contains("POST /billing/seats"));
+
+    let member_response = app
+        .clone()
+        .oneshot(seats_request(&member_token, 3))
+        .await
+        .expect("member seat update");
+    assert_eq!(member_response.status(), StatusCode::FORBIDDEN);
+
+    set_org_member_role(&pool, &org_id, &member_user, "manager").await;
+    let manager_response = app
+        .clone()
+        .oneshot(seats_request(&member_token, 3))
+        .await
+        .expect("manager seat update");
+    assert_eq!(manager_response.status(), StatusCode::FORBIDDEN);
+    set_org_member_role(&pool, &org_id, &member_user, "member").await;
+
+    let invalid_response = app
+        .clone()
+        .oneshot(seats_request(&owner_token, 11))
+        .await
+        .expect("invalid seat update");
+    assert_eq!(invalid_response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(error_text(invalid_response)
+        .await
+        .contains("SEAT_QUANTITY_INVALID"));
+
+    let below_usage_response = app
+        .clone()
+        .oneshot(seats_request(&owner_token, 1))
+        .await
+        .expect("below usage seat update");
+    assert_eq!(
+        below_usage_response.status(),
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+    assert!(error_text(below_usage_response)
+        .await
+        .contains("SEAT_QUANTITY_BELOW_USAGE"));
+
+    let added_response = app
+        .clone()
+        .oneshot(seats_request(&owner_token, 3))
+        .await
+        .expect("add seat");
+    assert_eq!(added_response.status(), StatusCode::OK);
+    let added_body = to_bytes(added_response.into_body(), usize::MAX)
+        .await
+        .expect("add seat body");
+    let added_json: serde_json::Value = serde_json::from_slice(&added_body).expect("add seat json");
+    assert_eq!(added_json.get("mode"), Some(&json!("mock")));
+    assert_eq!(added_json.get("previousSeatQuantity"), Some(&json!(2)));
+    assert_eq!(added_json.get("seatQuantity"), Some(&json!(3)));
+
+    let invite_response = app
+        .clone()
+        .oneshot(invite_request("invite-seats-allowed"))
+        .await
+        .expect("invite after adding seat");
+    assert_eq!(invite_response.status(), StatusCode::CREATED);
+
+    // The pending invitation holds the new seat, so it cannot be removed again.
+    let remove_response = app
+        .clone()
+        .oneshot(seats_request(&owner_token, 2))
+        .await
+        .expect("remove held seat");
+    assert_eq!(remove_response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    unsafe {
+        env::remove_var("FF_BILLING_ENABLED");
+        env::remove_var("BILLING_PROVIDER");
+    }
+}
+
 #[allow(unused_unsafe)]
+#[tokio::test]
+async fn team_webhook_grace_period_cancellation_and_lapse_downgrade() {
+    let _env_guard = env_lock()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let pool = match test_pool().await {
+        Ok(Some(pool)) => pool,
+        Ok(None) => {
+            eprintln!("Skipping subscription lifecycle test: DATABASE_URL is not configured");
+            return;
+        }
+        Err(error) => {
+            eprintln!("Skipping subscription lifecycle test: database unavailable ({error})");
+            return;
+        }
+    };
+    configure_auth_env();
+
+    let owner_user = user_id("lifecycle-owner");
+    let member_user = user_id("lifecycle-member");
+    let org_id = id("lifecycle-org");
+    insert_user(&pool, &owner_user).await;
+    insert_user(&pool, &member_user).await;
+    insert_organization(&pool, &org_id, &owner_user).await;
+    insert_org_member(&pool, &org_id, &member_user, "member").await;
+
+    let customer_id = format!("cus_{}", Uuid::new_v4().simple());
+    let provider_subscription_id = format!("sub_{}", Uuid::new_v4().simple());
+    let webhook_secret = format!("whsec_{}", Uuid::new_v4().simple());
+    let app = test_app(pool.clone());
+
+    unsafe {
+        env::set_var("FF_BILLING_ENABLED", "true");
+        env::set_var("BILLING_PROVIDER", "stripe");
+        env::set_var("STRIPE_WEBHOOK_SECRET", &webhook_secret);
+        env::set_var("BILLING_PAST_DUE_GRACE_DAYS", "3");
+        env::remove_var("RESEND_API_KEY");
+    }
+
+    let checkout_event = json!({
+        "id": id("evt-lifecycle-checkout"),
+        "type": "checkout.session.completed",
+        "data": {
+            "object": {
+                "mode": "subscription",
+                "subscription": provider_subscription_id.clone(),
+                "customer": customer_id.clone(),
+                "metadata": {
+                    "plan_code": "TEAM",
+                    "organization_id": org_id.clone(),
+                    "seat_quantity": "3",
+                    "user_id": owner_user.clone()
+                }
+            }
+        }
+    });
+    let response = post_signed_stripe_webhook(&app, &webhook_secret, checkout_event).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let subscription_event = |status: &str, cancel_at_period_end: bool, period_end: i64| {
+        json!({
+            "id": id("evt-lifecycle-sub"),
+            "type": "customer.subscription.updated",
+            "data": {
+                "object": {
+                    "id": provider_subscription_id.clone(),
+                    "customer": customer_id.clone(),
+                    "status": status,
+                    "current_period_start": Utc::now().timestamp(),
+                    "current_period_end": period_end,
+                    "cancel_at_period_end": cancel_at_period_end,
+                    "metadata": {
+                        "plan_code": "TEAM",
+                        "organization_id": org_id.clone(),
+                        "seat_quantity": "3"
+                    }
+                }
+            }
+        })
+    };
+    let period_end = (Utc::now() + Duration::days(10)).timestamp();
+
+    // past_due keeps Team for the grace window.
+    let response = post_signed_stripe_webhook(
+        &app,
+        &webhook_secret,
+        subscription_event("past_due", false, period_end),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (status, valid_until) = org_team_entitlement(&pool, &org_id).await;
+    assert_eq!(status, "active");
+    let grace_end = valid_until.expect("grace window end");
+    assert!(grace_end > Utc::now() + Duration::days(2));
+    assert!(grace_end <= Utc::now() + Duration::days(3));
+    assert_eq!(effective_plan(&pool, &member_user).await, "TEAM");
+
+    // Recovered payment with a scheduled cancellation ends access at period end.
+    let response = post_signed_stripe_webhook(
+        &app,
+        &webhook_secret,
+        subscription_event("active", true, period_end),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (status, valid_until) = org_team_entitlement(&pool, &org_id).await;
+    assert_eq!(status, "active");
+    assert_eq!(valid_until.map(|until| until.timestamp()), Some(period_end));
+    let past_due_since: Option<chrono::DateTime<Utc>> = sqlx::query_scalar(
+        "SELECT past_due_since FROM subscriptions WHERE provider_subscription_id = $1",
+    )
+    .bind(&provider_subscription_id)
+    .fetch_one(&pool)
+    .await
+    .expect("past_due_since");
+    assert!(past_due_since.is_none());
+
+    // The period ends without a webhook; the sweep expires Team and notifies owners.
+    sqlx::query(
+        r#"
+        UPDATE entitlements
+        SET valid_until = NOW() - INTERVAL '1 minute'
+        WHERE scope_type = 'organization' AND scope_id = $1 AND status = 'active'
+        "#,
+    )
+    .bind(&org_id)
+    .execute(&pool)
+    .await
+    .expect("reach period end");
+    backend::features::billing::lifecycle::run_lapse_sweep(&pool)
+        .await
+        .expect("first sweep");
+    backend::features::billing::lifecycle::run_lapse_sweep(&pool)
+        .await
+        .expect("second sweep");
+
+    let (status, _) = org_team_entitlement(&pool, &org_id).await;
+    assert_eq!(status, "expired");
+    assert_eq!(effective_plan(&pool, &member_user).await, "FREE");
+    let member_entitlements: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*)::BIGINT FROM entitlements WHERE scope_type = 'user' AND scope_id = $1",
+    )
+    .bind(&member_user)
+    .fetch_one(&pool)
+    .await
+    .expect("count member entitlements");
+    assert_eq!(
+        member_entitlements, 0,
+        "downgrade must not pin members to a personal plan"
+    );
+
+    let notifications: Vec<(String, String)> = sqlx::query_as(
+        r#"
+        SELECT user_id, delivery_status
+        FROM billing_notifications
+        WHERE organization_id = $1 AND kind = 'team_plan_lapsed'
+        "#,
+    )
+    .bind(&org_id)
+    .fetch_all(&pool)
+    .await
+    .expect("lapse notifications");
+    assert_eq!(
+        notifications,
+        vec![(owner_user.clone(), "skipped".to_string())]
+    );
+
+    unsafe {
+        env::remove_var("FF_BILLING_ENABLED");
+        env::remove_var("BILLING_PROVIDER");
+        env::remove_var("STRIPE_WEBHOOK_SECRET");
+        env::remove_var("BILLING_PAST_DUE_GRACE_DAYS");
+    }
+}
+
+#[tokio::test]
+async fn invoices_endpoint_lists_mock_invoices_by_scope() {
+    let _env_guard = env_lock()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let pool = match test_pool().await {
+        Ok(Some(pool)) => pool,
+        Ok(None) => {
+            eprintln!("Skipping invoices test: DATABASE_URL is not configured");
+            return;
+        }
+        Err(error) => {
+            eprintln!("Skipping invoices test: database unavailable ({error})");
+            return;
+        }
+    };
+    configure_auth_env();
+
+    let owner_user = user_id("billing-invoices-owner");
+    let member_user = user_id("billing-invoices-member");
+    let org_id = id("billing-invoices-org");
+    insert_user(&pool, &owner_user).await;
+    insert_user(&pool, &member_user).await;
+    insert_organization(&pool, &org_id, &owner_user).await;
+    insert_org_member(&pool, &org_id, &member_user, "member").await;
+    insert_active_team_subscription(&pool, &org_id, 3).await;
+    let owner_token = jwt_for_user(&owner_user);
+    let member_token = jwt_for_user(&member_user);
+    let app = test_app(pool.clone());
+    let org_invoices_path = format!("/billing/invoices?organizationId={org_id}");
+
+    unsafe {
+        env::remove_var("FF_BILLING_ENABLED");
+        env::set_var("BILLING_PROVIDER", "mock");
+        env::set_var("BILLING_MOCK_TEAM_SEAT_AMOUNT_JPY", "3000");
+    }
+    let disabled_response = app
+        .clone()
+        .oneshot(build_request(
+            Method::GET,
+            &org_invoices_path,
+            &owner_token,
+            None,
+        ))
+        .await
+        .expect("disabled invoices request");
+    assert_eq!(disabled_response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    unsafe {
+        env::set_var("FF_BILLING_ENABLED", "true");
+    }
+    let personal_response = app
+        .clone()
+        .oneshot(build_request(
+            Method::GET,
+            "/billing/invoices",
+            &owner_token,
+            None,
+        ))
+        .await
+        .expect("personal invoices request");
+    assert_eq!(personal_response.status(), StatusCode::OK);
+    let personal_body = to_bytes(personal_response.into_body(), usize::MAX)
+        .await
+        .expect("personal invoices body");
+    let personal_json: serde_json::Value =
+        serde_json::from_slice(&personal_body).expect("personal invoices json");
+    assert_eq!(personal_json.get("mode"), Some(&json!("mock")));
+    assert_eq!(personal_json.get("invoices"), Some(&json!([])));
+
+    let org_response = app
+        .clone()
+        .oneshot(build_request(
+            Method::GET,
+            &org_invoices_path,
+            &owner_token,
+            None,
+        ))
+        .await
+        .expect("organization invoices request");
+    assert_eq!(org_response.status(), StatusCode::OK);
+    let org_body = to_bytes(org_response.into_body(), usize::MAX)
+        .await
+        .expect("organization invoices body");
+    let org_json: serde_json::Value =
+        serde_json::from_slice(&org_body).expect("organization invoices json");
+    let invoices = org_json
+        .get("invoices")
+        .and_then(|v| v.as_array())
+        .expect("invoices array");
+    assert_eq!(invoices.len(), 1);
+    let invoice = &invoices[0];
+    assert_eq!(invoice.get("status"), Some(&json!("paid")));
+    assert_eq!(invoice.get("currency"), Some(&json!("jpy")));
+    assert_eq!(invoice.get("amountDue"), Some(&json!(9000)));
+    assert_eq!(invoice.get("amountPaid"), Some(&json!(9000)));
+    assert!(invoice
+        .get("periodStart")
+        .and_then(|v| v.as_str())
+        .is_some());
+    assert!(invoice.get("periodEnd").and_then(|v| v.as_str()).is_some());
+    assert!(invoice
+        .get("invoicePdfUrl")
+        .and_then(|v| v.as_str())
+        .is_some_and(|url| url.ends_with(".pdf")));
+
+    let member_response = app
+        .clone()
+        .oneshot(build_request(
+            Method::GET,
+            &org_invoices_path,
+            &member_token,
+            None,
+        ))
+        .await
+        .expect("member invoices request");
+    assert_eq!(member_response.status(), StatusCode::FORBIDDEN);
+
+    unsafe {
+        env::remove_var("FF_BILLING_ENABLED");
+        env::remove_var("BILLING_PROVIDER");
+        env::remove_var("BILLING_MOCK_TEAM_SEAT_AMOUNT_JPY");
+    }
+}
+
+#[tokio::test]
+async fn failed_stripe_events_replay_once_and_import_under_mock() {
+    let _env_guard = env_lock()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let pool = match test_pool().await {
+        Ok(Some(pool)) => pool,
+        Ok(None) => {
+            eprintln!("Skipping stripe event replay test: DATABASE_URL is not configured");
+            return;
+        }
+        Err(error) => {
+            eprintln!("Skipping stripe event replay test: database unavailable ({error})");
+            return;
+        }
+    };
+    configure_auth_env();
+
+    let owner_user = user_id("billing-replay-owner");
+    let org_id = id("billing-replay-org");
+    insert_user(&pool, &owner_user).await;
+    let app = test_app(pool.clone());
+    let billing = BillingService::new(pool.clone());
+    let webhook_secret = format!("whsec_{}", Uuid::new_v4().simple());
+    let event_id = id("evt-replay");
+
+    unsafe {
+        env::set_var("FF_BILLING_ENABLED", "true");
+        env::set_var("BILLING_PROVIDER", "stripe");
+        env::set_var("STRIPE_WEBHOOK_SECRET", &webhook_secret);
+    }
+
+    // The organization does not exist yet, so processing fails on its foreign key.
+    let checkout_event = json!({
+        "id": event_id.clone(),
+        "type": "checkout.session.completed",
+        "data": {
+            "object": {
+                "mode": "subscription",
+                "subscription": format!("sub_{}", Uuid::new_v4().simple()),
+                "customer": format!("cus_{}", Uuid::new_v4().simple()),
+                "metadata": {
+                    "plan_code": "TEAM",
+                    "organization_id": org_id.clone(),
+                    "seat_quantity": "3",
+                    "user_id": owner_user.clone()
+                }
+            }
+        }
+    });
+    let failed_response = post_signed_stripe_webhook(&app, &webhook_secret, checkout_event).await;
+    assert_eq!(failed_response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    let failed_events = billing
+        .list_unprocessed_stripe_events(Some("failed"), 500)
+        .await
+        .expect("list failed events");
+    let failed_event = failed_events
+        .iter()
+        .find(|event| event.event_id == event_id)
+        .expect("failed event is listed");
+    assert_eq!(failed_event.source, "webhook");
+    assert!(failed_event.last_error.is_some());
+    assert!(billing
+        .list_unprocessed_stripe_events(Some("processed"), 10)
+        .await
+        .is_err());
+
+    insert_organization(&pool, &org_id, &owner_user).await;
+    let replayed = billing
+        .replay_stripe_events(std::slice::from_ref(&event_id))
+        .await
+        .expect("replay event");
+    assert_eq!(replayed[0].outcome, "processed");
+    assert_eq!(org_team_entitlement(&pool, &org_id).await.0, "active");
+
+    let replayed_again = billing
+        .replay_stripe_events(&[event_id.clone(), id("evt-missing")])
+        .await
+        .expect("replay processed event");
+    assert_eq!(replayed_again[0].outcome, "skipped");
+    assert_eq!(replayed_again[1].outcome, "skipped");
+
+    let event_row =
+        sqlx::query("SELECT status, replay_count FROM stripe_events WHERE event_id = $1")
+            .bind(&event_id)
+            .fetch_one(&pool)
+            .await
+            .expect("query replayed event");
+    assert_eq!(event_row.get::<String, _>("status"), "processed");
+    assert_eq!(event_row.get::<i32, _>("replay_count"), 1);
+
+    // A `received` event may still be in the webhook handler; replays only
+    // pick it up once it is old enough to be stuck.
+    let received_event_id = id("evt-received");
+    sqlx::query(
+        r#"
+        INSERT INTO stripe_events (event_id, event_type, payload, status, source)
+        VALUES ($1, 'invoice.paid', $2, 'received', 'webhook')
+        "#,
+    )
+    .bind(&received_event_id)
+    .bind(json!({
+        "id": received_event_id.clone(),
+        "type": "invoice.paid",
+        "data": { "object": {} }
+    }))
+    .execute(&pool)
+    .await
+    .expect("insert received event");
+    let fresh = billing
+        .replay_stripe_events(std::slice::from_ref(&received_event_id))
+        .await
+        .expect("replay fresh received event");
+    assert_eq!(fresh[0].outcome, "skipped");
+
+    sqlx::query(
+        "UPDATE stripe_events SET received_at = NOW() - INTERVAL '10 minutes' WHERE event_id = $1",
+    )
+    .bind(&received_event_id)
+    .execute(&pool)
+    .await
+    .expect("age received event");
+    let stuck = billing
+        .replay_stripe_events(std::slice::from_ref(&received_event_id))
+        .await
+        .expect("replay stuck received event");
+    assert_eq!(stuck[0].outcome, "processed");
+
+    let import_payload = serde_json::to_vec(&json!({
+        "id": id("evt-import"),
+        "type": "invoice.paid",
+        "data": { "object": {} }
+    }))
+    .expect("serialize import payload");
+    assert!(billing.import_stripe_event(&import_payload).await.is_err());
+
+    unsafe {
+        env::set_var("BILLING_PROVIDER", "mock");
+    }
+    let imported = billing
+        .import_stripe_event(&import_payload)
+        .await
+        .expect("import event under mock provider");
+    assert!(!imported.duplicate);
+    let duplicate = billing
+        .import_stripe_event(&import_payload)
+        .await
+        .expect("import event twice");
+    assert!(duplicate.duplicate);
+
+    unsafe {
+        env::remove_var("FF_BILLING_ENABLED");
+        env::remove_var("BILLING_PROVIDER");
+        env::remove_var("STRIPE_WEBHOOK_SECRET");
+    }
+}
+
 fn configure_auth_env() {
     unsafe {
         env::set_var("AUTH0_DOMAIN", TEST_AUTH0_DOMAIN);
@@ -1041,6 +1854,17 @@ async fn post_signed_stripe_webhook(
         .expect("webhook request should succeed")
 }
 
+async fn error_text(response: axum::response::Response) -> String {
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("error body");
+    let json: serde_json::Value = serde_json::from_slice(&body).expect("error json");
+    json.get("error")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
 async fn test_pool() -> Result<Option<PgPool>, String> {
     dotenvy::dotenv().ok();
     let database_url = match env::var("DATABASE_URL") {
@@ -1167,6 +1991,18 @@ async fn insert_org_member_with_status(
     .expect("insert organization member with status");
 }
 
+async fn set_org_member_role(pool: &PgPool, org_id: &str, user_id: &str, role: &str) {
+    sqlx::query(
+        "UPDATE organization_members SET role = $3 WHERE organization_id = $1 AND user_id = $2",
+    )
+    .bind(org_id)
+    .bind(user_id)
+    .bind(role)
+    .execute(pool)
+    .await
+    .expect("update organization member role");
+}
+
 async fn find_org_member_id(pool: &PgPool, org_id: &str, user_id: &str) -> String {
     let row = sqlx::query(
         r#"
@@ -1224,3 +2060,49 @@ async fn insert_active_team_subscription(pool: &PgPool, org_id: &str, seat_quant
     .await
     .expect("insert active team subscription");
 }
+
+async fn purchased_credits(pool: &PgPool, scope_type: &str, scope_id: &str) -> i32 {
+    sqlx::query(
+        r#"
+        SELECT purchased_credits
+        FROM credit_wallets
+        WHERE scope_type = $1 AND scope_id = $2
+        "#,
+    )
+    .bind(scope_type)
+    .bind(scope_id)
+    .fetch_optional(pool)
+    .await
+    .expect("fetch wallet")
+    .map(|row| row.get::<i32, _>("purchased_credits"))
+    .unwrap_or(0)
+}
+
+async fn org_team_entitlement(
+    pool: &PgPool,
+    org_id: &str,
+) -> (String, Option<chrono::DateTime<Utc>>) {
+    sqlx::query_as(
+        r#"
+        SELECT status, valid_until
+        FROM entitlements
+        WHERE scope_type = 'organization' AND scope_id = $1 AND plan_code = 'TEAM'
+        ORDER BY created_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(org_id)
+    .fetch_one(pool)
+    .await
+    .expect("fetch organization entitlement")
+}
+
+async fn effective_plan(pool: &PgPool, user_id: &str) -> String {
+    EntitlementService::new(pool.clone())
+        .resolve_effective_plan(user_id)
+        .await
+        .expect("resolve effective plan")
+        .plan_code
+        .as_str()
+        .to_string()
+}
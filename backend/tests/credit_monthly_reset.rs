@@ -0,0 +1,214 @@
+use std::env;
+
+use backend::features::credits::services::CreditService;
+use chrono::{DateTime, Utc};
+use sqlx::{migrate::Migrator, postgres::PgPoolOptions, PgPool, Row};
+use uuid::Uuid;
+
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+#[tokio::test]
+async fn monthly_reset_restores_plan_allowance_once() {
+    let pool = match test_pool().await {
+        Ok(Some(pool)) => pool,
+        Ok(None) => {
+            eprintln!("Skipping credit reset test: DATABASE_URL is not configured");
+            return;
+        }
+        Err(error) => {
+            eprintln!("Skipping credit reset test: database unavailable ({error})");
+            return;
+        }
+    };
+
+    let user_id = user_id("free");
+    insert_user(&pool, &user_id).await;
+    insert_entitlement(&pool, "user", &user_id, "FREE").await;
+    let wallet_id = insert_due_wallet(&pool, "user", &user_id, 3, 5).await;
+
+    let service = CreditService::new(pool.clone());
+    service.run_monthly_reset().await.expect("first reset");
+    service.run_monthly_reset().await.expect("second reset");
+
+    let (monthly, purchased, reset_at) = wallet_state(&pool, &wallet_id).await;
+    assert_eq!(monthly, 100, "Free plan allowance");
+    assert_eq!(purchased, 5, "purchased credits survive the reset");
+    assert!(reset_at > Utc::now());
+
+    let entries = reset_entries(&pool, &wallet_id).await;
+    assert_eq!(entries.len(), 1, "second run must not grant again");
+    assert_eq!(entries[0].0, 100);
+    assert_eq!(entries[0].1, "credit");
+}
+
+#[tokio::test]
+async fn concurrent_resets_grant_organization_allowance_once() {
+    let pool = match test_pool().await {
+        Ok(Some(pool)) => pool,
+        Ok(None) => {
+            eprintln!("Skipping credit reset test: DATABASE_URL is not configured");
+            return;
+        }
+        Err(error) => {
+            eprintln!("Skipping credit reset test: database unavailable ({error})");
+            return;
+        }
+    };
+
+    let owner_id = user_id("org-owner");
+    let org_id = id("org");
+    insert_user(&pool, &owner_id).await;
+    insert_organization(&pool, &org_id, &owner_id).await;
+    insert_entitlement(&pool, "organization", &org_id, "TEAM").await;
+    let wallet_id = insert_due_wallet(&pool, "organization", &org_id, 0, 0).await;
+
+    let first = CreditService::new(pool.clone());
+    let second = CreditService::new(pool.clone());
+    let (a, b) = tokio::join!(first.run_monthly_reset(), second.run_monthly_reset());
+    a.expect("first reset");
+    b.expect("second reset");
+
+    let (monthly, _, _) = wallet_state(&pool, &wallet_id).await;
+    assert_eq!(monthly, 10000, "Team plan allowance");
+    assert_eq!(reset_entries(&pool, &wallet_id).await.len(), 1);
+}
+
+async fn test_pool() -> Result<Option<PgPool>, String> {
+    dotenvy::dotenv().ok();
+    let database_url = match env::var("DATABASE_URL") {
+        Ok(value) => value,
+        Err(_) => return Ok(None),
+    };
+
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&database_url)
+        .await
+        .map_err(|error| format!("failed to connect to database: {error}"))?;
+    if let Err(error) = MIGRATOR.run(&pool).await {
+        let error_text = error.to_string();
+        if !error_text.contains("previously applied but has been modified") {
+            return Err(format!("failed to run migrations: {error_text}"));
+        }
+    }
+
+    Ok(Some(pool))
+}
+
+fn user_id(prefix: &str) -> String {
+    format!("auth0|credit-reset-it-{prefix}-{}", Uuid::new_v4())
+}
+
+fn id(prefix: &str) -> String {
+    format!("credit-reset-it-{prefix}-{}", Uuid::new_v4())
+}
+
+async fn insert_user(pool: &PgPool, user_id: &str) {
+    sqlx::query(
+        r#"
+        INSERT INTO users (id, email, name)
+        VALUES ($1, $2, $3)
+        "#,
+    )
+    .bind(user_id)
+    .bind(format!("{user_id}@example.com"))
+    .bind(format!("User {user_id}"))
+    .execute(pool)
+    .await
+    .expect("insert user");
+}
+
+async fn insert_organization(pool: &PgPool, org_id: &str, owner_user_id: &str) {
+    sqlx::query(
+        r#"
+        INSERT INTO organizations (id, name, created_by_user_id)
+        VALUES ($1, $2, $3)
+        "#,
+    )
+    .bind(org_id)
+    .bind(format!("Test Org {org_id}"))
+    .bind(owner_user_id)
+    .execute(pool)
+    .await
+    .expect("insert organization");
+}
+
+async fn insert_entitlement(pool: &PgPool, scope_type: &str, scope_id: &str, plan_code: &str) {
+    sqlx::query(
+        r#"
+        INSERT INTO entitlements (
+            id, scope_type, scope_id, plan_code, status, valid_from, valid_until, source_subscription_id
+        )
+        VALUES ($1, $2, $3, $4, 'active', NOW(), NULL, NULL)
+        "#,
+    )
+    .bind(id("entitlement"))
+    .bind(scope_type)
+    .bind(scope_id)
+    .bind(plan_code)
+    .execute(pool)
+    .await
+    .expect("insert entitlement");
+}
+
+async fn insert_due_wallet(
+    pool: &PgPool,
+    scope_type: &str,
+    scope_id: &str,
+    monthly_credits: i32,
+    purchased_credits: i32,
+) -> String {
+    let wallet_id = id("wallet");
+    sqlx::query(
+        r#"
+        INSERT INTO credit_wallets (
+            id, scope_type, scope_id, monthly_credits, purchased_credits, monthly_reset_at
+        )
+        VALUES ($1, $2, $3, $4, $5, NOW() - INTERVAL '2 days')
+        "#,
+    )
+    .bind(&wallet_id)
+    .bind(scope_type)
+    .bind(scope_id)
+    .bind(monthly_credits)
+    .bind(purchased_credits)
+    .execute(pool)
+    .await
+    .expect("insert wallet");
+
+    wallet_id
+}
+
+async fn wallet_state(pool: &PgPool, wallet_id: &str) -> (i32, i32, DateTime<Utc>) {
+    let row = sqlx::query(
+        r#"
+        SELECT monthly_credits, purchased_credits, monthly_reset_at
+        FROM credit_wallets
+        WHERE id = $1
+        "#,
+    )
+    .bind(wallet_id)
+    .fetch_one(pool)
+    .await
+    .expect("fetch wallet");
+
+    (
+        row.get("monthly_credits"),
+        row.get("purchased_credits"),
+        row.get("monthly_reset_at"),
+    )
+}
+
+async fn reset_entries(pool: &PgPool, wallet_id: &str) -> Vec<(i32, String)> {
+    sqlx::query_as(
+        r#"
+        SELECT amount, direction
+        FROM credit_ledger
+        WHERE wallet_id = $1 AND reason = 'monthly_reset'
+        "#,
+    )
+    .bind(wallet_id)
+    .fetch_all(pool)
+    .await
+    .expect("fetch ledger entries")
+}
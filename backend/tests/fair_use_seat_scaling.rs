@@ -0,0 +1,193 @@
+use std::env;
+
+use backend::features::entitlements::{fair_use::enforce_chat_daily_limit, models::PlanCode};
+use sqlx::{migrate::Migrator, postgres::PgPoolOptions, PgPool};
+use uuid::Uuid;
+
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+#[tokio::test]
+async fn team_chat_limit_scales_with_seats_and_caps_each_member() {
+    let pool = match test_pool().await {
+        Ok(Some(pool)) => pool,
+        Ok(None) => {
+            eprintln!("Skipping fair-use seat test: DATABASE_URL is not configured");
+            return;
+        }
+        Err(error) => {
+            eprintln!("Skipping fair-use seat test: database unavailable ({error})");
+            return;
+        }
+    };
+
+    unsafe {
+        env::set_var("FAIR_USE_TEAM_AGENT_REPLIES_PER_SEAT_PER_DAY", "2");
+        env::set_var("FAIR_USE_TEAM_MEMBER_AGENT_REPLIES_PER_DAY", "3");
+    }
+
+    let heavy_user = user_id("heavy");
+    let light_user = user_id("light");
+    let org_id = id("org");
+    insert_user(&pool, &heavy_user).await;
+    insert_user(&pool, &light_user).await;
+    insert_organization(&pool, &org_id, &heavy_user).await;
+    let subscription_id = insert_team_subscription(&pool, &org_id, 2).await;
+
+    // Pool is 2 seats x 2 replies = 4; the heavy user alone reaches the member cap of 3.
+    insert_agent_replies(&pool, &heavy_user, &org_id, 3).await;
+    let member_error = enforce_chat_daily_limit(&pool, &PlanCode::Team, &heavy_user, Some(&org_id))
+        .await
+        .expect_err("member cap reached")
+        .to_string();
+    assert!(
+        member_error.contains("reached for member"),
+        "unexpected error: {member_error}"
+    );
+    assert!(member_error.contains("Limit: 3"));
+    assert!(member_error.contains("Resets at: "));
+    enforce_chat_daily_limit(&pool, &PlanCode::Team, &light_user, Some(&org_id))
+        .await
+        .expect("other members keep their share");
+
+    insert_agent_replies(&pool, &light_user, &org_id, 1).await;
+    let org_error = enforce_chat_daily_limit(&pool, &PlanCode::Team, &light_user, Some(&org_id))
+        .await
+        .expect_err("organization pool exhausted")
+        .to_string();
+    assert!(
+        org_error.contains("reached for organization"),
+        "unexpected error: {org_error}"
+    );
+    assert!(org_error.contains("Used: 4, Limit: 4"));
+
+    // A third seat raises the pool to 6.
+    sqlx::query("UPDATE subscriptions SET seat_quantity = 3 WHERE id = $1")
+        .bind(&subscription_id)
+        .execute(&pool)
+        .await
+        .expect("add seat");
+    enforce_chat_daily_limit(&pool, &PlanCode::Team, &light_user, Some(&org_id))
+        .await
+        .expect("larger pool after adding a seat");
+
+    unsafe {
+        env::remove_var("FAIR_USE_TEAM_AGENT_REPLIES_PER_SEAT_PER_DAY");
+        env::remove_var("FAIR_USE_TEAM_MEMBER_AGENT_REPLIES_PER_DAY");
+    }
+}
+
+async fn test_pool() -> Result<Option<PgPool>, String> {
+    dotenvy::dotenv().ok();
+    let database_url = match env::var("DATABASE_URL") {
+        Ok(value) => value,
+        Err(_) => return Ok(None),
+    };
+
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&database_url)
+        .await
+        .map_err(|error| format!("failed to connect to database: {error}"))?;
+    if let Err(error) = MIGRATOR.run(&pool).await {
+        let error_text = error.to_string();
+        if !error_text.contains("previously applied but has been modified") {
+            return Err(format!("failed to run migrations: {error_text}"));
+        }
+    }
+
+    Ok(Some(pool))
+}
+
+fn user_id(prefix: &str) -> String {
+    format!("auth0|fair-use-it-{prefix}-{}", Uuid::new_v4())
+}
+
+fn id(prefix: &str) -> String {
+    format!("fair-use-it-{prefix}-{}", Uuid::new_v4())
+}
+
+async fn insert_user(pool: &PgPool, user_id: &str) {
+    sqlx::query(
+        r#"
+        INSERT INTO users (id, email, name)
+        VALUES ($1, $2, $3)
+        "#,
+    )
+    .bind(user_id)
+    .bind(format!("{user_id}@example.com"))
+    .bind(format!("User {user_id}"))
+    .execute(pool)
+    .await
+    .expect("insert user");
+}
+
+async fn insert_organization(pool: &PgPool, org_id: &str, owner_user_id: &str) {
+    sqlx::query(
+        r#"
+        INSERT INTO organizations (id, name, created_by_user_id)
+        VALUES ($1, $2, $3)
+        "#,
+    )
+    .bind(org_id)
+    .bind(format!("Test Org {org_id}"))
+    .bind(owner_user_id)
+    .execute(pool)
+    .await
+    .expect("insert organization");
+}
+
+async fn insert_team_subscription(pool: &PgPool, org_id: &str, seat_quantity: i32) -> String {
+    let subscription_id = id("subscription");
+    sqlx::query(
+        r#"
+        INSERT INTO subscriptions (
+            id, organization_id, user_id, provider, provider_subscription_id, status,
+            plan_code, seat_quantity, current_period_start, current_period_end,
+            cancel_at_period_end
+        )
+        VALUES ($1, $2, NULL, 'stripe', $3, 'active', 'TEAM', $4, NOW(), NOW() + INTERVAL '30 days', FALSE)
+        "#,
+    )
+    .bind(&subscription_id)
+    .bind(org_id)
+    .bind(format!("sub_{}", Uuid::new_v4().simple()))
+    .bind(seat_quantity)
+    .execute(pool)
+    .await
+    .expect("insert team subscription");
+
+    subscription_id
+}
+
+async fn insert_agent_replies(pool: &PgPool, user_id: &str, org_id: &str, count: usize) {
+    let session_id = id("session");
+    sqlx::query(
+        r#"
+        INSERT INTO sessions (
+            id, scenario_id, scenario_discipline, status, started_at, last_activity_at,
+            user_id, organization_id
+        )
+        VALUES ($1, 'scenario_placeholder', 'BASIC', 'active', NOW(), NOW(), $2, $3)
+        "#,
+    )
+    .bind(&session_id)
+    .bind(user_id)
+    .bind(org_id)
+    .execute(pool)
+    .await
+    .expect("insert session");
+
+    for _ in 0..count {
+        sqlx::query(
+            r#"
+            INSERT INTO messages (id, session_id, role, content, created_at)
+            VALUES ($1, $2, 'agent', 'reply', NOW())
+            "#,
+        )
+        .bind(id("message"))
+        .bind(&session_id)
+        .execute(pool)
+        .await
+        .expect("insert agent reply");
+    }
+}
@@ -1,14 +1,23 @@
+use std::collections::HashMap;
 use std::env;
+use std::sync::{Arc, Mutex};
 
 use axum::{
     body::{to_bytes, Body},
+    extract::{Path, Query, State},
     http::{Method, Request, StatusCode},
+    routing::{get, post},
+    Form, Json, Router,
 };
+use backend::features::entitlements::{models::PlanCode, services::EntitlementService};
+use backend::features::organizations::services::OrganizationService;
+use backend::shared::email_stub::{EmailStub, StubEmail};
 use backend::{api, middleware::auth::JwksKeys, state::state_with_pool};
 use chrono::{Duration, Utc};
 use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header};
 use serde::Serialize;
 use serde_json::json;
+use sha2::{Digest, Sha256};
 use sqlx::{migrate::Migrator, postgres::PgPoolOptions, PgPool};
 use tower::util::ServiceExt;
 use uuid::Uuid;
@@ -62,6 +71,8 @@ twIDAQAB
 struct TestClaims {
     sub: String,
     email: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    email_verified: Option<bool>,
     name: String,
     picture: String,
     iss: String,
@@ -214,6 +225,9 @@ async fn organization_endpoints_create_invite_accept_update_and_delete_member()
     )
     .await;
 
+    insert_evaluation(&pool, &session_progress_completed_id, 1, 80.0, true).await;
+    insert_evaluation(&pool, &session_progress_completed_id, 2, 40.0, false).await;
+
     let progress_response = app
         .clone()
         .oneshot(build_request(
@@ -241,6 +255,12 @@ async fn organization_endpoints_create_invite_accept_update_and_delete_member()
         .expect("invitee progress row");
     assert_eq!(invitee_progress.get("totalSessions"), Some(&json!(2)));
     assert_eq!(invitee_progress.get("completedSessions"), Some(&json!(1)));
+    assert_eq!(invitee_progress.get("evaluatedSessions"), Some(&json!(1)));
+    assert_eq!(invitee_progress.get("passedSessions"), Some(&json!(1)));
+    assert_eq!(
+        invitee_progress.get("averageOfficialScore"),
+        Some(&json!(80.0))
+    );
 
     let progress_for_member_response = app
         .clone()
@@ -497,6 +517,1269 @@ async fn seat_limit_is_enforced_for_invites_and_invitation_acceptance() {
         .is_some_and(|text| text.contains("SEAT_LIMIT_REACHED")));
 }
 
+#[tokio::test]
+async fn members_of_several_organizations_switch_the_current_one() {
+    let Some(pool) = test_pool().await else {
+        eprintln!("Skipping organization switching test: DATABASE_URL is not configured");
+        return;
+    };
+    configure_auth_env();
+
+    let consultant = user_id("consultant");
+    let client_a_owner = user_id("client-a-owner");
+    let client_b_owner = user_id("client-b-owner");
+    let client_a = id("client-a");
+    let client_b = id("client-b");
+    let unrelated_org = id("unrelated");
+    for user in [&consultant, &client_a_owner, &client_b_owner] {
+        insert_user(&pool, user).await;
+    }
+    insert_organization(&pool, &client_a, &client_a_owner).await;
+    insert_organization(&pool, &client_b, &client_b_owner).await;
+    insert_organization(&pool, &unrelated_org, &client_a_owner).await;
+    // Joined client A first, so client B is the default current organization.
+    insert_member(&pool, &client_a, &consultant, "member", 2).await;
+    insert_member(&pool, &client_b, &consultant, "member", 1).await;
+    insert_org_team_entitlement(&pool, &client_b).await;
+
+    let token = jwt_for_user(&consultant);
+    let app = test_app(pool.clone());
+    let entitlements = EntitlementService::new(pool.clone());
+
+    let list_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::GET,
+                "/me/organizations",
+                &token,
+                None,
+            ))
+            .await
+            .expect("list my organizations"),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(
+        list_json.get("currentOrganizationId"),
+        Some(&json!(client_b.clone()))
+    );
+    let organizations = list_json
+        .get("organizations")
+        .and_then(|v| v.as_array())
+        .expect("organizations array");
+    assert_eq!(organizations.len(), 2);
+    assert_eq!(organizations[0].get("current"), Some(&json!(true)));
+    assert_eq!(organizations[1].get("current"), Some(&json!(false)));
+    let default_plan = entitlements
+        .resolve_effective_plan(&consultant)
+        .await
+        .expect("default plan");
+    assert_eq!(
+        default_plan.organization_id.as_deref(),
+        Some(client_b.as_str())
+    );
+
+    let switch_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::PUT,
+                "/me/organizations/current",
+                &token,
+                Some(json!({ "organizationId": client_a.clone() })),
+            ))
+            .await
+            .expect("switch to client A"),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(
+        switch_json.get("currentOrganizationId"),
+        Some(&json!(client_a.clone()))
+    );
+
+    let current_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::GET,
+                "/organizations/current",
+                &token,
+                None,
+            ))
+            .await
+            .expect("current organization after switch"),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(
+        current_json
+            .get("organization")
+            .and_then(|org| org.get("id")),
+        Some(&json!(client_a.clone()))
+    );
+
+    // Client A has no plan: the consultant is on Free there, without a user
+    // entitlement that would outrank client B after switching back.
+    let client_a_plan = entitlements
+        .resolve_effective_plan(&consultant)
+        .await
+        .expect("client A plan");
+    assert!(matches!(client_a_plan.plan_code, PlanCode::Free));
+    assert_eq!(client_a_plan.organization_id, None);
+    let user_entitlements: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM entitlements WHERE scope_type = 'user' AND scope_id = $1",
+    )
+    .bind(&consultant)
+    .fetch_one(&pool)
+    .await
+    .expect("count user entitlements");
+    assert_eq!(user_entitlements, 0);
+
+    let not_member_response = app
+        .clone()
+        .oneshot(build_request(
+            Method::PUT,
+            "/me/organizations/current",
+            &token,
+            Some(json!({ "organizationId": unrelated_org.clone() })),
+        ))
+        .await
+        .expect("switch to unrelated organization");
+    assert_eq!(not_member_response.status(), StatusCode::NOT_FOUND);
+
+    app.clone()
+        .oneshot(build_request(
+            Method::PUT,
+            "/me/organizations/current",
+            &token,
+            Some(json!({ "organizationId": client_b.clone() })),
+        ))
+        .await
+        .expect("switch back to client B");
+    let client_b_plan = entitlements
+        .resolve_effective_plan(&consultant)
+        .await
+        .expect("client B plan");
+    assert!(matches!(client_b_plan.plan_code, PlanCode::Team));
+    assert_eq!(
+        client_b_plan.organization_id.as_deref(),
+        Some(client_b.as_str())
+    );
+}
+
+#[tokio::test]
+async fn managers_list_resend_and_revoke_invitations() {
+    let Some(pool) = test_pool().await else {
+        eprintln!("Skipping invitation management test: DATABASE_URL is not configured");
+        return;
+    };
+    configure_auth_env();
+
+    let owner = user_id("owner");
+    let member = user_id("member");
+    let other_owner = user_id("other-owner");
+    for user in [&owner, &member, &other_owner] {
+        insert_user(&pool, user).await;
+    }
+    let org_id = id("org");
+    let other_org_id = id("other-org");
+    insert_organization(&pool, &org_id, &owner).await;
+    insert_organization(&pool, &other_org_id, &other_owner).await;
+    insert_member(&pool, &org_id, &member, "member", 1).await;
+    // Owner and member take two of three seats.
+    insert_team_subscription(&pool, &org_id, 3).await;
+    let stale_email = format!("stale-{}@example.com", Uuid::new_v4());
+    let (stale_id, stale_token) =
+        insert_stale_invitation(&pool, &org_id, &owner, &stale_email).await;
+    let (other_org_stale_id, _) =
+        insert_stale_invitation(&pool, &other_org_id, &other_owner, "other@example.com").await;
+
+    let owner_token = jwt_for_user(&owner);
+    let app = test_app(pool.clone());
+
+    let list_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::GET,
+                "/organizations/current/invitations",
+                &owner_token,
+                None,
+            ))
+            .await
+            .expect("list invitations"),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(list_json.get("pendingInvitationCount"), Some(&json!(0)));
+    let invitations = list_json["invitations"]
+        .as_array()
+        .expect("invitations array");
+    assert_eq!(invitations.len(), 1);
+    assert_eq!(invitations[0]["id"], json!(stale_id.clone()));
+    assert_eq!(invitations[0]["status"], json!("expired"));
+    assert!(invitations[0].get("inviteTokenHash").is_none());
+
+    let member_list_response = app
+        .clone()
+        .oneshot(build_request(
+            Method::GET,
+            "/organizations/current/invitations",
+            &jwt_for_user(&member),
+            None,
+        ))
+        .await
+        .expect("member lists invitations");
+    assert_eq!(member_list_response.status(), StatusCode::FORBIDDEN);
+
+    // The new invitation takes the last seat, so the expired one can't come back.
+    let fresh_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::POST,
+                "/organizations/current/invitations",
+                &owner_token,
+                Some(json!({ "email": "fresh@example.com", "role": "member" })),
+            ))
+            .await
+            .expect("create invitation"),
+        StatusCode::CREATED,
+    )
+    .await;
+    let fresh_id = fresh_json["invitation"]["id"]
+        .as_str()
+        .expect("fresh invitation id")
+        .to_string();
+    let seat_error = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::POST,
+                &format!("/organizations/current/invitations/{stale_id}/resend"),
+                &owner_token,
+                None,
+            ))
+            .await
+            .expect("resend over the seat limit"),
+        StatusCode::UNPROCESSABLE_ENTITY,
+    )
+    .await;
+    assert!(seat_error["error"]
+        .as_str()
+        .is_some_and(|text| text.contains("SEAT_LIMIT_REACHED")));
+
+    let revoke_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::POST,
+                &format!("/organizations/current/invitations/{fresh_id}/revoke"),
+                &owner_token,
+                None,
+            ))
+            .await
+            .expect("revoke invitation"),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(revoke_json.get("status"), Some(&json!("revoked")));
+    let resend_revoked_response = app
+        .clone()
+        .oneshot(build_request(
+            Method::POST,
+            &format!("/organizations/current/invitations/{fresh_id}/resend"),
+            &owner_token,
+            None,
+        ))
+        .await
+        .expect("resend revoked invitation");
+    assert_eq!(
+        resend_revoked_response.status(),
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+
+    let resend_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::POST,
+                &format!("/organizations/current/invitations/{stale_id}/resend"),
+                &owner_token,
+                None,
+            ))
+            .await
+            .expect("resend expired invitation"),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(resend_json["invitation"]["status"], json!("pending"));
+    let new_token = resend_json["inviteToken"].as_str().expect("new token");
+    assert_ne!(new_token, stale_token);
+    let old_link_response = app
+        .clone()
+        .oneshot(build_request(
+            Method::POST,
+            &format!("/organizations/current/invitations/{stale_token}/accept"),
+            &jwt_for_user(&member),
+            None,
+        ))
+        .await
+        .expect("accept with replaced token");
+    assert_eq!(old_link_response.status(), StatusCode::NOT_FOUND);
+
+    let relisted_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::GET,
+                "/organizations/current/invitations",
+                &owner_token,
+                None,
+            ))
+            .await
+            .expect("list invitations after resend"),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(relisted_json.get("pendingInvitationCount"), Some(&json!(1)));
+    let invitations = relisted_json["invitations"]
+        .as_array()
+        .expect("invitations array");
+    assert_eq!(invitations.len(), 1);
+    assert_eq!(invitations[0]["id"], json!(stale_id.clone()));
+
+    let foreign_revoke_response = app
+        .clone()
+        .oneshot(build_request(
+            Method::POST,
+            &format!("/organizations/current/invitations/{other_org_stale_id}/revoke"),
+            &owner_token,
+            None,
+        ))
+        .await
+        .expect("revoke another organization's invitation");
+    assert_eq!(foreign_revoke_response.status(), StatusCode::NOT_FOUND);
+
+    let swept = OrganizationService::new(pool.clone())
+        .expire_stale_invitations()
+        .await
+        .expect("expire stale invitations");
+    assert!(swept >= 1);
+    let other_org_status: String =
+        sqlx::query_scalar("SELECT status FROM organization_invitations WHERE id = $1")
+            .bind(&other_org_stale_id)
+            .fetch_one(&pool)
+            .await
+            .expect("other organization invitation status");
+    assert_eq!(other_org_status, "expired");
+}
+
+#[tokio::test]
+async fn invitation_emails_carry_a_deep_link_through_the_resend_stub() {
+    let Some(pool) = test_pool().await else {
+        eprintln!("Skipping invitation email test: DATABASE_URL is not configured");
+        return;
+    };
+    configure_auth_env();
+    let stub = EmailStub::spawn("127.0.0.1:0")
+        .await
+        .expect("spawn email stub");
+    unsafe {
+        env::set_var("RESEND_API_KEY", "re_test_key");
+        env::set_var("INVITATION_EMAIL_FROM", "team@example.com");
+        env::set_var("INVITATION_EMAIL_API_BASE_URL", stub.base_url());
+        env::set_var("APP_BASE_URL", "https://app.example.com");
+    }
+
+    let owner = user_id("owner");
+    insert_user(&pool, &owner).await;
+    let owner_token = jwt_for_user(&owner);
+    let app = test_app(pool.clone());
+    response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::POST,
+                "/organizations",
+                &owner_token,
+                Some(json!({ "name": "Research & <Design>" })),
+            ))
+            .await
+            .expect("create organization"),
+        StatusCode::CREATED,
+    )
+    .await;
+
+    let invitee_email = format!("invitee-{}@example.com", Uuid::new_v4());
+    let invite_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::POST,
+                "/organizations/current/invitations",
+                &owner_token,
+                Some(
+                    json!({ "email": invitee_email.clone(), "role": "member", "locale": "en-US" }),
+                ),
+            ))
+            .await
+            .expect("create invitation"),
+        StatusCode::CREATED,
+    )
+    .await;
+    assert_eq!(invite_json["emailDelivery"]["status"], json!("sent"));
+    assert_eq!(invite_json["invitation"]["locale"], json!("en"));
+    let invite_link = invite_json["inviteLink"].as_str().expect("invite link");
+    assert!(invite_link.starts_with("https://app.example.com/team/onboarding?invite="));
+
+    let sent = emails_to(&stub, &invitee_email);
+    assert_eq!(sent.len(), 1);
+    assert_eq!(
+        sent[0].subject(),
+        "You're invited to join Research & <Design>"
+    );
+    assert!(sent[0].text().contains(invite_link));
+    assert!(sent[0]
+        .text()
+        .contains("Integration User invited you to join"));
+    assert!(sent[0]
+        .html()
+        .contains(&format!(r#"<a href="{invite_link}">"#)));
+    assert!(sent[0].html().contains("Research &amp; &lt;Design&gt;"));
+
+    // A provider outage is reported, and a resend goes out once it recovers.
+    let invitation_id = invite_json["invitation"]["id"]
+        .as_str()
+        .expect("invitation id")
+        .to_string();
+    stub.respond_with(StatusCode::INTERNAL_SERVER_ERROR);
+    let failed_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::POST,
+                &format!("/organizations/current/invitations/{invitation_id}/resend"),
+                &owner_token,
+                None,
+            ))
+            .await
+            .expect("resend during outage"),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(failed_json["emailDelivery"]["status"], json!("failed"));
+    stub.respond_with(StatusCode::OK);
+    let resent_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::POST,
+                &format!("/organizations/current/invitations/{invitation_id}/resend"),
+                &owner_token,
+                None,
+            ))
+            .await
+            .expect("resend after outage"),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(resent_json["emailDelivery"]["status"], json!("sent"));
+    let resent_link = resent_json["inviteLink"].as_str().expect("resent link");
+    let sent = emails_to(&stub, &invitee_email);
+    assert_eq!(sent.len(), 3);
+    assert!(sent[2].text().contains(resent_link));
+    assert!(sent[2].subject().starts_with("You're invited"));
+
+    let default_locale_email = format!("default-{}@example.com", Uuid::new_v4());
+    let default_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::POST,
+                "/organizations/current/invitations",
+                &owner_token,
+                Some(json!({ "email": default_locale_email.clone(), "role": "member" })),
+            ))
+            .await
+            .expect("create invitation with the default locale"),
+        StatusCode::CREATED,
+    )
+    .await;
+    assert_eq!(default_json["invitation"]["locale"], json!("ja"));
+    assert_eq!(
+        emails_to(&stub, &default_locale_email)[0].subject(),
+        "Research & <Design> への招待"
+    );
+
+    let unsupported_response = app
+        .clone()
+        .oneshot(build_request(
+            Method::POST,
+            "/organizations/current/invitations",
+            &owner_token,
+            Some(json!({ "email": "fr@example.com", "role": "member", "locale": "fr" })),
+        ))
+        .await
+        .expect("create invitation with an unsupported locale");
+    assert_eq!(
+        unsupported_response.status(),
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+
+    unsafe {
+        env::remove_var("RESEND_API_KEY");
+        env::remove_var("INVITATION_EMAIL_FROM");
+        env::remove_var("INVITATION_EMAIL_API_BASE_URL");
+        env::remove_var("APP_BASE_URL");
+    }
+}
+
+#[tokio::test]
+async fn verified_domains_offer_or_auto_join_within_the_seat_limit() {
+    let Some(pool) = test_pool().await else {
+        eprintln!("Skipping domain join test: DATABASE_URL is not configured");
+        return;
+    };
+    configure_auth_env();
+    let dns_records = Arc::new(Mutex::new(HashMap::new()));
+    let dns_url = spawn_dns_stub(dns_records.clone()).await;
+    unsafe {
+        env::set_var("DOMAIN_VERIFICATION_DNS_URL", &dns_url);
+    }
+
+    let owner = user_id("owner");
+    insert_user(&pool, &owner).await;
+    let org_id = id("org");
+    insert_organization(&pool, &org_id, &owner).await;
+    // Owner plus two more seats.
+    insert_team_subscription(&pool, &org_id, 3).await;
+    let owner_token = jwt_for_user(&owner);
+    let app = test_app(pool.clone());
+
+    let public_domain_response = app
+        .clone()
+        .oneshot(build_request(
+            Method::POST,
+            "/organizations/current/domains",
+            &owner_token,
+            Some(json!({ "domain": "gmail.com" })),
+        ))
+        .await
+        .expect("register public domain");
+    assert_eq!(
+        public_domain_response.status(),
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+
+    let auto_domain = format!("acme-{}.test", Uuid::new_v4().simple());
+    let domain_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::POST,
+                "/organizations/current/domains",
+                &owner_token,
+                Some(json!({ "domain": format!("@{}", auto_domain.to_uppercase()), "joinPolicy": "auto" })),
+            ))
+            .await
+            .expect("register domain"),
+        StatusCode::CREATED,
+    )
+    .await;
+    assert_eq!(domain_json["domain"], json!(auto_domain.clone()));
+    assert_eq!(domain_json["defaultRole"], json!("member"));
+    assert_eq!(domain_json["verifiedAt"], json!(null));
+    let domain_id = domain_json["id"].as_str().expect("domain id").to_string();
+    let record_name = domain_json["verificationRecordName"]
+        .as_str()
+        .expect("record name")
+        .to_string();
+    assert_eq!(
+        record_name,
+        format!("_pm-journey-verification.{auto_domain}")
+    );
+
+    // Unverified domains admit nobody.
+    let early_user = user_id("early");
+    let early_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::GET,
+                "/me/organizations",
+                &jwt_for(&early_user, &format!("early@{auto_domain}"), Some(true)),
+                None,
+            ))
+            .await
+            .expect("early user organizations"),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(early_json["organizations"], json!([]));
+
+    let verify_path = format!("/organizations/current/domains/{domain_id}/verify");
+    let unpublished_error = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::POST,
+                &verify_path,
+                &owner_token,
+                None,
+            ))
+            .await
+            .expect("verify before publishing"),
+        StatusCode::UNPROCESSABLE_ENTITY,
+    )
+    .await;
+    assert!(unpublished_error["error"]
+        .as_str()
+        .is_some_and(|text| text.contains("DOMAIN_VERIFICATION_FAILED")));
+
+    dns_records.lock().expect("dns records").insert(
+        record_name,
+        domain_json["verificationRecordValue"]
+            .as_str()
+            .expect("record value")
+            .to_string(),
+    );
+    let verified_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::POST,
+                &verify_path,
+                &owner_token,
+                None,
+            ))
+            .await
+            .expect("verify domain"),
+        StatusCode::OK,
+    )
+    .await;
+    assert!(verified_json["verifiedAt"].is_string());
+
+    let alice = user_id("alice");
+    let alice_token = jwt_for(&alice, &format!("alice@{auto_domain}"), Some(true));
+    let alice_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::GET,
+                "/me/organizations",
+                &alice_token,
+                None,
+            ))
+            .await
+            .expect("alice organizations"),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(
+        alice_json["organizations"][0]["organization"]["id"],
+        json!(org_id.clone())
+    );
+    assert_eq!(
+        alice_json["organizations"][0]["membership"]["role"],
+        json!("member")
+    );
+
+    // Auth0 says this address is unverified, so it neither joins nor sees an offer.
+    let unverified = user_id("unverified");
+    let unverified_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::GET,
+                "/me/organization-offers",
+                &jwt_for(&unverified, &format!("mallory@{auto_domain}"), Some(false)),
+                None,
+            ))
+            .await
+            .expect("unverified offers"),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(unverified_json["offers"], json!([]));
+    assert_eq!(active_membership_count(&pool, &org_id).await, 2);
+
+    // Neither does one whose token carries no email_verified claim at all.
+    let unclaimed_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::GET,
+                "/me/organization-offers",
+                &jwt_for(&user_id("unclaimed"), &format!("trudy@{auto_domain}"), None),
+                None,
+            ))
+            .await
+            .expect("unclaimed offers"),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(unclaimed_json["offers"], json!([]));
+    assert_eq!(active_membership_count(&pool, &org_id).await, 2);
+
+    let carol = user_id("carol");
+    let carol_token = jwt_for(&carol, &format!("carol@{auto_domain}"), Some(true));
+    response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::GET,
+                "/me/organizations",
+                &carol_token,
+                None,
+            ))
+            .await
+            .expect("carol organizations"),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(active_membership_count(&pool, &org_id).await, 3);
+
+    // Seats are full: dave is offered the organization instead, and the block
+    // is audited once however often dave signs in.
+    let dave = user_id("dave");
+    let dave_token = jwt_for(&dave, &format!("dave@{auto_domain}"), Some(true));
+    for _ in 0..2 {
+        let offers_json = response_json(
+            app.clone()
+                .oneshot(build_request(
+                    Method::GET,
+                    "/me/organization-offers",
+                    &dave_token,
+                    None,
+                ))
+                .await
+                .expect("dave offers"),
+            StatusCode::OK,
+        )
+        .await;
+        assert_eq!(offers_json["offers"][0]["seatAvailable"], json!(false));
+    }
+    assert_eq!(active_membership_count(&pool, &org_id).await, 3);
+    let accept_path = format!("/me/organization-offers/{org_id}/accept");
+    let full_error = response_json(
+        app.clone()
+            .oneshot(build_request(Method::POST, &accept_path, &dave_token, None))
+            .await
+            .expect("dave accepts a full organization"),
+        StatusCode::UNPROCESSABLE_ENTITY,
+    )
+    .await;
+    assert!(full_error["error"]
+        .as_str()
+        .is_some_and(|text| text.contains("SEAT_LIMIT_REACHED")));
+
+    // Removing alice frees a seat for dave, but does not let alice back in.
+    let members_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::GET,
+                "/organizations/current/members",
+                &owner_token,
+                None,
+            ))
+            .await
+            .expect("list members"),
+        StatusCode::OK,
+    )
+    .await;
+    let alice_member_id = members_json["members"]
+        .as_array()
+        .expect("members")
+        .iter()
+        .find(|member| member["userId"] == json!(alice.clone()))
+        .and_then(|member| member["id"].as_str())
+        .expect("alice member id")
+        .to_string();
+    let delete_response = app
+        .clone()
+        .oneshot(build_request(
+            Method::DELETE,
+            &format!("/organizations/current/members/{alice_member_id}"),
+            &owner_token,
+            None,
+        ))
+        .await
+        .expect("remove alice");
+    assert_eq!(delete_response.status(), StatusCode::NO_CONTENT);
+    for token in [&alice_token, &dave_token] {
+        app.clone()
+            .oneshot(build_request(Method::GET, "/me/organizations", token, None))
+            .await
+            .expect("sign in after removal");
+    }
+    let dave_role: Option<String> = sqlx::query_scalar(
+        "SELECT role FROM organization_members WHERE organization_id = $1 AND user_id = $2",
+    )
+    .bind(&org_id)
+    .bind(&dave)
+    .fetch_optional(&pool)
+    .await
+    .expect("dave membership");
+    assert_eq!(dave_role.as_deref(), Some("member"));
+    let alice_offers = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::GET,
+                "/me/organization-offers",
+                &alice_token,
+                None,
+            ))
+            .await
+            .expect("alice offers after removal"),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(alice_offers["offers"], json!([]));
+    assert_eq!(active_membership_count(&pool, &org_id).await, 3);
+
+    // A member who joined by invitation and was then removed is not
+    // re-joined through the domain either.
+    let frank = user_id("frank");
+    insert_user(&pool, &frank).await;
+    insert_member(&pool, &org_id, &frank, "member", 1).await;
+    let frank_member_id: String = sqlx::query_scalar(
+        "SELECT id FROM organization_members WHERE organization_id = $1 AND user_id = $2",
+    )
+    .bind(&org_id)
+    .bind(&frank)
+    .fetch_one(&pool)
+    .await
+    .expect("frank member id");
+    let remove_frank_response = app
+        .clone()
+        .oneshot(build_request(
+            Method::DELETE,
+            &format!("/organizations/current/members/{frank_member_id}"),
+            &owner_token,
+            None,
+        ))
+        .await
+        .expect("remove frank");
+    assert_eq!(remove_frank_response.status(), StatusCode::NO_CONTENT);
+    let frank_offers = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::GET,
+                "/me/organization-offers",
+                &jwt_for(&frank, &format!("frank@{auto_domain}"), Some(true)),
+                None,
+            ))
+            .await
+            .expect("frank signs in after removal"),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(frank_offers["offers"], json!([]));
+    assert_eq!(active_membership_count(&pool, &org_id).await, 3);
+
+    // An offer-policy domain waits for the user to accept.
+    sqlx::query("UPDATE subscriptions SET seat_quantity = 4 WHERE organization_id = $1")
+        .bind(&org_id)
+        .execute(&pool)
+        .await
+        .expect("add a seat");
+    let offer_domain = format!("beta-{}.test", Uuid::new_v4().simple());
+    let offer_domain_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::POST,
+                "/organizations/current/domains",
+                &owner_token,
+                Some(json!({ "domain": offer_domain.clone(), "defaultRole": "reviewer" })),
+            ))
+            .await
+            .expect("register offer domain"),
+        StatusCode::CREATED,
+    )
+    .await;
+    assert_eq!(offer_domain_json["joinPolicy"], json!("offer"));
+    dns_records.lock().expect("dns records").insert(
+        offer_domain_json["verificationRecordName"]
+            .as_str()
+            .expect("record name")
+            .to_string(),
+        offer_domain_json["verificationRecordValue"]
+            .as_str()
+            .expect("record value")
+            .to_string(),
+    );
+    response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::POST,
+                &format!(
+                    "/organizations/current/domains/{}/verify",
+                    offer_domain_json["id"].as_str().expect("offer domain id")
+                ),
+                &owner_token,
+                None,
+            ))
+            .await
+            .expect("verify offer domain"),
+        StatusCode::OK,
+    )
+    .await;
+
+    let erin = user_id("erin");
+    let erin_token = jwt_for(&erin, &format!("erin@{offer_domain}"), Some(true));
+    let erin_offers = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::GET,
+                "/me/organization-offers",
+                &erin_token,
+                None,
+            ))
+            .await
+            .expect("erin offers"),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(
+        erin_offers["offers"][0]["organization"]["id"],
+        json!(org_id.clone())
+    );
+    assert_eq!(erin_offers["offers"][0]["defaultRole"], json!("reviewer"));
+    assert_eq!(erin_offers["offers"][0]["seatAvailable"], json!(true));
+    assert_eq!(active_membership_count(&pool, &org_id).await, 3);
+    let accepted_json = response_json(
+        app.clone()
+            .oneshot(build_request(Method::POST, &accept_path, &erin_token, None))
+            .await
+            .expect("erin accepts the offer"),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(accepted_json["membership"]["role"], json!("reviewer"));
+
+    let audit_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::GET,
+                "/organizations/current/audit-events",
+                &owner_token,
+                None,
+            ))
+            .await
+            .expect("audit events"),
+        StatusCode::OK,
+    )
+    .await;
+    let actions: Vec<(String, Option<String>)> = audit_json["events"]
+        .as_array()
+        .expect("events")
+        .iter()
+        .map(|event| {
+            (
+                event["action"].as_str().unwrap_or_default().to_string(),
+                event["subjectUserId"].as_str().map(str::to_string),
+            )
+        })
+        .collect();
+    let count = |action: &str, subject: Option<&str>| {
+        actions
+            .iter()
+            .filter(|(a, s)| a == action && s.as_deref() == subject)
+            .count()
+    };
+    assert_eq!(count("domain.registered", None), 2);
+    assert_eq!(count("domain.verified", None), 2);
+    assert_eq!(count("member.domain_joined", Some(&alice)), 1);
+    assert_eq!(count("member.domain_joined", Some(&carol)), 1);
+    assert_eq!(count("member.domain_joined", Some(&dave)), 1);
+    assert_eq!(count("member.domain_joined", Some(&erin)), 1);
+    assert_eq!(count("member.domain_join_blocked", Some(&dave)), 1);
+
+    unsafe {
+        env::remove_var("DOMAIN_VERIFICATION_DNS_URL");
+    }
+}
+
+#[tokio::test]
+async fn ownership_transfers_once_the_target_accepts_and_moves_the_stripe_customer() {
+    let Some(pool) = test_pool().await else {
+        eprintln!("Skipping ownership transfer test: DATABASE_URL is not configured");
+        return;
+    };
+    configure_auth_env();
+    let stripe = Arc::new(Mutex::new(StripeCustomerStub {
+        status: StatusCode::OK,
+        requests: Vec::new(),
+    }));
+    let stripe_url = spawn_stripe_customer_stub(stripe.clone()).await;
+    unsafe {
+        env::set_var("BILLING_PROVIDER", "stripe");
+        env::set_var("STRIPE_SECRET_KEY", "sk_test_transfer");
+        env::set_var("STRIPE_API_BASE_URL", &stripe_url);
+    }
+
+    let owner = user_id("owner");
+    let admin = user_id("admin");
+    let bob = user_id("bob");
+    for user in [&owner, &admin, &bob] {
+        insert_user(&pool, user).await;
+    }
+    let org_id = id("org");
+    insert_organization(&pool, &org_id, &owner).await;
+    insert_member(&pool, &org_id, &admin, "admin", 0).await;
+    insert_member(&pool, &org_id, &bob, "member", 0).await;
+    let customer_id = format!("cus_{}", Uuid::new_v4().simple());
+    sqlx::query(
+        r#"
+        INSERT INTO billing_customers (id, organization_id, provider, provider_customer_id)
+        VALUES ($1, $2, 'stripe', $3)
+        "#,
+    )
+    .bind(id("billing-customer"))
+    .bind(&org_id)
+    .bind(&customer_id)
+    .execute(&pool)
+    .await
+    .expect("insert org billing customer");
+
+    let owner_token = jwt_for_user(&owner);
+    let admin_token = jwt_for_user(&admin);
+    let bob_token = jwt_for_user(&bob);
+    let app = test_app(pool.clone());
+    let transfer_path = "/organizations/current/transfer-ownership";
+    let accept_path = "/organizations/current/transfer-ownership/accept";
+
+    let members_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::GET,
+                "/organizations/current/members",
+                &owner_token,
+                None,
+            ))
+            .await
+            .expect("list members"),
+        StatusCode::OK,
+    )
+    .await;
+    let member_id_of = |user: &str| {
+        members_json["members"]
+            .as_array()
+            .expect("members")
+            .iter()
+            .find(|member| member["userId"] == json!(user))
+            .and_then(|member| member["id"].as_str())
+            .expect("member id")
+            .to_string()
+    };
+    let owner_member_id = member_id_of(&owner);
+    let bob_member_id = member_id_of(&bob);
+
+    // The owner role is no longer assignable through a member update.
+    let promote_response = app
+        .clone()
+        .oneshot(build_request(
+            Method::PATCH,
+            &format!("/organizations/current/members/{bob_member_id}"),
+            &owner_token,
+            Some(json!({ "role": "owner" })),
+        ))
+        .await
+        .expect("promote bob directly");
+    assert_eq!(promote_response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let admin_response = app
+        .clone()
+        .oneshot(build_request(
+            Method::POST,
+            transfer_path,
+            &admin_token,
+            Some(json!({ "memberId": bob_member_id.clone() })),
+        ))
+        .await
+        .expect("admin starts a transfer");
+    assert_eq!(admin_response.status(), StatusCode::FORBIDDEN);
+    let self_response = app
+        .clone()
+        .oneshot(build_request(
+            Method::POST,
+            transfer_path,
+            &owner_token,
+            Some(json!({ "memberId": owner_member_id })),
+        ))
+        .await
+        .expect("owner transfers to self");
+    assert_eq!(self_response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let start_transfer = || {
+        build_request(
+            Method::POST,
+            transfer_path,
+            &owner_token,
+            Some(json!({ "memberId": bob_member_id.clone() })),
+        )
+    };
+    let transfer_json = response_json(
+        app.clone()
+            .oneshot(start_transfer())
+            .await
+            .expect("start transfer"),
+        StatusCode::CREATED,
+    )
+    .await;
+    assert_eq!(transfer_json["status"], json!("pending"));
+    assert_eq!(transfer_json["toUserId"], json!(bob.clone()));
+    let duplicate_error = response_json(
+        app.clone()
+            .oneshot(start_transfer())
+            .await
+            .expect("start a second transfer"),
+        StatusCode::UNPROCESSABLE_ENTITY,
+    )
+    .await;
+    assert!(duplicate_error["error"]
+        .as_str()
+        .is_some_and(|text| text.contains("OWNERSHIP_TRANSFER_PENDING")));
+
+    for token in [&admin_token, &bob_token] {
+        let pending_json = response_json(
+            app.clone()
+                .oneshot(build_request(Method::GET, transfer_path, token, None))
+                .await
+                .expect("view pending transfer"),
+            StatusCode::OK,
+        )
+        .await;
+        assert_eq!(pending_json["transfer"]["id"], transfer_json["id"]);
+    }
+
+    // Bob declines; the owner asks again.
+    let decline_response = app
+        .clone()
+        .oneshot(build_request(
+            Method::DELETE,
+            transfer_path,
+            &bob_token,
+            None,
+        ))
+        .await
+        .expect("bob declines");
+    assert_eq!(decline_response.status(), StatusCode::NO_CONTENT);
+    let declined_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::GET,
+                transfer_path,
+                &owner_token,
+                None,
+            ))
+            .await
+            .expect("view after decline"),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(declined_json["transfer"], json!(null));
+    response_json(
+        app.clone()
+            .oneshot(start_transfer())
+            .await
+            .expect("restart transfer"),
+        StatusCode::CREATED,
+    )
+    .await;
+
+    let owner_accept_response = app
+        .clone()
+        .oneshot(build_request(Method::POST, accept_path, &owner_token, None))
+        .await
+        .expect("owner accepts own transfer");
+    assert_eq!(owner_accept_response.status(), StatusCode::NOT_FOUND);
+
+    let accepted_json = response_json(
+        app.clone()
+            .oneshot(build_request(Method::POST, accept_path, &bob_token, None))
+            .await
+            .expect("bob accepts"),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(accepted_json["membership"]["role"], json!("owner"));
+    assert_eq!(accepted_json["billingCustomerUpdated"], json!(true));
+    assert_eq!(
+        accepted_json["organization"]["createdByUserId"],
+        json!(bob.clone())
+    );
+    assert_eq!(member_role(&pool, &org_id, &owner).await, "admin");
+    assert_eq!(organization_owner(&pool, &org_id).await, bob);
+
+    let requests = stripe.lock().expect("stripe stub").requests.clone();
+    assert_eq!(requests.len(), 1);
+    let (path, form) = requests.last().expect("customer update");
+    assert_eq!(path, &format!("/v1/customers/{customer_id}"));
+    assert_eq!(form.get("email"), Some(&user_email(&bob)));
+    assert_eq!(form.get("metadata[owner_user_id]"), Some(&bob));
+
+    // Bob hands ownership back during a Stripe outage: the roles still move
+    // and both the response and the audit event report that the customer
+    // was not updated.
+    response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::POST,
+                transfer_path,
+                &bob_token,
+                Some(json!({ "memberId": owner_member_id.clone() })),
+            ))
+            .await
+            .expect("bob starts a transfer back"),
+        StatusCode::CREATED,
+    )
+    .await;
+    stripe.lock().expect("stripe stub").status = StatusCode::INTERNAL_SERVER_ERROR;
+    let outage_response = app
+        .clone()
+        .oneshot(build_request(Method::POST, accept_path, &owner_token, None))
+        .await
+        .expect("accept during outage");
+    let outage_json = response_json(outage_response, StatusCode::OK).await;
+    assert_eq!(outage_json["membership"]["role"], json!("owner"));
+    assert_eq!(outage_json["billingCustomerUpdated"], json!(false));
+    assert_eq!(member_role(&pool, &org_id, &owner).await, "owner");
+    assert_eq!(member_role(&pool, &org_id, &bob).await, "admin");
+    assert_eq!(organization_owner(&pool, &org_id).await, owner);
+    assert_eq!(stripe.lock().expect("stripe stub").requests.len(), 2);
+    stripe.lock().expect("stripe stub").status = StatusCode::OK;
+
+    let audit_json = response_json(
+        app.clone()
+            .oneshot(build_request(
+                Method::GET,
+                "/organizations/current/audit-events",
+                &bob_token,
+                None,
+            ))
+            .await
+            .expect("audit events"),
+        StatusCode::OK,
+    )
+    .await;
+    // Events within the same second have no defined order.
+    let mut actions: Vec<&str> = audit_json["events"]
+        .as_array()
+        .expect("events")
+        .iter()
+        .filter_map(|event| event["action"].as_str())
+        .collect();
+    actions.sort_unstable();
+    assert_eq!(
+        actions,
+        vec![
+            "ownership.transfer_declined",
+            "ownership.transfer_requested",
+            "ownership.transfer_requested",
+            "ownership.transfer_requested",
+            "ownership.transferred",
+            "ownership.transferred",
+        ]
+    );
+    let mut billing_updates: Vec<bool> = audit_json["events"]
+        .as_array()
+        .expect("events")
+        .iter()
+        .filter(|event| event["action"] == json!("ownership.transferred"))
+        .filter_map(|event| event["detail"]["billingCustomerUpdated"].as_bool())
+        .collect();
+    billing_updates.sort_unstable();
+    assert_eq!(billing_updates, vec![false, true]);
+
+    unsafe {
+        env::remove_var("BILLING_PROVIDER");
+        env::remove_var("STRIPE_SECRET_KEY");
+        env::remove_var("STRIPE_API_BASE_URL");
+    }
+}
+
 #[allow(unused_unsafe)]
 fn configure_auth_env() {
     unsafe {
@@ -514,12 +1797,17 @@ fn test_app(pool: PgPool) -> axum::Router {
 }
 
 fn jwt_for_user(user_id: &str) -> String {
+    jwt_for(user_id, &user_email(user_id), None)
+}
+
+fn jwt_for(user_id: &str, email: &str, email_verified: Option<bool>) -> String {
     let mut header = Header::new(Algorithm::RS256);
     header.kid = Some(TEST_KID.to_string());
 
     let claims = TestClaims {
         sub: user_id.to_string(),
-        email: user_email(user_id),
+        email: email.to_string(),
+        email_verified,
         name: "Integration User".to_string(),
         picture: "https://example.com/picture.png".to_string(),
         iss: format!("https://{}/", TEST_AUTH0_DOMAIN),
@@ -594,6 +1882,78 @@ async fn insert_user(pool: &PgPool, user_id: &str) {
     .expect("insert user");
 }
 
+async fn response_json(
+    response: axum::response::Response,
+    expected_status: StatusCode,
+) -> serde_json::Value {
+    assert_eq!(response.status(), expected_status);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("response body");
+    serde_json::from_slice(&body).expect("response json")
+}
+
+async fn insert_organization(pool: &PgPool, org_id: &str, owner_user_id: &str) {
+    sqlx::query(
+        r#"
+        INSERT INTO organizations (id, name, created_by_user_id)
+        VALUES ($1, $2, $3)
+        "#,
+    )
+    .bind(org_id)
+    .bind(format!("Org {org_id}"))
+    .bind(owner_user_id)
+    .execute(pool)
+    .await
+    .expect("insert organization");
+    insert_member(pool, org_id, owner_user_id, "owner", 0).await;
+}
+
+async fn insert_member(
+    pool: &PgPool,
+    org_id: &str,
+    user_id: &str,
+    role: &str,
+    joined_days_ago: i32,
+) {
+    sqlx::query(
+        r#"
+        INSERT INTO organization_members (
+            id, organization_id, user_id, role, status, joined_at, created_at
+        )
+        VALUES (
+            $1, $2, $3, $4, 'active',
+            NOW() - make_interval(days => $5),
+            NOW() - make_interval(days => $5)
+        )
+        "#,
+    )
+    .bind(id("member"))
+    .bind(org_id)
+    .bind(user_id)
+    .bind(role)
+    .bind(joined_days_ago)
+    .execute(pool)
+    .await
+    .expect("insert organization member");
+}
+
+async fn insert_org_team_entitlement(pool: &PgPool, org_id: &str) {
+    sqlx::query(
+        r#"
+        INSERT INTO entitlements (
+            id, scope_type, scope_id, plan_code, status, valid_from, valid_until, source_subscription_id
+        )
+        VALUES ($1, 'organization', $2, 'TEAM', 'active', NOW(), NULL, NULL)
+        "#,
+    )
+    .bind(id("entitlement"))
+    .bind(org_id)
+    .execute(pool)
+    .await
+    .expect("insert org team entitlement");
+}
+
 async fn insert_team_subscription(pool: &PgPool, org_id: &str, seats: i32) {
     sqlx::query(
         r#"
@@ -630,7 +1990,7 @@ async fn insert_pending_invitation(
     .bind(id("invitation"))
     .bind(org_id)
     .bind(email)
-    .bind(token)
+    .bind(token_hash(token))
     .bind(creator_user_id)
     .execute(pool)
     .await
@@ -668,3 +2028,168 @@ async fn insert_session(
     .await
     .expect("insert session");
 }
+
+async fn insert_evaluation(
+    pool: &PgPool,
+    session_id: &str,
+    attempt: i32,
+    overall_score: f32,
+    is_official: bool,
+) {
+    sqlx::query(
+        r#"
+        INSERT INTO evaluations (
+            session_id, attempt, is_official, overall_score, passing, categories
+        )
+        VALUES ($1, $2, $3, $4, $5, '[]'::jsonb)
+        "#,
+    )
+    .bind(session_id)
+    .bind(attempt)
+    .bind(is_official)
+    .bind(overall_score)
+    .bind(overall_score >= 70.0)
+    .execute(pool)
+    .await
+    .expect("insert evaluation");
+}
+
+async fn insert_stale_invitation(
+    pool: &PgPool,
+    org_id: &str,
+    creator_user_id: &str,
+    email: &str,
+) -> (String, String) {
+    let invitation_id = id("invitation");
+    let token = id("stale-token");
+    sqlx::query(
+        r#"
+        INSERT INTO organization_invitations (
+            id, organization_id, email, role, invite_token_hash, expires_at, status, created_by_user_id, created_at
+        )
+        VALUES ($1, $2, $3, 'member', $4, NOW() - INTERVAL '1 day', 'pending', $5, NOW() - INTERVAL '8 days')
+        "#,
+    )
+    .bind(&invitation_id)
+    .bind(org_id)
+    .bind(email)
+    .bind(token_hash(&token))
+    .bind(creator_user_id)
+    .execute(pool)
+    .await
+    .expect("insert stale invitation");
+
+    (invitation_id, token)
+}
+
+/// Invitations store the SHA-256 hex digest of their accept token.
+fn token_hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn emails_to(stub: &EmailStub, email: &str) -> Vec<StubEmail> {
+    stub.emails()
+        .into_iter()
+        .filter(|sent| sent.body["to"] == json!([email]))
+        .collect()
+}
+
+async fn active_membership_count(pool: &PgPool, org_id: &str) -> i64 {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM organization_members WHERE organization_id = $1 AND status = 'active'",
+    )
+    .bind(org_id)
+    .fetch_one(pool)
+    .await
+    .expect("count active members")
+}
+
+/// DNS-over-HTTPS JSON resolver answering TXT queries from `records`.
+async fn spawn_dns_stub(records: Arc<Mutex<HashMap<String, String>>>) -> String {
+    async fn resolve(
+        State(records): State<Arc<Mutex<HashMap<String, String>>>>,
+        Query(query): Query<HashMap<String, String>>,
+    ) -> Json<serde_json::Value> {
+        let name = query.get("name").cloned().unwrap_or_default();
+        let answer = records
+            .lock()
+            .expect("dns records")
+            .get(&name)
+            .map(|value| vec![json!({ "name": name, "type": 16, "data": format!("\"{value}\"") })])
+            .unwrap_or_default();
+        Json(json!({ "Status": 0, "Answer": answer }))
+    }
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind dns stub");
+    let addr = listener.local_addr().expect("dns stub address");
+    let app = Router::new()
+        .route("/dns-query", get(resolve))
+        .with_state(records);
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, app).await;
+    });
+    format!("http://{addr}/dns-query")
+}
+
+async fn member_role(pool: &PgPool, org_id: &str, user_id: &str) -> String {
+    sqlx::query_scalar(
+        "SELECT role FROM organization_members WHERE organization_id = $1 AND user_id = $2",
+    )
+    .bind(org_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+    .expect("member role")
+}
+
+async fn organization_owner(pool: &PgPool, org_id: &str) -> String {
+    sqlx::query_scalar("SELECT created_by_user_id FROM organizations WHERE id = $1")
+        .bind(org_id)
+        .fetch_one(pool)
+        .await
+        .expect("organization owner")
+}
+
+struct StripeCustomerStub {
+    status: StatusCode,
+    /// Path and form fields of each customer update, in arrival order.
+    requests: Vec<(String, HashMap<String, String>)>,
+}
+
+/// Answers `POST /v1/customers/:id` with `status`, recording each request.
+async fn spawn_stripe_customer_stub(stub: Arc<Mutex<StripeCustomerStub>>) -> String {
+    async fn update_customer(
+        State(stub): State<Arc<Mutex<StripeCustomerStub>>>,
+        Path(customer_id): Path<String>,
+        Form(form): Form<HashMap<String, String>>,
+    ) -> (StatusCode, Json<serde_json::Value>) {
+        let mut stub = stub.lock().expect("stripe stub");
+        stub.requests
+            .push((format!("/v1/customers/{customer_id}"), form));
+        if stub.status.is_success() {
+            (
+                stub.status,
+                Json(json!({ "id": customer_id, "object": "customer" })),
+            )
+        } else {
+            (
+                stub.status,
+                Json(json!({ "error": { "message": "scripted Stripe outage" } })),
+            )
+        }
+    }
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind stripe stub");
+    let addr = listener.local_addr().expect("stripe stub address");
+    let app = Router::new()
+        .route("/v1/customers/:customer_id", post(update_customer))
+        .with_state(stub);
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, app).await;
+    });
+    format!("http://{addr}")
+}
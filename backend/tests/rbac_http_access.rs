@@ -4,7 +4,10 @@ use axum::{
     body::{to_bytes, Body},
     http::{Method, Request, StatusCode},
 };
-use backend::{api, middleware::auth::JwksKeys, state::state_with_pool};
+use backend::{
+    api, features::scenarios::services::ScenarioService, middleware::auth::JwksKeys,
+    state::state_with_pool,
+};
 use chrono::{Duration, Utc};
 use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header};
 use serde::Serialize;
@@ -212,6 +215,18 @@ async fn http_role_matrix_for_sessions_messages_comments_outputs() {
         .expect("request should succeed");
     assert_eq!(response.status(), StatusCode::FORBIDDEN);
 
+    let response = app
+        .clone()
+        .oneshot(build_request(
+            Method::POST,
+            &format!("/sessions/{session_id}/messages/stream"),
+            &admin_token,
+            Some(json!({"role":"agent","content":"admin streamed update"})),
+        ))
+        .await
+        .expect("request should succeed");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
     let response = app
         .clone()
         .oneshot(build_request(
@@ -394,6 +409,18 @@ async fn http_all_registered_api_endpoints_surface() {
         .expect("get scenario request should succeed");
     assert_eq!(get_scenario_response.status(), StatusCode::OK);
 
+    let admin_scenarios_response = app
+        .clone()
+        .oneshot(build_request(
+            Method::GET,
+            "/admin/scenarios",
+            &owner_token,
+            None,
+        ))
+        .await
+        .expect("admin scenarios request should succeed");
+    assert_eq!(admin_scenarios_response.status(), StatusCode::FORBIDDEN);
+
     let create_session_response = app
         .clone()
         .oneshot(build_request(
@@ -534,6 +561,31 @@ async fn http_all_registered_api_endpoints_surface() {
         .expect("post message request should succeed");
     assert_eq!(post_message_response.status(), StatusCode::OK);
 
+    let stream_message_response = app
+        .clone()
+        .oneshot(build_request(
+            Method::POST,
+            &format!("/sessions/{session_id}/messages/stream"),
+            &owner_token,
+            Some(json!({"role":"agent","content":"streamed status update"})),
+        ))
+        .await
+        .expect("stream message request should succeed");
+    assert_eq!(stream_message_response.status(), StatusCode::OK);
+    assert_eq!(
+        stream_message_response
+            .headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok()),
+        Some("text/event-stream")
+    );
+    let stream_body = to_bytes(stream_message_response.into_body(), usize::MAX)
+        .await
+        .expect("stream message body");
+    let stream_text = String::from_utf8(stream_body.to_vec()).expect("stream body utf8");
+    assert!(stream_text.contains("event: reply"));
+    assert!(stream_text.contains("streamed status update"));
+
     let list_comments_response = app
         .clone()
         .oneshot(build_request(
@@ -997,6 +1049,10 @@ async fn test_pool() -> Option<PgPool> {
         .await
         .ok()?;
     MIGRATOR.run(&pool).await.ok()?;
+    ScenarioService::new(pool.clone())
+        .seed_builtin_scenarios()
+        .await
+        .ok()?;
     Some(pool)
 }
 